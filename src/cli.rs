@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use edf_rs::file::EDFFile;
+use serde_json::{Value, json};
+
+use crate::database::{self, types::ChannelAlias};
+use crate::export::{self, ExportFormat};
+use crate::scoring::stages::StageVocabulary;
+use crate::signals::channel::ChannelType;
+use crate::statistics::sleep_stats::SleepStatistics;
+use crate::storage::preset::ProjectPreset;
+use crate::storage::{project_initializer, spectrogram_cache};
+use crate::{CurrentProject, ProjectConfiguration, ProjectSignals};
+
+// Options which do not take a value
+const FLAGS: [&str; 4] = ["reference", "no-filter", "no-clip", "no-align"];
+
+const USAGE: &str = "\
+Usage: NoctiGScorer [<command> [<arguments>] | <files>...]
+
+Starts the user interface in case no command is given. Any other arguments are
+files to open in the user interface (a project file or one or more EDF files).
+
+Commands:
+  create <files>... --name <name> --location <directory> [--preset <name|file>] [--vocabulary <name>] [--reference] [--no-filter] [--no-clip] [--no-align]
+      Creates a new project from the given EDF files, optionally using a project preset
+  info <files>...
+      Prints the header information of the given EDF files
+  stats <project> [--scorer <name>]
+      Prints the sleep statistics of the scores within the analysis period
+  export <project> --format <format> [--scorer <name>] [--output <file>]
+      Exports the scores within the analysis period in one of the formats: edf, csv, tsv, yasa-txt, yasa-npy, bids
+  spectrogram <project> [--source <index>] [--signal <index>]
+      Precomputes the spectrograms of all signals (or the selected ones) of the project
+  aliases [list | add <label> --name <name> --type <type> | remove <label>]
+      Lists or edits the channel label aliases applied when importing EDF files.
+      Types: EEG, EOG, EMG, ECG, Resp, SpO2, Other
+  help
+      Prints this help";
+
+/// Positional arguments and options (e.g. `--name <value>`) following a command
+struct Arguments {
+    positional: Vec<String>,
+    options: HashMap<String, Option<String>>
+}
+
+impl Arguments {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+
+            let value = if FLAGS.contains(&name) {
+                None
+            } else {
+                Some(args.next().ok_or(format!("Missing value of option --{}", name))?.clone())
+            };
+            options.insert(name.to_string(), value);
+        }
+
+        Ok(Self { positional, options })
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(|v| v.as_deref())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, Box<dyn Error>> {
+        self.value(name).ok_or(format!("Missing option --{}", name).into())
+    }
+
+    fn index(&self, name: &str) -> Result<Option<usize>, Box<dyn Error>> {
+        self.value(name).map(|v| v.parse::<usize>().map_err(|_| format!("Invalid index for --{}: {}", name, v).into())).transpose()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn project(&self) -> Result<CurrentProject, Box<dyn Error>> {
+        let path = self.positional.first().ok_or("Missing project file")?;
+        let mut project = CurrentProject::load(path)?;
+        if let Some(scorer) = self.value("scorer") {
+            if !project.project.scorer_sets().iter().any(|s| s.name == scorer) {
+                return Err(format!("Unknown scorer '{}'", scorer).into());
+            }
+            project.switch_scorer(scorer);
+        }
+
+        Ok(project)
+    }
+}
+
+// All commands which can be run without the user interface
+const COMMANDS: [&str; 9] = ["create", "info", "stats", "export", "spectrogram", "aliases", "help", "--help", "-h"];
+
+/// Checks whether the given argument is a command which is run without the user interface
+pub fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg)
+}
+
+/// Runs the given command without the user interface and returns the exit code of the process
+pub fn run(args: &[String]) -> i32 {
+    let (command, args) = args.split_first().map(|(c, a)| (c.as_str(), a)).unwrap_or(("help", &[]));
+    let result = Arguments::parse(args).and_then(|args| match command {
+        "create" => create(&args),
+        "info" => info(&args),
+        "stats" => stats(&args),
+        "export" => export(&args),
+        "spectrogram" => spectrogram(&args),
+        "aliases" => aliases(&args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("Unknown command '{}'\n\n{}", command, USAGE).into())
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+fn print_json(value: Value) -> Result<(), Box<dyn Error>> {
+    // Writing explicitly prevents panicking when the output is closed early (e.g. piped into `head`)
+    writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&value)?)?;
+    Ok(())
+}
+
+fn create(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    if args.positional.is_empty() {
+        return Err("At least one EDF file is required".into());
+    }
+
+    let vocabulary = match args.value("vocabulary") {
+        Some(name) => Some(StageVocabulary::presets().into_iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+            .ok_or(format!("Unknown stage vocabulary '{}'", name))?),
+        None => None
+    };
+
+    // Presets are either given by their name within the settings or as exported file
+    let preset = match args.value("preset") {
+        Some(path) if Path::new(path).is_file() => Some(ProjectPreset::load(Path::new(path))?),
+        Some(name) => Some(database::get_project_presets().map_err(|e| format!("{:?}", e))?.into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or(format!("Unknown preset '{}'", name))?),
+        None => None
+    };
+
+    let data = args.positional.iter().map(|path| {
+        let mut signals = ProjectSignals::from_path(path.clone());
+        signals.is_reference = args.flag("reference");
+        signals
+    }).collect();
+
+    let mut config = ProjectConfiguration {
+        name: args.required("name")?.to_string(),
+        path: args.required("location")?.to_string(),
+        data,
+        ..Default::default()
+    };
+
+    // Options given on the command line take precedence over the preset
+    if let Some(preset) = preset {
+        config.apply_preset(preset);
+    }
+    if let Some(vocabulary) = vocabulary {
+        config.stage_vocabulary = vocabulary;
+    }
+    config.filter_signal &= !args.flag("no-filter");
+    config.clip_signal &= !args.flag("no-clip");
+    config.auto_align_signals &= !args.flag("no-align");
+
+    let project_file = project_initializer::create_project(config)?;
+
+    print_json(json!({ "project": project_file }))
+}
+
+fn info(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    if args.positional.is_empty() {
+        return Err("At least one EDF file is required".into());
+    }
+
+    let files = args.positional.iter().map(|path| {
+        let header = EDFFile::open(path)?.header;
+        let signals = header.get_signals().iter().enumerate().map(|(i, signal)| json!({
+            "label": signal.label,
+            "transducer": signal.transducer,
+            "physical_dimension": signal.physical_dimension,
+            "physical_minimum": signal.physical_minimum,
+            "physical_maximum": signal.physical_maximum,
+            "prefilter": signal.prefilter,
+            "sample_frequency": header.get_signal_sample_frequency(i),
+            "is_annotation": signal.is_annotation()
+        })).collect::<Vec<_>>();
+
+        let record_count = header.get_record_count();
+        Ok(json!({
+            "path": path,
+            "start": header.start_date().and_time(header.get_start_time()).format("%Y-%m-%dT%H:%M:%S").to_string(),
+            "record_count": record_count,
+            "record_duration": header.get_record_duration(),
+            "duration": record_count.map(|c| c as f64 * header.get_record_duration()),
+            "is_continuous": header.is_continuous(),
+            "signals": signals
+        }))
+    }).collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    print_json(Value::Array(files))
+}
+
+fn stats(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    let project = args.project()?;
+    let scorings = project.scorings.as_ref().ok_or("Project has no scores")?;
+    let period = project.analysis_period();
+    let statistics = SleepStatistics::compute(scorings, &project.project.stage_vocabulary, &period);
+
+    // Lights-off / lights-on as absolute time if the start of the recording is known
+    let start = project.start_time().map(|t| t.and_utc().timestamp_millis());
+    let absolute = |value: Option<u64>| start.zip(value)
+        .and_then(|(start, value)| DateTime::from_timestamp_millis(start + value as i64))
+        .map(|t| t.naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string());
+
+    print_json(json!({
+        "project": project.project.name,
+        "scorer": project.project.active_scorer_name(),
+        "vocabulary": project.project.stage_vocabulary.name,
+        "epoch_count": project.epoch_count(),
+        "scored_epochs": scorings.values.len(),
+        "lights_off": absolute(project.project.lights_off),
+        "lights_on": absolute(project.project.lights_on),
+        "statistics": statistics
+    }))
+}
+
+fn export(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    let project = args.project()?;
+    let format_id = args.required("format")?;
+    let format = ExportFormat::ALL.into_iter()
+        .find(|f| f.id() == format_id)
+        .ok_or(format!("Unknown export format '{}'", format_id))?;
+    let period = project.analysis_period();
+
+    let path = match args.value("output") {
+        Some(output) => {
+            export::export_to(&project, format, &period, Path::new(output))?;
+            PathBuf::from(output)
+        }
+        None => export::export(&project, format, &period)?
+    };
+
+    print_json(json!({ "format": format_id, "path": path }))
+}
+
+fn spectrogram(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    let project = args.project()?;
+    let source_index = args.index("source")?;
+    let signal_index = args.index("signal")?;
+
+    let mut written = Vec::new();
+    for (source, reader) in project.project.signals.iter().zip(&project.readers).enumerate().filter(|(i, _)| source_index.is_none_or(|s| s == *i)).map(|(_, s)| s) {
+        let signals = reader.get_signals().iter().enumerate()
+            .filter(|(i, signal)| !signal.is_annotation() && signal_index.is_none_or(|s| s == *i))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        for signal in signals {
+            let path = spectrogram_cache::cache_path(Path::new(&project.path), source, signal);
+            let spectrogram = spectrogram_cache::compute(&Path::new(&project.path).join(&source.path), signal, |_| ())?;
+            spectrogram_cache::save(&path, &spectrogram)?;
+            written.push(path);
+        }
+    }
+
+    print_json(json!({ "spectrograms": written }))
+}
+
+fn aliases(args: &Arguments) -> Result<(), Box<dyn Error>> {
+    let (action, label) = (args.positional.first().map(String::as_str).unwrap_or("list"), args.positional.get(1));
+    match action {
+        "list" => {}
+        "add" => {
+            let label = label.ok_or("Missing channel label")?;
+            let type_label = args.required("type")?;
+            let channel_type = ChannelType::from_label(type_label).ok_or(format!("Unknown channel type '{}'", type_label))?;
+            database::set_channel_alias(&ChannelAlias {
+                alias: label.trim().to_string(),
+                name: args.required("name")?.trim().to_string(),
+                channel_type
+            }).map_err(|e| format!("{:?}", e))?;
+        }
+        "remove" => {
+            let label = label.ok_or("Missing channel label")?;
+            if !database::remove_channel_alias(label).map_err(|e| format!("{:?}", e))? {
+                return Err(format!("Unknown alias '{}'", label).into());
+            }
+        }
+        _ => return Err(format!("Unknown aliases action '{}'", action).into())
+    }
+
+    let aliases = database::get_channel_aliases().map_err(|e| format!("{:?}", e))?;
+    print_json(Value::Array(aliases.iter().map(|a| json!({
+        "alias": a.alias,
+        "name": a.name,
+        "type": a.channel_type.label()
+    })).collect()))
+}
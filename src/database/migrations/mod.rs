@@ -6,7 +6,9 @@ use crate::database::DB_VERSION;
 // The version number is the version this migration migrates to (e.g. a key of 2
 // would migrate a database with schema version 1 to schema version 2)
 static MIGRATIONS: LazyLock<Vec<(u32, LazyLock<&str>)>> = LazyLock::new(|| vec![
-    (2, LazyLock::new(|| include_str!("v2_add_more_path_caches.sql")))
+    (2, LazyLock::new(|| include_str!("v2_add_more_path_caches.sql"))),
+    (3, LazyLock::new(|| include_str!("v3_add_channel_aliases.sql"))),
+    (4, LazyLock::new(|| include_str!("v4_add_project_presets.sql")))
 ]);
 
 /// Gets all migration SQLs required for the database with the given schema version
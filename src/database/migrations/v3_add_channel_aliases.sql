@@ -0,0 +1,68 @@
+CREATE TABLE IF NOT EXISTS ChannelAliases (
+    alias VARCHAR(255) PRIMARY KEY COLLATE NOCASE,
+    name VARCHAR(255) NOT NULL,
+    channel_type VARCHAR(16) NOT NULL
+);
+
+INSERT OR IGNORE INTO ChannelAliases (alias, name, channel_type) VALUES
+    ('F3-M2', 'F3-M2', 'EEG'),
+    ('F3-A2', 'F3-M2', 'EEG'),
+    ('F4-M1', 'F4-M1', 'EEG'),
+    ('F4-A1', 'F4-M1', 'EEG'),
+    ('C3-M2', 'C3-M2', 'EEG'),
+    ('C3-A2', 'C3-M2', 'EEG'),
+    ('C4-M1', 'C4-M1', 'EEG'),
+    ('C4-A1', 'C4-M1', 'EEG'),
+    ('O1-M2', 'O1-M2', 'EEG'),
+    ('O1-A2', 'O1-M2', 'EEG'),
+    ('O2-M1', 'O2-M1', 'EEG'),
+    ('O2-A1', 'O2-M1', 'EEG'),
+    ('Fpz-Cz', 'Fpz-Cz', 'EEG'),
+    ('Pz-Oz', 'Pz-Oz', 'EEG'),
+    ('E1-M2', 'E1-M2', 'EOG'),
+    ('E1-A2', 'E1-M2', 'EOG'),
+    ('LOC-M2', 'E1-M2', 'EOG'),
+    ('LOC-A2', 'E1-M2', 'EOG'),
+    ('LOC', 'E1-M2', 'EOG'),
+    ('E2-M2', 'E2-M2', 'EOG'),
+    ('E2-A2', 'E2-M2', 'EOG'),
+    ('ROC-M2', 'E2-M2', 'EOG'),
+    ('ROC-A2', 'E2-M2', 'EOG'),
+    ('E2-M1', 'E2-M1', 'EOG'),
+    ('E2-A1', 'E2-M1', 'EOG'),
+    ('ROC-M1', 'E2-M1', 'EOG'),
+    ('ROC-A1', 'E2-M1', 'EOG'),
+    ('ROC', 'E2-M1', 'EOG'),
+    ('Horizontal', 'EOG Horizontal', 'EOG'),
+    ('Chin', 'Chin', 'EMG'),
+    ('Chin1-Chin2', 'Chin', 'EMG'),
+    ('Submental', 'Chin', 'EMG'),
+    ('LAT', 'Leg L', 'EMG'),
+    ('Leg L', 'Leg L', 'EMG'),
+    ('Left Leg', 'Leg L', 'EMG'),
+    ('RAT', 'Leg R', 'EMG'),
+    ('Leg R', 'Leg R', 'EMG'),
+    ('Right Leg', 'Leg R', 'EMG'),
+    ('ECG', 'ECG', 'ECG'),
+    ('EKG', 'ECG', 'ECG'),
+    ('ECG I', 'ECG', 'ECG'),
+    ('ECG II', 'ECG', 'ECG'),
+    ('ECG1-ECG2', 'ECG', 'ECG'),
+    ('Thorax', 'Thorax', 'Resp'),
+    ('Thor', 'Thorax', 'Resp'),
+    ('Chest', 'Thorax', 'Resp'),
+    ('Abdomen', 'Abdomen', 'Resp'),
+    ('Abdo', 'Abdomen', 'Resp'),
+    ('Abd', 'Abdomen', 'Resp'),
+    ('Airflow', 'Airflow', 'Resp'),
+    ('Flow', 'Airflow', 'Resp'),
+    ('Thermistor', 'Airflow', 'Resp'),
+    ('Resp oro-nasal', 'Airflow', 'Resp'),
+    ('Nasal Pressure', 'Nasal Pressure', 'Resp'),
+    ('Cannula', 'Nasal Pressure', 'Resp'),
+    ('PFlow', 'Nasal Pressure', 'Resp'),
+    ('Snore', 'Snore', 'Resp'),
+    ('SpO2', 'SpO2', 'SpO2'),
+    ('SaO2', 'SpO2', 'SpO2'),
+    ('OSAT', 'SpO2', 'SpO2'),
+    ('Pleth', 'Pleth', 'Other');
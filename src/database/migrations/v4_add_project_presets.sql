@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS ProjectPresets (
+    name VARCHAR(255) PRIMARY KEY COLLATE NOCASE,
+    preset TEXT NOT NULL
+);
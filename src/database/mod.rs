@@ -3,13 +3,15 @@ use chrono::NaiveDateTime;
 use md5::{Digest, Md5};
 use rusqlite::{Connection, ToSql, types::FromSql};
 
-use crate::database::{migrations::get_migrations, types::RecentProject};
+use crate::database::{migrations::get_migrations, types::{ChannelAlias, RecentProject}};
+use crate::signals::channel::ChannelType;
+use crate::storage::preset::ProjectPreset;
 
 pub mod migrations;
 pub mod types;
 
 pub const DB_PATH: &str = "./appdata.db";
-pub const DB_VERSION: u32 = 2;
+pub const DB_VERSION: u32 = 4;
 
 pub fn get_last_project_path() -> Result<Option<String>, DBError> {
     get_global_setting("last_project_path")
@@ -76,6 +78,85 @@ pub fn update_recently_opened(name: String, path: String) -> Result<(), DBError>
     Ok(())
 }
 
+pub fn get_channel_aliases() -> Result<Vec<ChannelAlias>, DBError> {
+    let connection = open_connection()?;
+    let mut stmt = connection.prepare("SELECT alias, name, channel_type FROM ChannelAliases ORDER BY alias").map_err(DBError::SqliteError)?;
+    let rows = stmt.query_map([], |r| {
+        let alias: String = r.get(0)?;
+        let name: String = r.get(1)?;
+        let channel_type: String = r.get(2)?;
+        Ok((alias, name, channel_type))
+    }).map_err(DBError::SqliteError)?;
+
+    let mut aliases = Vec::new();
+    for row in rows {
+        let Ok((alias, name, channel_type)) = row else {
+            continue;
+        };
+        let channel_type = ChannelType::from_label(&channel_type).unwrap_or(ChannelType::Other);
+        aliases.push(ChannelAlias { alias, name, channel_type });
+    }
+
+    Ok(aliases)
+}
+
+/// Adds the alias or replaces the name and type of an existing alias with the same label (case insensitive)
+pub fn set_channel_alias(alias: &ChannelAlias) -> Result<(), DBError> {
+    let connection = open_connection()?;
+    connection.execute("INSERT OR REPLACE INTO ChannelAliases (alias, name, channel_type) VALUES (?1, ?2, ?3)", [&alias.alias, &alias.name, alias.channel_type.label()])
+        .map_err(DBError::SqliteError)?;
+
+    Ok(())
+}
+
+/// Removes the alias and returns whether it existed
+pub fn remove_channel_alias(alias: &str) -> Result<bool, DBError> {
+    let connection = open_connection()?;
+    let count = connection.execute("DELETE FROM ChannelAliases WHERE alias = ?1", [alias])
+        .map_err(DBError::SqliteError)?;
+
+    Ok(count > 0)
+}
+
+/// Gets all stored project presets ordered by their name. Presets which cannot be read are skipped.
+pub fn get_project_presets() -> Result<Vec<ProjectPreset>, DBError> {
+    let connection = open_connection()?;
+    let mut stmt = connection.prepare("SELECT preset FROM ProjectPresets ORDER BY name COLLATE NOCASE").map_err(DBError::SqliteError)?;
+    let rows = stmt.query_map([], |r| r.get::<_, String>(0)).map_err(DBError::SqliteError)?;
+
+    let mut presets = Vec::new();
+    for row in rows {
+        let Ok(preset) = row else {
+            continue;
+        };
+        let Ok(preset) = serde_json::from_str::<ProjectPreset>(&preset) else {
+            continue;
+        };
+        presets.push(preset);
+    }
+
+    Ok(presets)
+}
+
+/// Adds the preset or replaces an existing preset with the same name (case insensitive)
+pub fn set_project_preset(preset: &ProjectPreset) -> Result<(), DBError> {
+    let content = serde_json::to_string(preset).map_err(DBError::JsonError)?;
+    let connection = open_connection()?;
+    connection.execute("INSERT OR REPLACE INTO ProjectPresets (name, preset) VALUES (?1, ?2)", [&preset.name, &content])
+        .map_err(DBError::SqliteError)?;
+
+    Ok(())
+}
+
+/// Removes the preset and returns whether it existed
+pub fn remove_project_preset(name: &str) -> Result<bool, DBError> {
+    let connection = open_connection()?;
+    let count = connection.execute("DELETE FROM ProjectPresets WHERE name = ?1", [name])
+        .map_err(DBError::SqliteError)?;
+
+    Ok(count > 0)
+}
+
 fn get_global_setting<T>(setting_name: &str) -> Result<T, DBError> where T: FromSql {
     let connection = open_connection()?;
     let value = connection.query_one(&format!("SELECT {} FROM GlobalSettings WHERE id = 0", setting_name), (), |r| r.get::<_, T>(0))
@@ -123,8 +204,9 @@ fn open_connection() -> Result<Connection, DBError> {
 }
 
 #[derive(Debug)]
-#[allow(dead_code)]
+#[allow(dead_code, clippy::enum_variant_names)]
 pub enum DBError {
     SqliteError(rusqlite::Error),
-    IOError(std::io::Error)
+    IOError(std::io::Error),
+    JsonError(serde_json::Error)
 }
@@ -1,4 +1,7 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::signals::channel::ChannelType;
 
 #[derive(Debug, Clone)]
 pub struct RecentProject {
@@ -6,3 +9,11 @@ pub struct RecentProject {
     pub path: String,
     pub last_opened: DateTime<Utc>
 }
+
+/// Canonical name and type of channels whose label matches the alias
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChannelAlias {
+    pub alias: String,
+    pub name: String,
+    pub channel_type: ChannelType
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::CurrentProject;
+use crate::export::hypnogram::{epoch_rows, escape};
+use crate::statistics::sleep_stats::AnalysisPeriod;
+
+// Value for missing entries as defined by BIDS
+const NOT_AVAILABLE: &str = "n/a";
+
+/// Writes the hypnogram and all scored events within the analysis period as BIDS events file. The onsets are
+/// in seconds relative to the start of the first epoch of the project.
+pub fn write_events(project: &CurrentProject, period: &AnalysisPeriod, path: &Path) -> Result<(), Box<dyn Error>> {
+    let period_millis = period.millis(project.project.epoch_duration);
+    let mut rows = Vec::new();
+
+    // Every epoch as sleep stage including the unscored ones
+    rows.extend(epoch_rows(project, period).into_iter().map(|row| (
+        row.start_relative,
+        row.duration,
+        "sleep_stage".to_string(),
+        row.stage.unwrap_or(NOT_AVAILABLE.to_string()),
+        NOT_AVAILABLE.to_string()
+    )));
+
+    rows.extend(project.events.values.iter().filter(|e| period_millis.contains(&e.onset)).map(|event| (
+        event.onset as f64 / 1_000.0,
+        event.duration as f64 / 1_000.0,
+        "event".to_string(),
+        event.event_type.label().to_string(),
+        event.channel.clone()
+    )));
+
+    // Bounds of the analysis period, if they were set
+    let bounds = [(project.project.lights_off, "lights_off"), (project.project.lights_on, "lights_on")];
+    rows.extend(bounds.into_iter().filter_map(|(timestamp, trial_type)| timestamp.map(|timestamp| (
+        timestamp as f64 / 1_000.0,
+        0.0,
+        trial_type.to_string(),
+        NOT_AVAILABLE.to_string(),
+        NOT_AVAILABLE.to_string()
+    ))));
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut content = "onset\tduration\ttrial_type\tvalue\tchannel\n".to_string();
+    for (onset, duration, trial_type, value, channel) in rows {
+        content.push_str(&format!("{:.3}\t{:.3}\t{}\t{}\t{}\n", onset, duration, trial_type, escape(&value, '\t'), escape(&channel, '\t')));
+    }
+
+    fs::write(path, content)?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::path::Path;
+
+use crate::{CurrentProject, Marker};
+use crate::statistics::sleep_stats::AnalysisPeriod;
+use crate::storage::edf_annotations::{EdfAnnotation, write_annotations};
+
+// Text of the annotations marking the bounds of the analysis period
+pub const LIGHTS_OFF: &str = "Lights off";
+pub const LIGHTS_ON: &str = "Lights on";
+
+// Prefixes of the markers (e.g. "Marker Red") and annotations (e.g. "Annotation Red: Spindle") followed by the marker
+pub const MARKER_PREFIX: &str = "Marker ";
+pub const ANNOTATION_PREFIX: &str = "Annotation ";
+
+/// Text of an annotation of the given marker and value, which is read again by `parse_annotation`
+pub fn annotation_text(marker: &Marker, value: &str) -> String {
+    format!("{}{}: {}", ANNOTATION_PREFIX, marker.label(), value)
+}
+
+/// Gets the marker of a marker annotation (e.g. "Marker Red")
+pub fn parse_marker(text: &str) -> Option<Marker> {
+    Marker::from_label(text.strip_prefix(MARKER_PREFIX)?)
+}
+
+/// Gets the marker and value of an annotation (e.g. "Annotation Red: Spindle")
+pub fn parse_annotation(text: &str) -> Option<(Marker, String)> {
+    let (marker, value) = text.strip_prefix(ANNOTATION_PREFIX)?.split_once(':')?;
+    Some((Marker::from_label(marker)?, value.trim().to_string()))
+}
+
+/// Collects the scores, markers, annotations and events of the project within the analysis period as EDF+ annotations.
+/// The onsets are in seconds relative to the start of the first epoch of the project.
+pub fn collect_annotations(project: &CurrentProject, period: &AnalysisPeriod) -> Vec<EdfAnnotation> {
+    let vocabulary = &project.project.stage_vocabulary;
+    let period_millis = period.millis(project.project.epoch_duration);
+    let mut annotations = Vec::new();
+
+    // Every scored epoch as a stage annotation (e.g. "Sleep stage N2")
+    if let Some(scorings) = &project.scorings {
+        let epoch_duration = project.project.epoch_duration as f64;
+        let values = scorings.values.range(period.epochs.clone());
+        annotations.extend(values.map(|(epoch, stage)| EdfAnnotation {
+            onset: *epoch as f64 * epoch_duration,
+            duration: epoch_duration,
+            text: vocabulary.get(stage).map(|s| s.annotation_text()).unwrap_or(format!("Sleep stage {}", stage.0))
+        }));
+    }
+
+    // Markers and annotations are either global or bound to a signal
+    let signal_suffix = |signal: Option<u32>| signal
+        .and_then(|index| project.signal_at(index as usize))
+        .map(|(_, channel)| format!(" ({})", channel))
+        .unwrap_or_default();
+
+    let markers = project.markers.global.iter().map(|entry| (None, entry))
+        .chain(project.markers.local.iter().flat_map(|(signal, markers)| markers.iter().map(|entry| (Some(*signal), entry))));
+    for (signal, (marker, timestamps)) in markers {
+        annotations.extend(timestamps.iter().filter(|t| period_millis.contains(t)).map(|timestamp| EdfAnnotation {
+            onset: *timestamp as f64 / 1_000.0,
+            duration: 0.0,
+            text: format!("{}{}{}", MARKER_PREFIX, marker.label(), signal_suffix(signal))
+        }));
+    }
+
+    let values = project.annotations.global.iter().map(|entry| (None, entry))
+        .chain(project.annotations.local.iter().flat_map(|(signal, values)| values.iter().map(|entry| (Some(*signal), entry))));
+    for (signal, (marker, values)) in values {
+        annotations.extend(values.iter().filter(|v| period_millis.contains(&v.timestamp)).map(|value| EdfAnnotation {
+            onset: value.timestamp as f64 / 1_000.0,
+            duration: 0.0,
+            text: format!("{}{}", annotation_text(marker, &value.value), signal_suffix(signal))
+        }));
+    }
+
+    // Events including the channel they are bound to (e.g. "Hypopnea (Flow)")
+    let events = project.events.values.iter().filter(|e| period_millis.contains(&e.onset));
+    annotations.extend(events.map(|event| EdfAnnotation {
+        onset: event.onset as f64 / 1_000.0,
+        duration: event.duration as f64 / 1_000.0,
+        text: format!("{} ({})", event.event_type.label(), event.channel)
+    }));
+
+    // Bounds of the analysis period, if they were set
+    let bounds = [(project.project.lights_off, LIGHTS_OFF), (project.project.lights_on, LIGHTS_ON)];
+    annotations.extend(bounds.into_iter().filter_map(|(timestamp, text)| timestamp.map(|timestamp| EdfAnnotation {
+        onset: timestamp as f64 / 1_000.0,
+        duration: 0.0,
+        text: text.to_string()
+    })));
+
+    // Control characters are not allowed within the text of annotations
+    for annotation in &mut annotations {
+        annotation.text = annotation.text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    }
+    annotations.sort_by(|a, b| a.onset.total_cmp(&b.onset));
+
+    annotations
+}
+
+/// Writes all labels of the project within the analysis period into an annotation-only EDF+ file. The file starts
+/// with the first epoch of the project, so the offsets of the sources are already applied.
+pub fn export(project: &CurrentProject, period: &AnalysisPeriod, path: &Path) -> Result<(), Box<dyn Error>> {
+    let start = project.start_time().ok_or("Project has no sources to get the start time from")?;
+    let duration = project.epoch_count() as f64 * project.project.epoch_duration as f64;
+
+    write_annotations(path, start, duration, &collect_annotations(project, period))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{AnnotationValue, Scorings};
+    use crate::import;
+    use crate::scoring::events::{EventType, ScoredEvent};
+    use crate::scoring::stages::Stage;
+    use crate::testing::{self, TestDirectory};
+
+    #[test]
+    fn exported_annotations_are_imported_again() {
+        // Offsets with fractions of a second, which have to be kept within the data-records of the export
+        let directory = TestDirectory::new("edf-export");
+        let mut project = testing::project(&directory, &[("psg.edf", "C3", 1_500), ("flow.edf", "Flow", 4_250)]);
+
+        let mut scorings = Scorings::new(30);
+        for (epoch, stage) in [(0, "W"), (1, "N1"), (2, "N2"), (4, "N3"), (5, "R")] {
+            scorings.values.insert(epoch, Stage::new(stage));
+        }
+        project.scorings = Some(scorings.clone());
+
+        let events = [
+            ScoredEvent { event_type: EventType::Arousal, source: 0, channel: "C3".to_string(), onset: 31_250, duration: 4_500 },
+            ScoredEvent { event_type: EventType::Hypopnea, source: 1, channel: "Flow".to_string(), onset: 95_000, duration: 12_000 }
+        ];
+        for event in events.clone() {
+            project.events.insert(event);
+        }
+
+        // Signals are numbered across all sources, Flow is the second signal
+        project.markers.global.insert(Marker::Red, vec![60_000]);
+        project.markers.local.insert(1, [(Marker::Blue, vec![95_500])].into());
+        let snoring = AnnotationValue { timestamp: 120_000, value: "Snoring (loud)".to_string() };
+        let spindle = AnnotationValue { timestamp: 45_000, value: "Spindle".to_string() };
+        project.annotations.global.insert(Marker::Green, vec![snoring.clone()]);
+        project.annotations.local.insert(0, [(Marker::Purple, vec![spindle.clone()])].into());
+        project.project.lights_off = Some(20_000);
+        project.project.lights_on = Some(225_000);
+
+        let path = directory.join("export.edf");
+        export(&project, &project.analysis_period(), &path).unwrap();
+        let preview = import::preview(&project, &path).unwrap();
+
+        assert_eq!(preview.offset, Some(0.0));
+        assert_eq!(preview.lights_off, Some(20_000));
+        assert_eq!(preview.lights_on, Some(225_000));
+        assert_eq!(preview.stages, scorings.values);
+        assert!(preview.unknown_labels.is_empty(), "{:?}", preview.unknown_labels);
+
+        let imported = preview.events.iter()
+            .map(|e| ((e.source, e.channel.clone()), (e.event_type, e.onset, e.duration)))
+            .collect::<BTreeMap<_, _>>();
+        let expected = events.iter()
+            .map(|e| ((e.source, e.channel.clone()), (e.event_type, e.onset, e.duration)))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(imported, expected);
+
+        let mut markers = preview.markers.clone();
+        markers.sort_by_key(|(_, _, timestamp)| *timestamp);
+        assert_eq!(markers, vec![(None, Marker::Red, 60_000), (Some(1), Marker::Blue, 95_500)]);
+
+        let mut annotations = preview.annotations.clone();
+        annotations.sort_by_key(|(_, _, value)| value.timestamp);
+        assert_eq!(annotations, vec![(Some(0), Marker::Purple, spindle), (None, Marker::Green, snoring)]);
+    }
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use ndarray::Array1;
+use ndarray_npy::write_npy;
+
+use crate::CurrentProject;
+use crate::statistics::sleep_stats::AnalysisPeriod;
+
+// Stage text and integer code of epochs which were not scored (YASA convention)
+pub const UNSCORED: &str = "Unscored";
+pub const UNSCORED_CODE: i32 = -1;
+
+/// A single epoch of the exported hypnogram
+pub struct EpochRow {
+    pub epoch: u64,
+    pub start: Option<NaiveDateTime>,
+    pub start_relative: f64,    // seconds relative to the first epoch
+    pub duration: f64,          // seconds
+    pub stage: Option<String>,
+    pub code: i32
+}
+
+/// Gets every epoch of the analysis period including the unscored ones
+pub fn epoch_rows(project: &CurrentProject, period: &AnalysisPeriod) -> Vec<EpochRow> {
+    let Some(scorings) = &project.scorings else {
+        return Vec::new();
+    };
+
+    let vocabulary = &project.project.stage_vocabulary;
+    let start = project.start_time();
+    let duration = project.project.epoch_duration as f64;
+
+    period.epochs.clone().map(|epoch| {
+        let stage = scorings.values.get(&epoch);
+        let start_relative = epoch as f64 * duration;
+
+        EpochRow {
+            epoch,
+            start: start.map(|s| s + TimeDelta::milliseconds((start_relative * 1_000.0) as i64)),
+            start_relative,
+            duration,
+            stage: stage.map(|s| s.0.clone()),
+            code: stage.and_then(|s| vocabulary.get(s)).and_then(|s| s.code).map_or(UNSCORED_CODE, |c| c as i32)
+        }
+    }).collect()
+}
+
+/// Writes the hypnogram as table with one row per epoch, separated by the given delimiter (e.g. CSV or TSV)
+pub fn write_table(project: &CurrentProject, period: &AnalysisPeriod, path: &Path, delimiter: char) -> Result<(), Box<dyn Error>> {
+    let mut content = ["epoch", "start_time", "start_relative", "duration", "stage", "code"].join(&delimiter.to_string());
+    content.push('\n');
+
+    for row in epoch_rows(project, period) {
+        let start = row.start.map(|s| s.format("%Y-%m-%dT%H:%M:%S%.3f").to_string()).unwrap_or_default();
+        let stage = row.stage.unwrap_or(UNSCORED.to_string());
+        content.push_str(&[
+            row.epoch.to_string(),
+            start,
+            format!("{:.3}", row.start_relative),
+            format!("{:.3}", row.duration),
+            escape(&stage, delimiter),
+            row.code.to_string()
+        ].join(&delimiter.to_string()));
+        content.push('\n');
+    }
+
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Writes the integer coded hypnogram as text file with one value per line
+pub fn write_yasa_text(project: &CurrentProject, period: &AnalysisPeriod, path: &Path) -> Result<(), Box<dyn Error>> {
+    let content = epoch_rows(project, period).iter()
+        .map(|row| format!("{}\n", row.code))
+        .collect::<String>();
+
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Writes the integer coded hypnogram as NumPy array
+pub fn write_yasa_npy(project: &CurrentProject, period: &AnalysisPeriod, path: &Path) -> Result<(), Box<dyn Error>> {
+    let codes = Array1::from_iter(epoch_rows(project, period).iter().map(|row| row.code));
+    write_npy(path, &codes)?;
+    Ok(())
+}
+
+/// Quotes the value in case it contains the delimiter or quotes
+pub fn escape(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
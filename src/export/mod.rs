@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::CurrentProject;
+use crate::statistics::sleep_stats::AnalysisPeriod;
+
+pub mod edf_plus;
+pub mod hypnogram;
+pub mod bids;
+
+/// File formats the labels of a project can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    EdfAnnotations,
+    Csv,
+    Tsv,
+    YasaText,
+    YasaNpy,
+    BidsEvents
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 6] = [
+        ExportFormat::EdfAnnotations,
+        ExportFormat::Csv,
+        ExportFormat::Tsv,
+        ExportFormat::YasaText,
+        ExportFormat::YasaNpy,
+        ExportFormat::BidsEvents
+    ];
+
+    /// Gets the identifier of the format used on the command line
+    pub fn id(&self) -> &'static str {
+        match self {
+            ExportFormat::EdfAnnotations => "edf",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::YasaText => "yasa-txt",
+            ExportFormat::YasaNpy => "yasa-npy",
+            ExportFormat::BidsEvents => "bids"
+        }
+    }
+
+    /// Gets the separator between the project name and the suffix of the exported file name.
+    /// BIDS files use an underscore to follow the BIDS naming convention (e.g. `<project>_events.tsv`).
+    fn file_separator(&self) -> &'static str {
+        match self {
+            ExportFormat::BidsEvents => "_",
+            _ => "."
+        }
+    }
+
+    /// Gets the suffix of the exported file name including its extension
+    fn file_suffix(&self) -> &'static str {
+        match self {
+            ExportFormat::EdfAnnotations => "annotations.edf",
+            ExportFormat::Csv => "hypnogram.csv",
+            ExportFormat::Tsv => "hypnogram.tsv",
+            ExportFormat::YasaText => "hypnogram.txt",
+            ExportFormat::YasaNpy => "hypnogram.npy",
+            ExportFormat::BidsEvents => "events.tsv"
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::EdfAnnotations => write!(f, "EDF+ annotations"),
+            ExportFormat::Csv => write!(f, "Hypnogram (CSV)"),
+            ExportFormat::Tsv => write!(f, "Hypnogram (TSV)"),
+            ExportFormat::YasaText => write!(f, "YASA hypnogram (.txt)"),
+            ExportFormat::YasaNpy => write!(f, "YASA hypnogram (.npy)"),
+            ExportFormat::BidsEvents => write!(f, "BIDS events (.tsv)")
+        }
+    }
+}
+
+/// Exports the labels of the project within the analysis period in the given format next to its sources and
+/// returns the path of the written file
+pub fn export(project: &CurrentProject, format: ExportFormat, period: &AnalysisPeriod) -> Result<PathBuf, Box<dyn Error>> {
+    let path = export_path(project, format);
+    export_to(project, format, period, &path)?;
+
+    Ok(path)
+}
+
+/// Exports the labels of the project within the analysis period in the given format to the given path.
+/// Hypnograms only contain the epochs of the period, event based formats additionally contain its bounds.
+pub fn export_to(project: &CurrentProject, format: ExportFormat, period: &AnalysisPeriod, path: &Path) -> Result<(), Box<dyn Error>> {
+    match format {
+        ExportFormat::EdfAnnotations => edf_plus::export(project, period, path),
+        ExportFormat::Csv => hypnogram::write_table(project, period, path, ','),
+        ExportFormat::Tsv => hypnogram::write_table(project, period, path, '\t'),
+        ExportFormat::YasaText => hypnogram::write_yasa_text(project, period, path),
+        ExportFormat::YasaNpy => hypnogram::write_yasa_npy(project, period, path),
+        ExportFormat::BidsEvents => bids::write_events(project, period, path)
+    }
+}
+
+/// Gets the path of an exported file next to the sources of the project, e.g. `sources/<project>.annotations.edf`
+fn export_path(project: &CurrentProject, format: ExportFormat) -> PathBuf {
+    let name = Path::new(&project.project_name).file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or(project.project.name.clone());
+    Path::new(&project.path).join("sources").join(format!("{}{}{}", name, format.file_separator(), format.file_suffix()))
+}
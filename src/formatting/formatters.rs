@@ -41,6 +41,11 @@ pub fn date_time_string(timestamp: u64) -> String {
     time.format("%d-%m-%Y %H:%M:%S").to_string()
 }
 
+pub fn time_string(timestamp: u64) -> String {
+    let time = DateTime::<Utc>::from_timestamp_secs(timestamp as i64).unwrap();
+    time.format("%H:%M:%S").to_string()
+}
+
 fn hms_u64(total_seconds: u64) -> String {
     let hours = total_seconds / 3600;
     let rem = total_seconds % 3600;
@@ -77,4 +82,22 @@ pub fn hms_separate(seconds: u64) -> String {
     } else {
         parts.join(" ")
     }
+}
+
+/// Formats an amount of bytes with a binary unit, e.g. `1.5 GB`
+pub fn file_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
 }
\ No newline at end of file
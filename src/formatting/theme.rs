@@ -13,8 +13,6 @@ use palette::Pair;
 use iced::theme::palette::Background;
 use iced::Color;
 
-use crate::Stage;
-
 pub const SPECTROGRAM_BORDER_WIDTH: f32 = 2.0;
 
 // **************
@@ -44,24 +42,6 @@ pub const CLEAR_DARK: Palette = Palette {
     warning:    rgba8!(195,  66,  63),
 };
 
-pub const CLEAR_DARK_STAGES: StagePalette = StagePalette {
-    w:     rgba8!(213, 150,  68),   // rgba8!(80, 170,  95)
-    r:     rgba8!(215,  74,  47),
-    n1:    rgba8!( 80, 165, 255),
-    n2:    rgba8!(130, 105, 235),
-    n3:    rgba8!(180,  75, 215),
-    unset: Color::TRANSPARENT
-};
-
-pub const CLEAR_LIGHT_STAGES: StagePalette = StagePalette {
-    w:     rgba8!(213, 150,  68),
-    r:     rgba8!(196,  60,  33),
-    n1:    rgba8!( 44,  62,  80),
-    n2:    rgba8!(161,  54, 212),
-    n3:    rgba8!(134, 171, 213),
-    unset: Color::TRANSPARENT
-};
-
 pub fn generate_extended(palette: Palette) -> palette::Extended {
     palette::Extended {
         background: Background {
@@ -103,43 +83,6 @@ pub fn generate_extended(palette: Palette) -> palette::Extended {
     }
 }
 
-pub fn get_log_palette(theme: &Theme) -> StagePalette {
-    match theme.palette() {
-        CLEAR_DARK => CLEAR_DARK_STAGES,
-        _ => CLEAR_LIGHT_STAGES
-    }
-}
-
-pub fn container_stage_r(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.r)
-}
-
-pub fn container_stage_w(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.w)
-}
-
-pub fn container_stage_n1(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.n1)
-}
-
-pub fn container_stage_n2(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.n2)
-}
-
-pub fn container_stage_n3(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.n3)
-}
-
-pub fn container_stage_unset(theme: &Theme) -> widget::container::Style {
-    let palette = get_log_palette(theme);
-    container_stage_base(palette.unset)
-}
-
 fn container_stage_base(color: Color) -> widget::container::Style {
     widget::container::Style {
         background: Some(color.into()),
@@ -181,6 +124,19 @@ pub fn container_spectrogram(theme: &Theme) -> widget::container::Style {
     }
 }
 
+/// Shading for epochs outside of the analysis period (lights-off / lights-on)
+pub fn container_outside_period(theme: &Theme) -> widget::container::Style {
+    let color = theme.extended_palette().background.weakest.color;
+    widget::container::Style {
+        background: Some(color.scale_alpha(0.72).into()),
+        border: border::Border {
+            radius: 4.0.into(),
+            ..Default::default()
+        },
+        ..widget::container::Style::default()
+    }
+}
+
 pub fn container_tag(theme: &Theme) -> widget::container::Style {
     let stroke = theme.extended_palette().background.weak.color;
     let mut style = container_stage_base(stroke);
@@ -253,6 +209,14 @@ pub fn container_secondary(theme: &Theme) -> widget::container::Style {
     }
 }
 
+pub fn container_secondary_invalid(theme: &Theme) -> widget::container::Style {
+    let mut style = container_secondary(theme);
+    style.border.color = theme.palette().danger;
+    style.border.width = 1.0;
+
+    style
+}
+
 pub fn container_counter(theme: &Theme, is_current: bool) -> widget::container::Style {
     let palette = theme.palette();
 
@@ -432,6 +396,15 @@ pub fn text_input(theme: &Theme, status: iced::widget::text_input::Status) -> wi
     style
 }
 
+/// Text input of a field with an invalid value, e.g. in the project creation wizard
+pub fn text_input_invalid(theme: &Theme, status: iced::widget::text_input::Status) -> widget::text_input::Style {
+    let mut style = text_input(theme, status);
+    style.border.color = theme.palette().danger;
+    style.border.width = 1.0;
+
+    style
+}
+
 pub fn text_input_secondary(theme: &Theme, status: iced::widget::text_input::Status) -> widget::text_input::Style {
     let palette = theme.extended_palette();
     let mut style = text_input(theme, status);
@@ -441,15 +414,6 @@ pub fn text_input_secondary(theme: &Theme, status: iced::widget::text_input::Sta
     style
 }
 
-pub struct StagePalette {
-    pub r: Color,
-    pub w: Color,
-    pub n1: Color,
-    pub n2: Color,
-    pub n3: Color,
-    pub unset: Color,
-}
-
 // **************
 // Control styles
 // **************
@@ -517,24 +481,24 @@ pub fn text_tertiary(_theme: &Theme) -> widget::text::Style {
     }
 }
 
-pub fn border_background_base(theme: &Theme, stage: &Stage) -> widget::container::Style {
-    match stage {
-        Stage::W => container_stage_w(theme),
-        Stage::R => container_stage_r(theme),
-        Stage::N1 => container_stage_n1(theme),
-        Stage::N2 => container_stage_n2(theme),
-        Stage::N3 => container_stage_n3(theme),
-        Stage::Unset => container_stage_unset(theme)//container_stage_transparent(),
+/// Text conveying a warning, e.g. about problems found before importing a file.
+pub fn text_danger(theme: &Theme) -> widget::text::Style {
+    widget::text::Style {
+        color: Some(theme.palette().danger),
     }
 }
 
+pub fn border_background_base(_theme: &Theme, color: Option<Color>) -> widget::container::Style {
+    container_stage_base(color.unwrap_or(Color::TRANSPARENT))
+}
+
 /// Text conveying some secondary information, like a footnote.
-pub fn text_foreground_base(theme: &Theme, stage: &Stage) -> widget::text::Style {
+pub fn text_foreground_base(theme: &Theme, is_set: bool) -> widget::text::Style {
     widget::text::Style {
-        color: Some(if *stage == Stage::Unset {
-            Color { r: 0.6, g: 0.6, b: 0.6, a: 1.0 }
-        } else {
+        color: Some(if is_set {
             theme.extended_palette().background.base.color
+        } else {
+            Color { r: 0.6, g: 0.6, b: 0.6, a: 1.0 }
         }),
     }
 }
@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::path::Path;
+
+use chrono::TimeDelta;
+use edf_rs::file::EDFFile;
+
+use crate::import::{ImportedLabel, ImportedLabels, LabelValue};
+use crate::storage::edf_annotations::read_annotations;
+
+/// Reads all annotations of an EDF+ file (e.g. a Sleep-EDF hypnogram). Annotations ending with a
+/// channel in parentheses (e.g. "Arousal (C3)") are bound to that channel.
+pub fn read(path: &Path) -> Result<ImportedLabels, Box<dyn Error>> {
+    let mut file = EDFFile::open(path)?;
+    let start = file.header.start_date().and_time(file.header.get_start_time());
+
+    // Fractions of a second of the start time are stored as onset of the first data-record (EDF+ time keeping)
+    let start_offset = file.read_record()?.map(|r| r.get_start_offset()).unwrap_or(0.0);
+    let start = start + TimeDelta::milliseconds((start_offset * 1_000.0).round() as i64);
+
+    let labels = read_annotations(path)?.into_iter().map(|annotation| {
+        let text = annotation.text.trim();
+        let (text, channel) = match text.strip_suffix(')').and_then(|t| t.rsplit_once(" (")) {
+            Some((text, channel)) => (text.to_string(), Some(channel.to_string())),
+            None => (text.to_string(), None)
+        };
+
+        ImportedLabel {
+            onset: annotation.onset - start_offset,
+            duration: annotation.duration,
+            value: LabelValue::Text(text),
+            channel
+        }
+    }).collect();
+
+    Ok(ImportedLabels {
+        epoch_duration: None,
+        start: Some(start),
+        labels
+    })
+}
@@ -0,0 +1,146 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use ndarray::Array1;
+
+use crate::import::{ImportedLabel, ImportedLabels, LabelValue};
+
+// Names of the columns which identify the first row of a table as header
+const KNOWN_COLUMNS: [&str; 10] = ["epoch", "stage", "code", "value", "onset", "start_relative", "start_time", "duration", "trial_type", "channel"];
+
+// Trial type of stages in BIDS events files
+const TRIAL_TYPE_STAGE: &str = "sleep_stage";
+
+/// Reads a hypnogram table (e.g. CSV, TSV or BIDS events) or a plain list with one stage per line (e.g. YASA).
+/// Tables with a header may contain the onset and duration of each row, otherwise every row is a single epoch
+/// of the given length in seconds.
+pub fn read_table(path: &Path, delimiter: char, epoch_length: f64) -> Result<ImportedLabels, Box<dyn Error>> {
+    let content = fs::read_to_string(path)?;
+    let mut rows = content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| split_row(line, delimiter));
+
+    let Some(first) = rows.next() else {
+        return Ok(ImportedLabels::default());
+    };
+    // Plain list of stages without a header
+    let header = first.iter().map(|c| c.to_lowercase()).collect::<Vec<_>>();
+    if !header.iter().any(|c| KNOWN_COLUMNS.contains(&c.as_str())) {
+        let labels = std::iter::once(first).chain(rows).enumerate().map(|(i, row)| ImportedLabel {
+            onset: i as f64 * epoch_length,
+            duration: epoch_length,
+            value: label_value(&row[0]),
+            channel: None
+        }).collect();
+
+        return Ok(ImportedLabels {
+            epoch_duration: None,
+            start: None,
+            labels
+        });
+    }
+
+    let column = |names: &[&str]| names.iter().find_map(|name| header.iter().position(|c| c == name));
+    let stage_column = column(&["stage", "value", "code"]).ok_or("No stage column found")?;
+    let onset_column = column(&["onset", "start_relative"]);
+    let epoch_column = column(&["epoch"]);
+    let duration_column = column(&["duration"]);
+    let start_column = column(&["start_time"]);
+    let trial_type_column = column(&["trial_type"]);
+    let channel_column = column(&["channel"]);
+
+    let mut epoch_duration = None;
+    let mut start = None;
+    let mut labels = Vec::new();
+    for (i, row) in rows.enumerate() {
+        let cell = |index: Option<usize>| index.and_then(|i| row.get(i)).map(|v| v.as_str()).filter(|v| !v.is_empty() && *v != "n/a");
+        let number = |index: Option<usize>| cell(index).and_then(|v| v.parse::<f64>().ok());
+
+        let duration = number(duration_column).unwrap_or(epoch_length);
+        let onset = number(onset_column)
+            .or(number(epoch_column).map(|epoch| epoch * duration))
+            .unwrap_or(i as f64 * duration);
+
+        // Rows of BIDS events which are not stages are scored events
+        let is_stage = cell(trial_type_column).is_none_or(|t| t == TRIAL_TYPE_STAGE);
+        if is_stage {
+            epoch_duration.get_or_insert(duration);
+        }
+        if start.is_none() && let Some(time) = cell(start_column).and_then(parse_time) {
+            start = Some(time - TimeDelta::milliseconds((onset * 1_000.0) as i64));
+        }
+
+        labels.push(ImportedLabel {
+            onset,
+            duration,
+            value: cell(Some(stage_column)).map(label_value).unwrap_or(LabelValue::Text("?".to_string())),
+            channel: cell(channel_column).filter(|_| !is_stage).map(str::to_string)
+        });
+    }
+
+    Ok(ImportedLabels {
+        epoch_duration,
+        start,
+        labels
+    })
+}
+
+/// Reads an integer coded hypnogram stored as NumPy array (YASA convention) with one stage per epoch of the given length in seconds
+pub fn read_npy(path: &Path, epoch_length: f64) -> Result<ImportedLabels, Box<dyn Error>> {
+    // NumPy stores integers with 64 bits by default, but smaller and floating point types are common too
+    let codes = ndarray_npy::read_npy::<_, Array1<i64>>(path)
+        .or_else(|_| ndarray_npy::read_npy::<_, Array1<i32>>(path).map(|a| a.mapv(i64::from)))
+        .or_else(|_| ndarray_npy::read_npy::<_, Array1<f64>>(path).map(|a| a.mapv(|v| v as i64)))?;
+
+    let labels = codes.iter().enumerate().map(|(i, code)| ImportedLabel {
+        onset: i as f64 * epoch_length,
+        duration: epoch_length,
+        value: LabelValue::Code(*code as i32),
+        channel: None
+    }).collect();
+
+    Ok(ImportedLabels {
+        epoch_duration: None,
+        start: None,
+        labels
+    })
+}
+
+fn label_value(value: &str) -> LabelValue {
+    match value.trim().parse::<i32>() {
+        Ok(code) => LabelValue::Code(code),
+        Err(_) => LabelValue::Text(value.trim().to_string())
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+}
+
+/// Splits a row of a table by the delimiter, taking quoted values into account
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut is_quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if is_quoted && chars.peek() == Some(&'"') => {
+                value.push('"');
+                chars.next();
+            }
+            '"' => is_quoted = !is_quoted,
+            c if c == delimiter && !is_quoted => values.push(std::mem::take(&mut value).trim().to_string()),
+            c => value.push(c)
+        }
+    }
+    values.push(value.trim().to_string());
+
+    values
+}
@@ -0,0 +1,582 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+
+use crate::{AnnotationValue, CurrentProject, Marker};
+use crate::export::edf_plus::{parse_annotation, parse_marker};
+use crate::scoring::events::{EventType, ScoredEvent};
+use crate::scoring::stages::{Stage, StageVocabulary};
+use crate::storage::edf_annotations::{EdfAnnotation, find_lights_annotations, is_lights_annotation};
+
+pub mod profusion;
+pub mod edf_plus;
+pub mod hypnogram;
+
+// File extensions of all files scorings can be imported from
+pub const IMPORT_EXTENSIONS: [&str; 6] = ["xml", "edf", "csv", "tsv", "txt", "npy"];
+
+/// File formats scorings and events can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    ProfusionXml,
+    EdfHypnogram,
+    Csv,
+    Tsv,
+    YasaText,
+    YasaNpy
+}
+
+impl ImportFormat {
+    /// Gets the format of the file based on its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "xml" => Some(ImportFormat::ProfusionXml),
+            "edf" => Some(ImportFormat::EdfHypnogram),
+            "csv" => Some(ImportFormat::Csv),
+            "tsv" => Some(ImportFormat::Tsv),
+            "txt" => Some(ImportFormat::YasaText),
+            "npy" => Some(ImportFormat::YasaNpy),
+            _ => None
+        }
+    }
+}
+
+impl Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportFormat::ProfusionXml => write!(f, "NSRR / Profusion XML"),
+            ImportFormat::EdfHypnogram => write!(f, "EDF+ hypnogram"),
+            ImportFormat::Csv => write!(f, "CSV"),
+            ImportFormat::Tsv => write!(f, "TSV"),
+            ImportFormat::YasaText => write!(f, "YASA hypnogram (.txt)"),
+            ImportFormat::YasaNpy => write!(f, "YASA hypnogram (.npy)")
+        }
+    }
+}
+
+/// Value of an imported label, either a text (e.g. "Sleep stage W") or an integer coded stage (YASA convention)
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelValue {
+    Text(String),
+    Code(i32)
+}
+
+/// A single label read from an imported file. The onset is in seconds relative to the start of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedLabel {
+    pub onset: f64,
+    pub duration: f64,
+    pub value: LabelValue,
+    pub channel: Option<String>
+}
+
+/// All labels read from an imported file
+#[derive(Debug, Default)]
+pub struct ImportedLabels {
+    // Epoch length of the file in seconds, if it is stated in the file
+    pub epoch_duration: Option<f64>,
+
+    // Absolute start time of the file, if it is stated in the file
+    pub start: Option<NaiveDateTime>,
+
+    pub labels: Vec<ImportedLabel>
+}
+
+/// Result of reading an import file, mapped to the epochs and channels of the project.
+/// Nothing is written to the project until the preview is applied.
+pub struct ImportPreview {
+    pub path: PathBuf,
+    pub format: ImportFormat,
+    pub epoch_duration: Option<f64>,
+
+    // Start of the imported file in seconds relative to the first epoch of the project
+    pub offset: Option<f64>,
+
+    pub stages: BTreeMap<u64, Stage>,
+    pub events: Vec<ScoredEvent>,
+
+    // Markers and annotations (e.g. of an EDF+ export of this application) with the index of the signal they are bound to
+    pub markers: Vec<(Option<u32>, Marker, u64)>,
+    pub annotations: Vec<(Option<u32>, Marker, AnnotationValue)>,
+
+    // Bounds of the analysis period in milliseconds relative to the first epoch, if the file contains lights annotations
+    pub lights_off: Option<u64>,
+    pub lights_on: Option<u64>,
+
+    // Amount of epochs which are already scored with a different stage
+    pub replaced: usize,
+
+    // Labels which could neither be mapped to a stage nor to an event type and their amount of occurrences
+    pub unknown_labels: BTreeMap<String, usize>,
+    pub warnings: Vec<String>
+}
+
+/// Resolution of an imported stage label using the stage vocabulary of the project
+#[derive(Debug, PartialEq)]
+enum StageResolution {
+    Stage(Stage),
+    Unscored,
+    Unknown
+}
+
+/// Reads the labels of the given file and maps them to the project without changing it
+pub fn preview(project: &CurrentProject, path: &Path) -> Result<ImportPreview, Box<dyn Error>> {
+    let format = ImportFormat::from_path(path).ok_or("Unsupported file format")?;
+    let epoch_length = project.project.epoch_duration as f64;
+    let imported = match format {
+        ImportFormat::ProfusionXml => profusion::read(path)?,
+        ImportFormat::EdfHypnogram => edf_plus::read(path)?,
+        ImportFormat::Csv => hypnogram::read_table(path, ',', epoch_length)?,
+        ImportFormat::Tsv => hypnogram::read_table(path, '\t', epoch_length)?,
+        ImportFormat::YasaText => hypnogram::read_table(path, ',', epoch_length)?,
+        ImportFormat::YasaNpy => hypnogram::read_npy(path, epoch_length)?
+    };
+
+    let vocabulary = &project.project.stage_vocabulary;
+    let epoch_count = project.epoch_count();
+    let mut warnings = Vec::new();
+
+    if let Some(duration) = imported.epoch_duration && (duration - epoch_length).abs() > 1e-3 {
+        warnings.push(format!("Epoch length of the file is {} s but the project uses {} s. Stages are assigned by the center of each epoch.", duration, epoch_length));
+    }
+
+    // Align the file using its start time. Files without a start time are expected to start with the first epoch.
+    let offset = imported.start.zip(project.start_time())
+        .map(|(start, project_start)| (start - project_start).num_milliseconds() as f64 / 1_000.0);
+    match offset {
+        Some(offset) if offset.abs() >= 1e-3 => warnings.push(format!("File starts {:.3} s {} the first epoch of the project", offset.abs(), if offset > 0.0 { "after" } else { "before" })),
+        Some(_) => {},
+        None => warnings.push("File has no start time, it is aligned with the first epoch of the project".to_string())
+    }
+
+    // Lights annotations (e.g. of an EDF+ export of this application) are offered as analysis period
+    let lights = imported.labels.iter().filter_map(|label| match &label.value {
+        LabelValue::Text(text) if is_lights_annotation(text) => Some(EdfAnnotation {
+            onset: label.onset + offset.unwrap_or(0.0),
+            duration: label.duration,
+            text: text.clone()
+        }),
+        _ => None
+    }).collect::<Vec<_>>();
+    let to_millis = |onset: f64| (onset * 1_000.0).round().max(0.0) as u64;
+    let (lights_off, lights_on) = find_lights_annotations(&lights);
+
+    let mut stages = BTreeMap::new();
+    let mut events = Vec::new();
+    let mut markers = Vec::new();
+    let mut annotations = Vec::new();
+    let mut unknown_labels = BTreeMap::new();
+    let mut misaligned = 0;
+    let mut outside = 0;
+    let mut unbound = 0;
+
+    for label in imported.labels {
+        let start = label.onset + offset.unwrap_or(0.0);
+        let end = start + label.duration;
+
+        let resolution = match &label.value {
+            LabelValue::Text(text) => resolve_stage(vocabulary, text),
+            LabelValue::Code(code) => resolve_code(vocabulary, *code)
+        };
+        match resolution {
+            StageResolution::Stage(stage) => {
+                // Each epoch gets the stage of the label covering its center
+                let first = (start / epoch_length - 0.5).ceil().max(0.0) as u64;
+                let last = ((end / epoch_length - 0.5).ceil().max(0.0) as u64).min(epoch_count);
+                if first >= last {
+                    outside += 1;
+                }
+                if !is_multiple(label.duration, epoch_length) || !is_multiple(start, epoch_length) {
+                    misaligned += 1;
+                }
+                for epoch in first..last {
+                    stages.insert(epoch, stage.clone());
+                }
+                continue;
+            }
+            StageResolution::Unscored => continue,
+            StageResolution::Unknown => {}
+        }
+
+        let text = match &label.value {
+            LabelValue::Text(text) if is_lights_annotation(text) => continue,
+            LabelValue::Text(text) => text.clone(),
+            LabelValue::Code(code) => {
+                *unknown_labels.entry(code.to_string()).or_insert(0) += 1;
+                continue;
+            }
+        };
+
+        // Markers and annotations are either global or bound to a signal
+        let marker = parse_marker(&text).map(|marker| (marker, None))
+            .or_else(|| parse_annotation(&text).map(|(marker, value)| (marker, Some(value))));
+        if let Some((marker, value)) = marker {
+            let signal = label.channel.as_deref().map(|c| project.signal_index(c).map(|i| i as u32));
+            let (signal, value) = match signal {
+                Some(Some(signal)) => (Some(signal), value),
+                // Values of annotations may end with parentheses themselves
+                Some(None) if let Some(value) = value => (None, Some(format!("{} ({})", value, label.channel.unwrap_or_default()))),
+                Some(None) => {
+                    unbound += 1;
+                    continue;
+                }
+                None => (None, value)
+            };
+            if start < 0.0 || start >= epoch_count as f64 * epoch_length {
+                outside += 1;
+                continue;
+            }
+
+            let timestamp = (start * 1_000.0).round() as u64;
+            match value {
+                Some(value) => annotations.push((signal, marker, AnnotationValue { timestamp, value })),
+                None => markers.push((signal, marker, timestamp))
+            }
+            continue;
+        }
+        let Some(event_type) = resolve_event_type(&text) else {
+            *unknown_labels.entry(text).or_insert(0) += 1;
+            continue;
+        };
+        let Some((source, channel)) = label.channel.as_deref().and_then(|c| project.find_channel(c)) else {
+            unbound += 1;
+            continue;
+        };
+        if start < 0.0 || start >= epoch_count as f64 * epoch_length {
+            outside += 1;
+            continue;
+        }
+
+        events.push(ScoredEvent {
+            event_type,
+            source,
+            channel,
+            onset: (start * 1_000.0).round() as u64,
+            duration: (label.duration.max(0.0) * 1_000.0).round() as u64
+        });
+    }
+
+    if misaligned > 0 {
+        warnings.push(format!("{} stages are not aligned with the epochs of the project", misaligned));
+    }
+    if outside > 0 {
+        warnings.push(format!("{} labels are outside of the recording and are skipped", outside));
+    }
+    if unbound > 0 {
+        warnings.push(format!("{} events and markers have no matching channel in the project and are skipped", unbound));
+    }
+
+    let replaced = project.scorings.as_ref()
+        .map(|s| stages.iter().filter(|(epoch, stage)| s.values.get(epoch).is_some_and(|s| s != *stage)).count())
+        .unwrap_or(0);
+
+    Ok(ImportPreview {
+        path: path.to_path_buf(),
+        format,
+        epoch_duration: imported.epoch_duration,
+        offset,
+        stages,
+        events,
+        markers,
+        annotations,
+        lights_off: lights_off.map(to_millis),
+        lights_on: lights_on.map(to_millis),
+        replaced,
+        unknown_labels,
+        warnings
+    })
+}
+
+/// Writes the stages and events of the preview into the active scorer set of the project
+pub fn apply(project: &mut CurrentProject, preview: ImportPreview) {
+    project.record_change();
+    if let Some(scorings) = &mut project.scorings {
+        for (epoch, stage) in preview.stages {
+            scorings.values.insert(epoch, stage);
+            scorings.provenance.remove(&epoch);
+        }
+    }
+
+    for event in preview.events {
+        project.events.insert(event);
+    }
+
+    project.project.lights_off = preview.lights_off.or(project.project.lights_off);
+    project.project.lights_on = preview.lights_on.or(project.project.lights_on);
+
+    for (signal, marker, timestamp) in preview.markers {
+        let markers = match signal {
+            Some(signal) => project.markers.local.entry(signal).or_default(),
+            None => &mut project.markers.global
+        };
+        markers.entry(marker).or_default().push(timestamp);
+    }
+    for (signal, marker, value) in preview.annotations {
+        let annotations = match signal {
+            Some(signal) => project.annotations.local.entry(signal).or_default(),
+            None => &mut project.annotations.global
+        };
+        annotations.entry(marker).or_default().push(value);
+    }
+}
+
+fn is_multiple(value: f64, epoch_length: f64) -> bool {
+    let epochs = value / epoch_length;
+    (epochs - epochs.round()).abs() < 1e-3
+}
+
+/// Normalizes a stage label to a comparable key, e.g. "Sleep stage 1", "Stage 1 sleep" and "N1" all become "1"
+fn normalize_stage(text: &str) -> String {
+    let text = text.to_lowercase().chars().filter(|c| c.is_alphanumeric() || *c == '?').collect::<String>();
+    let text = text.strip_prefix("sleepstage").or(text.strip_prefix("stage")).unwrap_or(&text);
+    let text = text.strip_suffix("sleep").unwrap_or(text);
+
+    match text {
+        "wake" | "w" => "w".to_string(),
+        "rem" | "r" => "r".to_string(),
+        "movementtime" | "movement" | "mt" => "mt".to_string(),
+        "unscored" | "?" => "?".to_string(),
+        _ => {
+            // Drop the prefix of numbered stages (e.g. "N1" or "S1")
+            let mut chars = text.chars();
+            match (chars.next(), chars.as_str()) {
+                (Some('n' | 's'), digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => digits.to_string(),
+                _ => text.to_string()
+            }
+        }
+    }
+}
+
+/// Resolves a stage label by the identifier, label or annotation text of the stages in the vocabulary.
+/// Common stages which are not part of the vocabulary (e.g. "Stage 4" in AASM) are resolved by their integer code.
+fn resolve_stage(vocabulary: &StageVocabulary, text: &str) -> StageResolution {
+    let key = normalize_stage(text);
+    if key.is_empty() || key == "?" {
+        return StageResolution::Unscored;
+    }
+
+    let stage = vocabulary.stages.iter().find(|s| {
+        [&s.id, &s.label, &s.annotation_text()].iter().any(|value| normalize_stage(value) == key)
+    });
+    if let Some(stage) = stage {
+        return StageResolution::Stage(stage.stage());
+    }
+
+    let code = match key.as_str() {
+        "w" => 0,
+        "1" => 1,
+        "2" => 2,
+        "3" | "4" => 3,
+        "r" => 4,
+        _ => return StageResolution::Unknown
+    };
+    resolve_code(vocabulary, code)
+}
+
+/// Resolves an integer coded stage (YASA convention), negative codes are treated as unscored
+fn resolve_code(vocabulary: &StageVocabulary, code: i32) -> StageResolution {
+    if code < 0 {
+        return StageResolution::Unscored;
+    }
+
+    match vocabulary.stages.iter().find(|s| s.code.is_some_and(|c| c as i32 == code)) {
+        Some(stage) => StageResolution::Stage(stage.stage()),
+        None => StageResolution::Unknown
+    }
+}
+
+/// Resolves the event type of an event label, e.g. "Obstructive Apnea" or "Arousal (ASDA)"
+fn resolve_event_type(text: &str) -> Option<EventType> {
+    let text = text.to_lowercase();
+    if let Some(event_type) = EventType::ALL.into_iter().find(|e| e.label().to_lowercase() == text) {
+        return Some(event_type);
+    }
+
+    if text.contains("arousal") {
+        Some(EventType::Arousal)
+    } else if text.contains("hypopnea") {
+        Some(EventType::Hypopnea)
+    } else if text.contains("apnea") && text.contains("obstructive") {
+        Some(EventType::ObstructiveApnea)
+    } else if text.contains("apnea") && text.contains("central") {
+        Some(EventType::CentralApnea)
+    } else if text.contains("apnea") && text.contains("mixed") {
+        Some(EventType::MixedApnea)
+    } else if text.contains("desat") {
+        Some(EventType::Desaturation)
+    } else if text.contains("periodic") || text.starts_with("plm") {
+        Some(EventType::PeriodicLimbMovement)
+    } else if text.contains("limb movement") || text.contains("leg movement") {
+        Some(EventType::LimbMovement)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{self, TestDirectory};
+
+    const PROFUSION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<CMPStudyConfig>
+    <EpochLength>30</EpochLength>
+    <ScoredEvents>
+        <ScoredEvent>
+            <Name>Obstructive Apnea</Name>
+            <Start>45.5</Start>
+            <Duration>12</Duration>
+            <Input>Flow</Input>
+        </ScoredEvent>
+    </ScoredEvents>
+    <SleepStages>
+        <SleepStage>0</SleepStage>
+        <SleepStage>1</SleepStage>
+        <SleepStage>5</SleepStage>
+        <SleepStage>9</SleepStage>
+    </SleepStages>
+</CMPStudyConfig>"#;
+
+    fn text(value: &str) -> LabelValue {
+        LabelValue::Text(value.to_string())
+    }
+
+    fn stage(id: &str) -> StageResolution {
+        StageResolution::Stage(Stage::new(id))
+    }
+
+    #[test]
+    fn normalizes_stage_labels() {
+        for label in ["Sleep stage 1", "Stage 1 sleep", "N1", "S1", "n1", "1"] {
+            assert_eq!(normalize_stage(label), "1", "{}", label);
+        }
+        for (label, key) in [("Wake", "w"), ("Sleep stage W", "w"), ("REM", "r"), ("Stage R sleep", "r"), ("MT", "mt"), ("Movement time", "mt"), ("?", "?"), ("Unscored", "?")] {
+            assert_eq!(normalize_stage(label), key, "{}", label);
+        }
+    }
+
+    #[test]
+    fn resolves_stages_by_the_vocabulary() {
+        let aasm = StageVocabulary::aasm();
+        assert_eq!(resolve_stage(&aasm, "Sleep stage 1"), stage("N1"));
+        assert_eq!(resolve_stage(&aasm, "Stage 1 sleep"), stage("N1"));
+        assert_eq!(resolve_stage(&aasm, "S1"), stage("N1"));
+        assert_eq!(resolve_stage(&aasm, "Sleep stage R"), stage("R"));
+        assert_eq!(resolve_stage(&aasm, "?"), StageResolution::Unscored);
+        assert_eq!(resolve_stage(&aasm, ""), StageResolution::Unscored);
+
+        // Stages which are not part of the vocabulary are resolved by their integer code
+        assert_eq!(resolve_stage(&aasm, "Sleep stage 4"), stage("N3"));
+        assert_eq!(resolve_stage(&aasm, "MT"), StageResolution::Unknown);
+        assert_eq!(resolve_stage(&aasm, "Obstructive Apnea"), StageResolution::Unknown);
+
+        let rechtschaffen_kales = StageVocabulary::rechtschaffen_kales();
+        assert_eq!(resolve_stage(&rechtschaffen_kales, "Sleep stage 4"), stage("S4"));
+        assert_eq!(resolve_stage(&rechtschaffen_kales, "MT"), stage("MT"));
+        assert_eq!(resolve_stage(&rechtschaffen_kales, "N1"), stage("S1"));
+
+        assert_eq!(resolve_code(&aasm, 4), stage("R"));
+        assert_eq!(resolve_code(&aasm, -1), StageResolution::Unscored);
+        assert_eq!(resolve_code(&aasm, 7), StageResolution::Unknown);
+    }
+
+    #[test]
+    fn checks_alignment_with_epochs() {
+        assert!(is_multiple(0.0, 30.0));
+        assert!(is_multiple(90.0, 30.0));
+        assert!(is_multiple(90.0001, 30.0));
+        assert!(!is_multiple(45.0, 30.0));
+        assert!(!is_multiple(20.0, 30.0));
+    }
+
+    #[test]
+    fn parses_profusion_xml() {
+        let imported = profusion::parse(PROFUSION).unwrap();
+        assert_eq!(imported.epoch_duration, Some(30.0));
+        assert_eq!(imported.start, None);
+        assert_eq!(imported.labels, vec![
+            ImportedLabel { onset: 0.0, duration: 30.0, value: text("W"), channel: None },
+            ImportedLabel { onset: 30.0, duration: 30.0, value: text("1"), channel: None },
+            ImportedLabel { onset: 60.0, duration: 30.0, value: text("R"), channel: None },
+            ImportedLabel { onset: 90.0, duration: 30.0, value: text("?"), channel: None },
+            ImportedLabel { onset: 45.5, duration: 12.0, value: text("Obstructive Apnea"), channel: Some("Flow".to_string()) }
+        ]);
+    }
+
+    #[test]
+    fn reads_plain_hypnograms_with_the_given_epoch_length() {
+        let directory = TestDirectory::new("import-yasa");
+        let path = directory.join("hypnogram.txt");
+        fs::write(&path, "W\nN1\n").unwrap();
+
+        let imported = hypnogram::read_table(&path, ',', 20.0).unwrap();
+        assert_eq!(imported.epoch_duration, None);
+        assert_eq!(imported.labels, vec![
+            ImportedLabel { onset: 0.0, duration: 20.0, value: text("W"), channel: None },
+            ImportedLabel { onset: 20.0, duration: 20.0, value: text("N1"), channel: None }
+        ]);
+    }
+
+    #[test]
+    fn previews_profusion_xml() {
+        let directory = TestDirectory::new("import-profusion");
+        let project = testing::project(&directory, &[("psg.edf", "C3", 0), ("flow.edf", "Flow", 0)]);
+        let path = directory.join("scoring.xml");
+        fs::write(&path, PROFUSION).unwrap();
+
+        let preview = preview(&project, &path).unwrap();
+        assert_eq!(preview.format, ImportFormat::ProfusionXml);
+        assert_eq!(preview.stages, BTreeMap::from([(0, Stage::new("W")), (1, Stage::new("N1")), (2, Stage::new("R"))]));
+        assert_eq!(preview.events, vec![ScoredEvent {
+            event_type: EventType::ObstructiveApnea,
+            source: 1,
+            channel: "Flow".to_string(),
+            onset: 45_500,
+            duration: 12_000
+        }]);
+        assert!(preview.unknown_labels.is_empty());
+        assert_eq!(preview.warnings, vec!["File has no start time, it is aligned with the first epoch of the project"]);
+    }
+
+    #[test]
+    fn warns_about_different_epoch_lengths() {
+        let directory = TestDirectory::new("import-epoch-length");
+        let project = testing::project(&directory, &[("psg.edf", "C3", 0)]);
+        let path = directory.join("scoring.xml");
+        fs::write(&path, PROFUSION.replace("<EpochLength>30", "<EpochLength>20")).unwrap();
+
+        // Epochs get the stage covering their center, the stage from 20 s to 40 s does not cover any center
+        let preview = preview(&project, &path).unwrap();
+        assert_eq!(preview.epoch_duration, Some(20.0));
+        assert_eq!(preview.stages, BTreeMap::from([(0, Stage::new("W")), (1, Stage::new("R"))]));
+        assert_eq!(preview.warnings, vec![
+            "Epoch length of the file is 20 s but the project uses 30 s. Stages are assigned by the center of each epoch.",
+            "File has no start time, it is aligned with the first epoch of the project",
+            "3 stages are not aligned with the epochs of the project",
+            "1 labels are outside of the recording and are skipped",
+            "1 events and markers have no matching channel in the project and are skipped"
+        ]);
+    }
+
+    #[test]
+    fn warns_about_offsets_and_misaligned_stages() {
+        let directory = TestDirectory::new("import-offset");
+        let project = testing::project(&directory, &[("psg.edf", "C3", 0)]);
+        let path = directory.join("scoring.csv");
+        fs::write(&path, "onset,duration,stage,start_time\n0,30,W,2026-02-13T22:30:15\n30,30,N2,\n").unwrap();
+
+        let preview = preview(&project, &path).unwrap();
+        assert_eq!(preview.offset, Some(15.0));
+        assert_eq!(preview.stages, BTreeMap::from([(0, Stage::new("W")), (1, Stage::new("N2"))]));
+        assert_eq!(preview.warnings, vec![
+            "File starts 15.000 s after the first epoch of the project",
+            "2 stages are not aligned with the epochs of the project"
+        ]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::import::{ImportedLabel, ImportedLabels, LabelValue};
+
+// Epoch length used by Profusion in case the file does not state it
+const DEFAULT_EPOCH_LENGTH: f64 = 30.0;
+
+/// Annotation file of Compumedics Profusion (`CMPStudyConfig`) or the NSRR (`PSGAnnotation`).
+/// Both share the structure of the scored events, but Profusion stores the stages as separate list.
+#[derive(Deserialize)]
+struct AnnotationFile {
+    #[serde(rename = "EpochLength")]
+    epoch_length: Option<f64>,
+    #[serde(rename = "ScoredEvents", default)]
+    scored_events: ScoredEvents,
+    #[serde(rename = "SleepStages", default)]
+    sleep_stages: SleepStages
+}
+
+#[derive(Deserialize, Default)]
+struct ScoredEvents {
+    #[serde(rename = "ScoredEvent", default)]
+    values: Vec<ScoredEvent>
+}
+
+#[derive(Deserialize, Default)]
+struct SleepStages {
+    #[serde(rename = "SleepStage", default)]
+    values: Vec<i32>
+}
+
+#[derive(Deserialize)]
+struct ScoredEvent {
+    // Profusion
+    #[serde(rename = "Name")]
+    name: Option<String>,
+    #[serde(rename = "Input")]
+    input: Option<String>,
+
+    // NSRR, the values are formatted as "<name>|<description>" (e.g. "Stages|Stages" and "Stage 2 sleep|2")
+    #[serde(rename = "EventType")]
+    event_type: Option<String>,
+    #[serde(rename = "EventConcept")]
+    event_concept: Option<String>,
+    #[serde(rename = "SignalLocation")]
+    signal_location: Option<String>,
+
+    #[serde(rename = "Start")]
+    start: f64,
+    #[serde(rename = "Duration", default)]
+    duration: f64
+}
+
+/// Reads the stages and scored events of a Profusion or NSRR XML annotation file
+pub fn read(path: &Path) -> Result<ImportedLabels, Box<dyn Error>> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Parses the stages and scored events of the content of a Profusion or NSRR XML annotation file
+pub fn parse(content: &str) -> Result<ImportedLabels, Box<dyn Error>> {
+    let file = serde_xml_rs::from_str::<AnnotationFile>(content)?;
+    let epoch_length = file.epoch_length.unwrap_or(DEFAULT_EPOCH_LENGTH);
+
+    let mut labels = file.sleep_stages.values.iter().enumerate().map(|(i, stage)| ImportedLabel {
+        onset: i as f64 * epoch_length,
+        duration: epoch_length,
+        value: stage_value(*stage),
+        channel: None
+    }).collect::<Vec<_>>();
+
+    for event in file.scored_events.values {
+        let is_stage = event.event_type.as_deref().is_some_and(|t| t.starts_with("Stages"));
+        let concept = event.event_concept.as_deref().map(|c| c.split('|').collect::<Vec<_>>());
+
+        let value = match (is_stage, concept) {
+            // NSRR stages contain the Profusion stage number as description
+            (true, Some(concept)) => match concept.last().and_then(|v| v.trim().parse::<i32>().ok()) {
+                Some(stage) => stage_value(stage),
+                None => LabelValue::Text(concept[0].trim().to_string())
+            },
+            (false, Some(concept)) => LabelValue::Text(concept[0].trim().to_string()),
+            (_, None) => match event.name {
+                Some(name) => LabelValue::Text(name.trim().to_string()),
+                None => continue
+            }
+        };
+
+        // Skip the information about the start of the recording which NSRR stores as event
+        if matches!(&value, LabelValue::Text(text) if text.eq_ignore_ascii_case("Recording Start Time")) {
+            continue;
+        }
+
+        labels.push(ImportedLabel {
+            onset: event.start,
+            duration: event.duration,
+            value,
+            channel: event.input.or(event.signal_location).filter(|c| !c.trim().is_empty())
+        });
+    }
+
+    Ok(ImportedLabels {
+        epoch_duration: Some(epoch_length),
+        start: None,
+        labels
+    })
+}
+
+/// Converts a Profusion stage number (0 = Wake, 1 - 4 = Stage 1 - 4, 5 = REM, 6 = Movement, 9 = Unscored)
+fn stage_value(stage: i32) -> LabelValue {
+    let text = match stage {
+        0 => "W",
+        1..=4 => return LabelValue::Text(stage.to_string()),
+        5 => "R",
+        6 => "Movement time",
+        9 => "?",
+        _ => return LabelValue::Text(format!("Profusion stage {}", stage))
+    };
+
+    LabelValue::Text(text.to_string())
+}
@@ -1,10 +1,11 @@
 use iced::alignment::Vertical;
 use iced::{Element, Length, Padding};
-use iced::widget::{button, column, container, row, space, svg, text};
+use iced::widget::{Column, button, column, container, row, space, svg, text};
 use iced::widget::svg::Handle;
 
 use crate::{CreatePage, ICON, Message, NoctiG, Page, WindowType};
 use crate::layout::create_project::{project_data, project_details, project_processing};
+use crate::storage::project_initializer::ConfigurationField;
 use crate::formatting::{font, theme};
 
 pub fn view<'a>(app: &'a NoctiG, page: &'a CreatePage) -> Element<'a, Message> {
@@ -60,7 +61,7 @@ pub fn view<'a>(app: &'a NoctiG, page: &'a CreatePage) -> Element<'a, Message> {
 
                     button(text("Edit Presets").size(12.0))
                         .style(theme::button_text_secondary)
-                        .on_press(Message::OpenWindow(WindowType::Licenses))
+                        .on_press(Message::OpenPresetEditor)
                         .padding([4.0, 8.0])
                 ]
             ).padding([6.0, 8.0]),
@@ -76,13 +77,15 @@ pub fn view<'a>(app: &'a NoctiG, page: &'a CreatePage) -> Element<'a, Message> {
                 text("Create a new project and import signals from EDF files. Then adjust signal processing and other miscellaneous options for the project.").style(theme::text_secondary).size(14.0),
 
                 match page {
-                    CreatePage::Project => project_details::view(project),
+                    CreatePage::Project => project_details::view(project, &app.project_presets),
                     CreatePage::Data => project_data::view(project),
                     CreatePage::Processing => project_processing::view(project),
                 },
 
                 space().height(16.0),
 
+                view_errors(project.errors_of(&ConfigurationField::General)),
+
                 // Informational footer links
                 row![
                     space().width(Length::Fill),
@@ -104,6 +107,13 @@ pub fn view<'a>(app: &'a NoctiG, page: &'a CreatePage) -> Element<'a, Message> {
     ]).into()
 }
 
+/// Shows the problems of a field of the project configuration below it
+pub fn view_errors<'a>(messages: Vec<&'a str>) -> Element<'a, Message> {
+    Column::from_iter(messages.into_iter().map(|m| text(m).style(theme::text_danger).size(12.0).into()))
+        .spacing(2.0)
+        .into()
+}
+
 fn view_branding<'a>() -> Element<'a, Message>  {
     container(
         row![
@@ -1,12 +1,21 @@
 use iced::alignment::Vertical;
 use iced::{Element, Length, Padding};
-use iced::widget::{Column, button, column, container, row, scrollable, space, text};
+use iced::widget::{Column, button, checkbox, column, container, pick_list, row, scrollable, space, text, text_input, tooltip};
+use iced::widget::tooltip::Position;
 use iced_font_awesome::{fa_icon, fa_icon_solid};
 
 use crate::{Message, ProjectConfiguration, ProjectSignals};
 use crate::formatting::{formatters, theme};
+use crate::layout::create_project::create_viewer::view_errors;
+use crate::signals::channel::ChannelType;
+use crate::storage::project_initializer::ConfigurationField;
 
 pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
+    // Referenced files stay at their location, all others are copied into the project
+    let copies = project.data.iter().filter(|s| !s.is_reference).collect::<Vec<_>>();
+    let copy_size = copies.iter().map(|s| s.size).sum::<u64>();
+    let disk_space = format!("{} of {} files copied into the project, requiring {} of disk space", copies.len(), project.data.len(), formatters::file_size(copy_size));
+
     column![
         space().height(30.0),
 
@@ -27,10 +36,12 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
 
         space().height(8.0),
 
+        view_errors(project.errors_of(&ConfigurationField::Sources)),
+
         container(
             scrollable(
                 // TODO: Add some indicator that no signals are currently imported if data is empty
-                Column::from_iter(project.data.iter().map(view_edf))
+                Column::from_iter(project.data.iter().map(|source| view_edf(source, project.errors_of(&ConfigurationField::Source(source.path.clone())))))
                     .spacing(8.0)
                     .padding(Padding {
                         left: 0.0,
@@ -40,38 +51,136 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
                     })
             )
         ).width(Length::Fill).height(Length::Fill),
+
+        space().height(8.0),
+
+        text(disk_space).style(theme::text_secondary).size(12.0),
     ].into()
 }
 
-fn view_edf<'a>(source: &ProjectSignals) -> Element<'a, Message>  {
+fn view_edf<'a>(source: &ProjectSignals, errors: Vec<&'a str>) -> Element<'a, Message>  {
     let start_time = formatters::date_time_string(source.timestamp);
     let duration = formatters::hms_separate(source.duration as u64);
     let signal_count = source.signal_count;
+    let path = source.path.clone();
+    let channels_path = source.path.clone();
+
+    let channels: Element<'a, Message> = if source.is_expanded {
+        view_channels(source)
+    } else {
+        space().into()
+    };
+
+    let summary = row![
+        space().width(4.0),
+
+        fa_icon("window-maximize").size(16.0),  // TODO: EDF+ symbol
+
+        column![
+            text(source.name.clone()).style(theme::text_primary),
+            text(source.path.clone()).style(theme::text_secondary).size(12.0),    // TODO: Make this ellipsis in case of too small of available space
+            row![
+                text(format!("{} Signals", signal_count)).style(theme::text_secondary).size(12.0),
+                text(start_time).style(theme::text_secondary).size(12.0),
+                text(duration).style(theme::text_secondary).size(12.0),
+                text(formatters::file_size(source.size)).style(theme::text_secondary).size(12.0),
+            ].spacing(16.0),
+            view_errors(errors.clone()),
+        ].spacing(1.0).width(Length::Fill).padding([0.0, 24.0]),
+
+        button(
+            row![
+                fa_icon_solid(if source.is_expanded { "chevron-up" } else { "chevron-down" }).color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0),
+                text("Channels").size(14.0)
+            ].align_y(Vertical::Center).spacing(8.0))
+            .style(theme::button_text)
+            .on_press(Message::ToggleImportSignalChannels(channels_path))
+            .padding([4.0, 8.0]),
+
+        space().width(8.0),
+
+        // Referencing large files saves disk space, but the project breaks in case the files are moved
+        checkbox(source.is_reference)
+            .on_toggle(move |checked| Message::ToggleImportSignalReference(path.clone(), checked))
+            .size(16.0)
+            .spacing(8.0)
+            .style(theme::checkbox)
+            .label("Reference"),
+
+        space().width(8.0),
+
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::RemoveImportSignal(source.path.clone()))
+            .style(theme::button_text),
+    ].align_y(Vertical::Center).padding([4.0, 4.0]);
 
     container(
-        container(row![
-            space().width(4.0),
+        container(column![summary, channels])
+        ).padding([4.0, 16.0])
+        .style(if errors.is_empty() { theme::container_secondary } else { theme::container_secondary_invalid })
+        .into()
+}
 
-            fa_icon("window-maximize").size(16.0),  // TODO: EDF+ symbol
+/// Table of the channels of a source to choose which are shown, how they are named and which type they have
+fn view_channels<'a>(source: &ProjectSignals) -> Element<'a, Message> {
+    let header = row![
+        text("Visible").style(theme::text_secondary).size(12.0).width(Length::Fixed(64.0)),
+        text("Label").style(theme::text_secondary).size(12.0).width(Length::FillPortion(2)),
+        text("Name").style(theme::text_secondary).size(12.0).width(Length::FillPortion(3)),
+        text("Type").style(theme::text_secondary).size(12.0).width(Length::Fixed(120.0)),
+        space().width(Length::Fixed(36.0)),
+    ].spacing(16.0);
 
-            column![
-                text(source.name.clone()).style(theme::text_primary),
-                text(source.path.clone()).style(theme::text_secondary).size(12.0),    // TODO: Make this ellipsis in case of too small of available space
-                row![
-                    text(format!("{} Signals", signal_count)).style(theme::text_secondary).size(12.0),
-                    text(start_time).style(theme::text_secondary).size(12.0),
-                    text(duration).style(theme::text_secondary).size(12.0),
-                ].spacing(16.0)
-            ].spacing(1.0).width(Length::Fill).padding([0.0, 24.0]),
-
-            button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
-                .height(36.0)
+    let rows = source.channels.iter().enumerate().map(|(i, channel)| {
+        let (visible_path, name_path, type_path) = (source.path.clone(), source.path.clone(), source.path.clone());
+
+        // Remembering the name and type adds them to the alias dictionary, so they are applied to all further imports
+        let remember = tooltip(
+            button(fa_icon_solid("bookmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+                .height(28.0)
                 .width(36.0)
-                .padding(12.0)
-                .on_press(Message::RemoveImportSignal(source.path.clone()))
+                .padding([8.0, 12.0])
+                .on_press(Message::SaveImportChannelAlias(source.path.clone(), i))
                 .style(theme::button_text),
-        ].align_y(Vertical::Center).padding([4.0, 4.0]))
-        ).padding([4.0, 16.0])
-        .style(theme::container_secondary)
-        .into()
+            container(
+                text("Remember the name and type for this label in all future imports").size(13.0).center(),
+            ).padding([8.0, 12.0]).style(theme::container_tooltip).max_width(192.0),
+            Position::Left
+        );
+
+        row![
+            container(
+                checkbox(channel.visible)
+                    .on_toggle(move |checked| Message::ToggleImportChannelVisible(visible_path.clone(), i, checked))
+                    .size(16.0)
+                    .style(theme::checkbox)
+            ).width(Length::Fixed(64.0)),
+
+            text(channel.label.clone()).style(theme::text_primary).size(14.0).width(Length::FillPortion(2)),
+
+            text_input(&channel.label, channel.name.as_deref().unwrap_or_default())
+                .style(theme::text_input)
+                .on_input(move |name| Message::ImportChannelNameChanged(name_path.clone(), i, name))
+                .size(14.0)
+                .padding([4.0, 8.0])
+                .width(Length::FillPortion(3)),
+
+            pick_list(ChannelType::ALL, Some(channel.channel_type), move |channel_type| Message::ImportChannelTypeChanged(type_path.clone(), i, channel_type))
+                .text_size(14.0)
+                .padding([4.0, 8.0])
+                .width(Length::Fixed(120.0)),
+
+            remember,
+        ].spacing(16.0).align_y(Vertical::Center).into()
+    });
+
+    column![
+        space().height(4.0),
+        header,
+        Column::from_iter(rows).spacing(4.0),
+        space().height(4.0),
+    ].spacing(6.0).padding([0.0, 44.0]).into()
 }
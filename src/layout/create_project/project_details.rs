@@ -1,11 +1,18 @@
 use iced::alignment::Vertical;
 use iced::{Element, Length, Padding};
-use iced::widget::{Row, button, center, column, container, row, scrollable, space, text, text_input};
+use iced::widget::{Row, button, center, column, container, pick_list, row, scrollable, space, text, text_input};
 use iced_font_awesome::fa_icon_solid;
 
 use crate::{Message, ProjectConfiguration, formatting::theme};
+use crate::layout::create_project::create_viewer::view_errors;
+use crate::storage::preset::ProjectPreset;
+use crate::storage::project_initializer::ConfigurationField;
 
-pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
+pub fn view<'a>(project: &'a ProjectConfiguration, presets: &'a [ProjectPreset]) -> Element<'a, Message> {
+    let name_errors = project.errors_of(&ConfigurationField::Name);
+    let location_errors = project.errors_of(&ConfigurationField::Location);
+    let name_style = if name_errors.is_empty() { theme::text_input } else { theme::text_input_invalid };
+    let location_style = if location_errors.is_empty() { theme::text_input } else { theme::text_input_invalid };
 
     // Build tags
     let tags = Row::from_iter(
@@ -38,10 +45,12 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
                     text("Project Name").size(14.0),
 
                     text_input("", &project.name)
-                        .style(theme::text_input)
+                        .style(name_style)
                         .on_input(Message::ProjectNameChanged)
                         .width(Length::Fill)
                         .padding([8.0, 12.0]),
+
+                    view_errors(name_errors),
                 ].spacing(6.0),
 
                 column![
@@ -49,7 +58,7 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
 
                     row![
                         text_input("", &project.path)
-                            .style(theme::text_input)
+                            .style(location_style)
                             .on_input(Message::ProjectLocationChanged)
                             .width(Length::Fill)
                             .padding([8.0, 12.0]),
@@ -58,7 +67,20 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
                             .style(theme::button_secondary)
                             .on_press(Message::LaunchBrowseProjectLocation)
                             .padding([8.0, 12.0])
-                    ].spacing(8.0)
+                    ].spacing(8.0),
+
+                    view_errors(location_errors),
+                ].spacing(6.0),
+
+                column![
+                    text("Preset").size(14.0),
+
+                    pick_list(presets, project.preset.as_ref(), Message::CreatePresetChanged)
+                        .placeholder("No preset")
+                        .width(Length::Fill)
+                        .padding([8.0, 12.0]),
+
+                    text("Presets define the displayed epochs, the processing options, channel names and types, derived channels, montages and the stage vocabulary of the project").style(theme::text_secondary).size(12.0),
                 ].spacing(6.0),
 
                 column![
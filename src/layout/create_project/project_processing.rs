@@ -1,8 +1,9 @@
 use iced::{Element, Length};
-use iced::widget::{checkbox, column, space};
+use iced::widget::{checkbox, column, pick_list, space, text};
 
 use crate::{Message, ProjectConfiguration};
 use crate::formatting::theme;
+use crate::scoring::stages::StageVocabulary;
 
 pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
     column![
@@ -27,6 +28,14 @@ pub fn view<'a>(project: &'a ProjectConfiguration) -> Element<'a, Message> {
             .style(theme::checkbox)
             .label("Clip to signal range"),
 
+        column![
+            text("Stage vocabulary").size(14.0),
+
+            pick_list(StageVocabulary::presets(), Some(project.stage_vocabulary.clone()), Message::StageVocabularyChanged)
+                .padding([8.0, 12.0])
+                .width(Length::Fixed(256.0)),
+        ].spacing(6.0),
+
         space().height(Length::Fill),
     ].spacing(20.0).into()
 }
\ No newline at end of file
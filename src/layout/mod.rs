@@ -2,3 +2,6 @@ pub mod create_project;
 pub mod scorer;
 pub mod start;
 pub mod license;
+pub mod presets;
+pub mod project_settings;
+pub mod relink;
@@ -0,0 +1,294 @@
+use iced::alignment::Vertical;
+use iced::{Element, Length, Padding};
+use iced::widget::{Column, button, checkbox, column, container, pick_list, row, scrollable, space, text, text_input};
+use iced_font_awesome::fa_icon_solid;
+
+use crate::database::types::ChannelAlias;
+use crate::{Message, NoctiG};
+use crate::formatting::theme;
+use crate::scoring::stages::StageVocabulary;
+use crate::signals::channel::ChannelType;
+use crate::storage::preset::ProjectPreset;
+use crate::storage::project_settings::MAX_CONTEXT_EPOCHS;
+
+pub fn view(app: &NoctiG) -> Element<'_, Message> {
+    let Some(editor) = &app.preset_editor else {
+        return space().into();
+    };
+
+    let presets = Column::from_iter(editor.presets.iter().enumerate().map(|(i, preset)| {
+        let is_selected = editor.selected == Some(i);
+        button(text(preset.name.clone()))
+            .style(move |theme, status| theme::button_current_create_page(theme, status, is_selected))
+            .on_press(Message::PresetSelected(i))
+            .padding(12.0)
+            .width(Length::Fill)
+            .into()
+    })).spacing(4.0);
+
+    let details: Element<'_, Message> = match editor.selected.and_then(|i| editor.presets.get(i)) {
+        Some(preset) => view_preset(preset),
+        None => center_text("Add a new preset or import an exported one")
+    };
+
+    container(column![
+        space().height(12.0),
+        text("Project Presets").size(32.0),
+        space().height(4.0),
+
+        text("Presets are templates for new projects. They define the displayed epochs, the processing options, the names and types of channels, derived channels, montages and the stage vocabulary, so all projects of a team are created with the same configuration.").style(theme::text_secondary).size(14.0),
+
+        space().height(12.0),
+
+        row![
+            column![
+                scrollable(presets).height(Length::Fill),
+
+                row![
+                    button(
+                        row![
+                            fa_icon_solid("plus").size(15.0),
+                            text("New")
+                        ].align_y(Vertical::Center).spacing(12.0))
+                        .style(theme::button_secondary)
+                        .on_press(Message::PresetAdd)
+                        .padding([8.0, 12.0]),
+                    button(
+                        row![
+                            fa_icon_solid("file-import").size(15.0),
+                            text("Import")
+                        ].align_y(Vertical::Center).spacing(12.0))
+                        .style(theme::button_secondary)
+                        .on_press(Message::PresetLaunchImport)
+                        .padding([8.0, 12.0]),
+                ].spacing(8.0),
+            ].spacing(8.0).width(Length::Fixed(256.0)),
+
+            scrollable(details)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ].spacing(32.0).height(Length::Fill),
+
+        space().height(16.0),
+
+        Column::from_iter(editor.errors.iter().map(|e| text(e).style(theme::text_danger).size(13.0).into())).spacing(4.0),
+
+        row![
+            space().width(Length::Fill),
+
+            button(text("Cancel"))
+                .style(theme::button_text)
+                .on_press(Message::ClosePresetEditor)
+                .padding([8.0, 12.0]),
+            button(text("Save"))
+                .style(theme::button_primary)
+                .on_press(Message::SavePresets)
+                .padding([8.0, 12.0]),
+        ].spacing(8.0),
+
+        space().height(12.0)
+    ].spacing(6.0)).width(Length::Fill).height(Length::Fill).padding([12.0, 36.0]).into()
+}
+
+fn center_text<'a>(content: &'a str) -> Element<'a, Message> {
+    container(text(content).style(theme::text_secondary).size(14.0))
+        .center(Length::Fill)
+        .into()
+}
+
+fn view_preset(preset: &ProjectPreset) -> Element<'_, Message> {
+    let counts = (0..=MAX_CONTEXT_EPOCHS).collect::<Vec<_>>();
+
+    column![
+        row![
+            column![
+                text("Preset Name").size(14.0),
+                text_input("", &preset.name)
+                    .style(theme::text_input)
+                    .on_input(Message::PresetNameChanged)
+                    .width(Length::Fill)
+                    .padding([8.0, 12.0]),
+            ].spacing(6.0).width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("file-export").size(15.0),
+                    text("Export")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::PresetLaunchExport)
+                .padding([8.0, 12.0]),
+            button(
+                row![
+                    fa_icon_solid("trash").size(15.0),
+                    text("Delete")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::PresetRemove)
+                .padding([8.0, 12.0]),
+        ].spacing(8.0).align_y(Vertical::Bottom),
+
+        row![
+            column![
+                text("Epochs before current").size(14.0),
+                pick_list(counts.clone(), Some(preset.epochs_before_current), Message::PresetEpochsBeforeChanged)
+                    .padding([8.0, 12.0])
+                    .width(Length::Fixed(128.0)),
+            ].spacing(6.0),
+            column![
+                text("Epochs after current").size(14.0),
+                pick_list(counts, Some(preset.epochs_after_current), Message::PresetEpochsAfterChanged)
+                    .padding([8.0, 12.0])
+                    .width(Length::Fixed(128.0)),
+            ].spacing(6.0),
+            column![
+                text("Stage vocabulary").size(14.0),
+                pick_list(StageVocabulary::presets(), Some(preset.stage_vocabulary.clone()), Message::PresetStageVocabularyChanged)
+                    .padding([8.0, 12.0])
+                    .width(Length::Fixed(256.0)),
+            ].spacing(6.0),
+        ].spacing(48.0),
+
+        row![
+            checkbox(preset.filter_signal)
+                .on_toggle(Message::PresetToggleFilterSignal)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Filter signals"),
+            checkbox(preset.auto_align_signals)
+                .on_toggle(Message::PresetToggleAutoAlignSignals)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Auto align signals"),
+            checkbox(preset.clip_signal)
+                .on_toggle(Message::PresetToggleClipSignal)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Clip to signal range"),
+        ].spacing(32.0),
+
+        view_channel_rules(preset),
+        view_derived_channels(preset),
+        view_montages(preset),
+    ].spacing(28.0).padding(Padding { right: 24.0, ..Default::default() }).into()
+}
+
+fn view_channel_rules(preset: &ProjectPreset) -> Element<'_, Message> {
+    column![
+        row![
+            text("Channel Rules").size(14.0),
+
+            space().width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("plus").size(15.0),
+                    text("Add")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::PresetAddChannelRule)
+                .padding([8.0, 12.0]),
+        ].align_y(Vertical::Bottom),
+
+        text("Channels whose label matches a rule are renamed and typed accordingly when sources are added. The rules take precedence over the alias dictionary.")
+            .style(theme::text_secondary)
+            .size(12.0),
+
+        Column::from_iter(preset.channel_rules.iter().enumerate().map(|(i, rule)| view_channel_rule(i, rule))).spacing(4.0),
+    ].spacing(8.0).into()
+}
+
+fn view_channel_rule(index: usize, rule: &ChannelAlias) -> Element<'_, Message> {
+    row![
+        text_input("Label", &rule.alias)
+            .style(theme::text_input)
+            .on_input(move |label| Message::PresetChannelRuleLabelChanged(index, label))
+            .size(14.0)
+            .padding([4.0, 8.0])
+            .width(Length::FillPortion(1)),
+        text_input("Name", &rule.name)
+            .style(theme::text_input)
+            .on_input(move |name| Message::PresetChannelRuleNameChanged(index, name))
+            .size(14.0)
+            .padding([4.0, 8.0])
+            .width(Length::FillPortion(1)),
+        pick_list(ChannelType::ALL, Some(rule.channel_type), move |channel_type| Message::PresetChannelRuleTypeChanged(index, channel_type))
+            .text_size(14.0)
+            .padding([4.0, 8.0])
+            .width(Length::Fixed(120.0)),
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::PresetRemoveChannelRule(index))
+            .style(theme::button_text),
+    ].spacing(12.0).align_y(Vertical::Center).into()
+}
+
+fn view_derived_channels(preset: &ProjectPreset) -> Element<'_, Message> {
+    column![
+        text("Derived Channels").size(14.0),
+
+        text("Derived channels are taken from a project using \"Save as preset\" in its settings. They are only added to new projects containing all of their channels.")
+            .style(theme::text_secondary)
+            .size(12.0),
+
+        Column::from_iter(preset.derived_channels.iter().enumerate().map(|(i, channel)|
+            view_item(
+                format!("{} = {}", channel.name, channel.expression),
+                channel.channel_type.to_string(),
+                Message::PresetRemoveDerivedChannel(i)
+            )
+        )).spacing(4.0),
+    ].spacing(8.0).into()
+}
+
+fn view_montages(preset: &ProjectPreset) -> Element<'_, Message> {
+    column![
+        row![
+            text("Montages").size(14.0),
+
+            space().width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("file-import").size(15.0),
+                    text("Import")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::PresetLaunchImportMontage)
+                .padding([8.0, 12.0]),
+        ].align_y(Vertical::Bottom),
+
+        text("Montages are taken from a project using \"Save as preset\" in its settings or imported from montage files.")
+            .style(theme::text_secondary)
+            .size(12.0),
+
+        Column::from_iter(preset.montages.iter().enumerate().map(|(i, montage)|
+            view_item(
+                montage.name.clone(),
+                format!("{} Channels", montage.channels.len()),
+                Message::PresetRemoveMontage(i)
+            )
+        )).spacing(4.0),
+    ].spacing(8.0).into()
+}
+
+/// Single entry of a list which can only be removed
+fn view_item<'a>(title: String, details: String, remove: Message) -> Element<'a, Message> {
+    container(row![
+        text(title).style(theme::text_primary).width(Length::Fill),
+        text(details).style(theme::text_secondary).size(12.0),
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(remove)
+            .style(theme::button_text),
+    ].spacing(16.0).align_y(Vertical::Center).padding([4.0, 20.0]))
+        .style(theme::container_secondary)
+        .into()
+}
@@ -0,0 +1,416 @@
+use iced::alignment::Vertical;
+use iced::{Element, Length, Padding};
+use iced::widget::{Column, Row, button, center, checkbox, column, container, pick_list, row, scrollable, space, text, text_input};
+use iced_font_awesome::{fa_icon, fa_icon_solid};
+
+use crate::{Message, NoctiG};
+use crate::formatting::{formatters, theme};
+use crate::signals::channel::ChannelType;
+use crate::signals::derived::DerivedChannel;
+use crate::signals::montage;
+use crate::storage::project_settings::{MAX_CONTEXT_EPOCHS, MontageChannelField, MontageChannelSettings, MontageSettings, ProjectSettings, SourceSettings};
+
+pub fn view(app: &NoctiG) -> Element<'_, Message> {
+    let Some(settings) = &app.project_settings else {
+        return space().into();
+    };
+
+    container(column![
+        space().height(12.0),
+        text("Project Settings").size(32.0),
+        space().height(4.0),
+
+        text("Adjust the details, the displayed epochs, the processing options and the signal sources of the project. All scores are kept when the changes are saved.").style(theme::text_secondary).size(14.0),
+
+        scrollable(
+            column![
+                space().height(8.0),
+                view_details(settings),
+                view_display(settings),
+                view_sources(settings),
+                view_derived_channels(settings),
+                view_montages(settings),
+            ].spacing(28.0).padding(Padding { right: 24.0, ..Default::default() })
+        ).height(Length::Fill),
+
+        space().height(16.0),
+
+        Column::from_iter(settings.errors.iter().map(|e| text(e).style(theme::text_danger).size(13.0).into())).spacing(4.0),
+        Column::from_iter(settings.notice.iter().map(|n| text(n).style(theme::text_secondary).size(13.0).into())),
+        Column::from_iter(settings.is_saving.then(|| text("Reading the added signal sources...").style(theme::text_secondary).size(13.0).into())),
+
+        row![
+            button(text("Save as preset"))
+                .style(theme::button_secondary)
+                .on_press(Message::SettingsSaveAsPreset)
+                .padding([8.0, 12.0]),
+
+            space().width(Length::Fill),
+
+            button(text("Cancel"))
+                .style(theme::button_text)
+                .on_press(Message::CancelProjectSettings)
+                .padding([8.0, 12.0]),
+            button(text("Save"))
+                .style(theme::button_primary)
+                .on_press_maybe((!settings.is_saving).then_some(Message::SaveProjectSettings))
+                .padding([8.0, 12.0]),
+        ].spacing(8.0),
+
+        space().height(12.0)
+    ].spacing(6.0)).width(Length::Fill).height(Length::Fill).padding([12.0, 36.0]).into()
+}
+
+fn view_details(settings: &ProjectSettings) -> Element<'_, Message> {
+    let tags = Row::from_iter(
+        settings.tags.iter().enumerate().map(|(i, tag)|
+            container(
+                row![
+                    text(tag).size(14.0),
+                    button(center("\u{00D7}").padding(Padding{top: -2.0, ..Default::default()}))
+                        .on_press(Message::SettingsRemoveTag(i))
+                        .width(Length::Fixed(28.0))
+                        .height(Length::Fixed(28.0))
+                        .padding(0)
+                        .style(theme::button_secondary)
+                ].spacing(4.0).align_y(Vertical::Center)
+            ).padding(Padding { left: 8.0, ..Default::default() }).style(theme::container_tag).into()
+        )
+    );
+
+    column![
+        column![
+            text("Project Name").size(14.0),
+
+            text_input("", &settings.name)
+                .style(theme::text_input)
+                .on_input(Message::SettingsNameChanged)
+                .width(Length::Fill)
+                .padding([8.0, 12.0]),
+        ].spacing(6.0),
+
+        column![
+            text("Tags").size(14.0),
+
+            row![
+                text_input("", &settings.new_tag)
+                    .style(theme::text_input)
+                    .on_input(Message::SettingsNewTagChanged)
+                    .on_submit(Message::SettingsAddTag)
+                    .width(Length::Fill)
+                    .padding([8.0, 12.0]),
+
+                button(fa_icon_solid("plus").size(14.0))
+                    .style(theme::button_secondary)
+                    .on_press(Message::SettingsAddTag)
+                    .padding([12.0, 12.0])
+            ].spacing(8.0),
+
+            space(),
+
+            tags.spacing(8.0).wrap()
+        ].spacing(6.0),
+    ].spacing(28.0).into()
+}
+
+fn view_display(settings: &ProjectSettings) -> Element<'_, Message> {
+    let counts = (0..=MAX_CONTEXT_EPOCHS).collect::<Vec<_>>();
+
+    row![
+        column![
+            text("Epochs before current").size(14.0),
+            pick_list(counts.clone(), Some(settings.epochs_before_current), Message::SettingsEpochsBeforeChanged)
+                .padding([8.0, 12.0])
+                .width(Length::Fixed(128.0)),
+        ].spacing(6.0),
+        column![
+            text("Epochs after current").size(14.0),
+            pick_list(counts, Some(settings.epochs_after_current), Message::SettingsEpochsAfterChanged)
+                .padding([8.0, 12.0])
+                .width(Length::Fixed(128.0)),
+        ].spacing(6.0),
+
+        column![
+            checkbox(settings.filter_signal)
+                .on_toggle(Message::SettingsToggleFilterSignal)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Filter signals"),
+            checkbox(settings.auto_align_signals)
+                .on_toggle(Message::SettingsToggleAutoAlignSignals)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Auto align signals"),
+            checkbox(settings.clip_signal)
+                .on_toggle(Message::SettingsToggleClipSignal)
+                .size(19.0)
+                .spacing(12.0)
+                .style(theme::checkbox)
+                .label("Clip to signal range"),
+        ].spacing(12.0),
+    ].spacing(48.0).into()
+}
+
+fn view_sources(settings: &ProjectSettings) -> Element<'_, Message> {
+    column![
+        row![
+            text("Sources").size(14.0),
+
+            space().width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("plus").size(15.0),
+                    text("Add")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::SettingsLaunchAddSource)
+                .padding([8.0, 12.0]),
+        ].align_y(Vertical::Bottom),
+
+        Column::from_iter(settings.sources.iter().enumerate().map(|(i, source)| view_source(i, source))).spacing(8.0),
+    ].spacing(8.0).into()
+}
+
+fn view_source(index: usize, source: &SourceSettings) -> Element<'_, Message> {
+    let signals = &source.signals;
+
+    // New sources can either be copied into the project or referenced at their current location
+    let storage: Element<'_, Message> = if source.is_new() {
+        checkbox(signals.is_reference)
+            .on_toggle(move |checked| Message::SettingsToggleSourceReference(index, checked))
+            .size(16.0)
+            .spacing(8.0)
+            .style(theme::checkbox)
+            .label("Reference")
+            .into()
+    } else {
+        text(if signals.is_reference { "Referenced" } else { "Copied" }).style(theme::text_secondary).size(12.0).into()
+    };
+
+    container(row![
+        fa_icon("window-maximize").size(16.0),
+
+        column![
+            row![
+                text(signals.name.clone()).style(theme::text_primary),
+                text(if source.is_new() { "New" } else { "" }).style(theme::text_secondary).size(12.0),
+            ].spacing(8.0).align_y(Vertical::Center),
+            text(signals.path.clone()).style(theme::text_secondary).size(12.0),
+            row![
+                text(format!("{} Signals", signals.signal_count)).style(theme::text_secondary).size(12.0),
+                text(formatters::date_time_string(signals.timestamp)).style(theme::text_secondary).size(12.0),
+                text(formatters::hms_separate(signals.duration as u64)).style(theme::text_secondary).size(12.0),
+                text(formatters::file_size(signals.size)).style(theme::text_secondary).size(12.0),
+            ].spacing(16.0)
+        ].spacing(1.0).width(Length::Fill).padding([0.0, 20.0]),
+
+        storage,
+
+        column![
+            text("Offset (ms)").style(theme::text_secondary).size(12.0),
+            text_input("0", &source.offset)
+                .style(theme::text_input)
+                .on_input(move |offset| Message::SettingsSourceOffsetChanged(index, offset))
+                .size(14.0)
+                .width(120.0)
+                .padding([4.0, 8.0]),
+        ].spacing(2.0),
+
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::SettingsRemoveSource(index))
+            .style(theme::button_text),
+    ].spacing(16.0).align_y(Vertical::Center).padding([8.0, 20.0]))
+        .style(theme::container_secondary)
+        .into()
+}
+
+fn view_derived_channels(settings: &ProjectSettings) -> Element<'_, Message> {
+    column![
+        row![
+            text("Derived Channels").size(14.0),
+
+            space().width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("plus").size(15.0),
+                    text("Add")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::SettingsAddDerivedChannel)
+                .padding([8.0, 12.0]),
+        ].align_y(Vertical::Bottom),
+
+        text("Channels are referenced by their label or name in square brackets, optionally with the number of their source (e.g. [2:C4]). Expressions support +, -, *, /, parentheses, numbers and avg(...), e.g. [C4] - avg([M1], [M2]).")
+            .style(theme::text_secondary)
+            .size(12.0),
+
+        Column::from_iter(settings.derived_channels.iter().enumerate().map(|(i, channel)| view_derived_channel(i, channel))).spacing(8.0),
+    ].spacing(8.0).into()
+}
+
+fn view_derived_channel(index: usize, channel: &DerivedChannel) -> Element<'_, Message> {
+    container(row![
+        column![
+            text("Name").style(theme::text_secondary).size(12.0),
+            text_input("C4-M1", &channel.name)
+                .style(theme::text_input)
+                .on_input(move |name| Message::SettingsDerivedNameChanged(index, name))
+                .size(14.0)
+                .padding([4.0, 8.0]),
+        ].spacing(2.0).width(Length::FillPortion(1)),
+
+        column![
+            text("Expression").style(theme::text_secondary).size(12.0),
+            text_input("[C4] - [M1]", &channel.expression)
+                .style(theme::text_input)
+                .on_input(move |expression| Message::SettingsDerivedExpressionChanged(index, expression))
+                .size(14.0)
+                .padding([4.0, 8.0]),
+        ].spacing(2.0).width(Length::FillPortion(3)),
+
+        column![
+            text("Type").style(theme::text_secondary).size(12.0),
+            pick_list(ChannelType::ALL, Some(channel.channel_type), move |channel_type| Message::SettingsDerivedTypeChanged(index, channel_type))
+                .text_size(14.0)
+                .padding([4.0, 8.0])
+                .width(Length::Fixed(120.0)),
+        ].spacing(2.0),
+
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::SettingsRemoveDerivedChannel(index))
+            .style(theme::button_text),
+    ].spacing(16.0).align_y(Vertical::Bottom).padding([8.0, 20.0]))
+        .style(theme::container_secondary)
+        .into()
+}
+
+fn view_montages(settings: &ProjectSettings) -> Element<'_, Message> {
+    let references = settings.channel_references();
+
+    column![
+        row![
+            text("Montages").size(14.0),
+
+            space().width(Length::Fill),
+
+            button(
+                row![
+                    fa_icon_solid("file-import").size(15.0),
+                    text("Import")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::SettingsLaunchImportMontage)
+                .padding([8.0, 12.0]),
+            button(
+                row![
+                    fa_icon_solid("plus").size(15.0),
+                    text("Add")
+                ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::SettingsAddMontage)
+                .padding([8.0, 12.0]),
+        ].spacing(8.0).align_y(Vertical::Bottom),
+
+        text("Montages show the selected channels in the given order with their own gain (µV above and below the baseline), filter (Hz, 0 disables it) and color. Empty values keep the defaults of the channel type. Switch between montages with Tab while scoring.")
+            .style(theme::text_secondary)
+            .size(12.0),
+
+        Column::from_iter(settings.montages.iter().enumerate().map(|(i, montage)| view_montage(i, montage, references.clone()))).spacing(8.0),
+    ].spacing(8.0).into()
+}
+
+fn view_montage(index: usize, montage: &MontageSettings, references: Vec<String>) -> Element<'_, Message> {
+    container(column![
+        row![
+            text_input("Name", &montage.name)
+                .style(theme::text_input)
+                .on_input(move |name| Message::SettingsMontageNameChanged(index, name))
+                .size(14.0)
+                .padding([4.0, 8.0])
+                .width(Length::Fill),
+
+            pick_list(references, None::<String>, move |channel| Message::SettingsAddMontageChannel(index, channel))
+                .placeholder("Add channel")
+                .text_size(14.0)
+                .padding([4.0, 8.0])
+                .width(Length::Fixed(180.0)),
+
+            button(fa_icon_solid("file-export").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+                .height(36.0)
+                .width(36.0)
+                .padding(12.0)
+                .on_press(Message::SettingsLaunchExportMontage(index))
+                .style(theme::button_text),
+            button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+                .height(36.0)
+                .width(36.0)
+                .padding(12.0)
+                .on_press(Message::SettingsRemoveMontage(index))
+                .style(theme::button_text),
+        ].spacing(16.0).align_y(Vertical::Center),
+
+        Column::from_iter(montage.channels.iter().enumerate().map(|(i, channel)| view_montage_channel(index, i, channel))).spacing(4.0),
+    ].spacing(8.0).padding([8.0, 20.0]))
+        .style(theme::container_secondary)
+        .into()
+}
+
+fn view_montage_channel(montage: usize, index: usize, channel: &MontageChannelSettings) -> Element<'_, Message> {
+    let field = |name: &'static str, placeholder: &'static str, value: &str, field: MontageChannelField| column![
+        text(name).style(theme::text_secondary).size(12.0),
+        text_input(placeholder, value)
+            .style(theme::text_input)
+            .on_input(move |value| Message::SettingsMontageChannelChanged(montage, index, field, value))
+            .size(14.0)
+            .padding([4.0, 8.0]),
+    ].spacing(2.0).width(Length::Fixed(96.0));
+
+    // Preview of the entered color
+    let color = montage::parse_color(&channel.color);
+    let swatch = container(space().width(16.0).height(16.0)).style(move |_| container::Style {
+        background: color.map(Into::into),
+        border: iced::Border { color: theme::CLEAR_DARK_TEXT_SECONDARY, width: if color.is_some() { 0.0 } else { 1.0 }, radius: 4.0.into() },
+        ..Default::default()
+    });
+
+    row![
+        text(channel.channel.clone()).width(Length::Fill),
+
+        field("Gain (µV)", "Default", &channel.gain, MontageChannelField::Gain),
+        field("High-pass (Hz)", "Default", &channel.high_pass, MontageChannelField::HighPass),
+        field("Low-pass (Hz)", "Default", &channel.low_pass, MontageChannelField::LowPass),
+        row![
+            field("Color", "#4275DB", &channel.color, MontageChannelField::Color),
+            swatch,
+        ].spacing(8.0).align_y(Vertical::Bottom),
+
+        button(fa_icon_solid("chevron-up").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::SettingsMoveMontageChannel(montage, index, -1))
+            .style(theme::button_text),
+        button(fa_icon_solid("chevron-down").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::SettingsMoveMontageChannel(montage, index, 1))
+            .style(theme::button_text),
+        button(fa_icon_solid("xmark").color(theme::CLEAR_DARK_TEXT_SECONDARY).size(12.0))
+            .height(36.0)
+            .width(36.0)
+            .padding(12.0)
+            .on_press(Message::SettingsRemoveMontageChannel(montage, index))
+            .style(theme::button_text),
+    ].spacing(12.0).align_y(Vertical::Bottom).into()
+}
@@ -0,0 +1,109 @@
+use iced::alignment::Vertical;
+use iced::{Element, Length};
+use iced::widget::{Column, button, column, container, row, scrollable, space, text};
+use iced_font_awesome::fa_icon_solid;
+
+use crate::{Message, NoctiG};
+use crate::formatting::{formatters, theme};
+use crate::storage::relink::RelinkSource;
+
+pub fn view(app: &NoctiG) -> Element<'_, Message> {
+    let Some(relink) = &app.relink else {
+        return space().into();
+    };
+
+    let status = if relink.is_searching {
+        "Searching..."
+    } else if relink.is_resolved() {
+        "All signal sources were found"
+    } else {
+        "Some signal sources are still missing"
+    };
+
+    container(column![
+        space().height(12.0),
+        text("Missing Signal Sources").size(32.0),
+        space().height(4.0),
+
+        text(format!("Some signal sources of {} were moved or deleted. The files are searched in the directories below and verified by their content, so renamed files are found as well.", relink.project_file.display())).style(theme::text_secondary).size(14.0),
+
+        space().height(20.0),
+
+        scrollable(
+            column![
+                Column::from_iter(relink.sources.iter().map(view_source)).spacing(8.0),
+
+                space().height(12.0),
+
+                row![
+                    text("Search directories").size(14.0),
+                    space().width(Length::Fill),
+                    button(
+                        row![
+                            fa_icon_solid("plus").size(15.0),
+                            text("Add")
+                        ].align_y(Vertical::Center).spacing(12.0))
+                        .style(theme::button_secondary)
+                        .on_press_maybe((!relink.is_searching).then_some(Message::RelinkLaunchAddDirectory))
+                        .padding([8.0, 12.0]),
+                ].align_y(Vertical::Bottom),
+
+                Column::from_iter(relink.directories.iter().map(|d| text(d.display().to_string()).style(theme::text_secondary).size(13.0).into())).spacing(4.0),
+            ].spacing(8.0)
+        ).height(Length::Fill),
+
+        space().height(16.0),
+
+        Column::from_iter(relink.errors.iter().map(|e| text(e).style(theme::text_danger).size(13.0).into())).spacing(4.0),
+
+        row![
+            text(status).style(theme::text_secondary).size(14.0),
+
+            space().width(Length::Fill),
+
+            button(text("Cancel"))
+                .style(theme::button_text)
+                .on_press(Message::CancelRelink)
+                .padding([8.0, 12.0]),
+            button(text("Search again"))
+                .style(theme::button_secondary)
+                .on_press_maybe((!relink.is_searching && !relink.is_resolved()).then_some(Message::RelinkSearch))
+                .padding([8.0, 12.0]),
+            button(text("Open project"))
+                .style(theme::button_primary)
+                .on_press_maybe((!relink.is_searching && relink.is_resolved()).then_some(Message::ApplyRelink))
+                .padding([8.0, 12.0]),
+        ].spacing(8.0).align_y(Vertical::Center),
+
+        space().height(12.0)
+    ].spacing(6.0)).width(Length::Fill).height(Length::Fill).padding([12.0, 36.0]).into()
+}
+
+fn view_source(source: &RelinkSource) -> Element<'_, Message> {
+    let details = [
+        source.source.size.map(formatters::file_size),
+        source.source.hash.as_ref().map(|h| format!("MD5 {}", h))
+    ].into_iter().flatten().collect::<Vec<_>>().join("   ");
+
+    let (icon, location) = match &source.found {
+        Some(path) => ("check", format!("Found at {}", path.display())),
+        None => ("xmark", "Not found".to_string())
+    };
+
+    container(row![
+        fa_icon_solid(icon).size(16.0),
+
+        column![
+            text(source.source.path.clone()).style(theme::text_primary),
+            text(location).style(theme::text_secondary).size(12.0),
+            text(details).style(theme::text_secondary).size(12.0),
+        ].spacing(1.0).width(Length::Fill),
+
+        button(text("Browse").size(14.0))
+            .style(theme::button_secondary)
+            .on_press(Message::RelinkLaunchBrowse(source.index))
+            .padding([4.0, 8.0]),
+    ].spacing(20.0).align_y(Vertical::Center).padding([8.0, 20.0]))
+        .style(theme::container_secondary)
+        .into()
+}
@@ -1,20 +1,45 @@
 use iced::widget::svg::Handle;
-use iced::widget::{Canvas, Column, Id, Row, Space, center, column, container, row, scrollable, shader, space, stack, svg, text};
-use iced::{Alignment, Element, Length, Padding};
+use iced::widget::{Canvas, Column, Id, Row, Space, button, center, column, container, mouse_area, pick_list, row, scrollable, shader, space, stack, svg, text, text_input};
+use iced::{Alignment, Element, Length, Padding, mouse};
 use iced::alignment::Vertical;
+use std::ops::Range;
 use std::sync::LazyLock;
 use itertools::Itertools;   // Required until `intersperse_with` is stabilized
 
 use crate::formatting::font::REGULAR_BOLD;
 use crate::formatting::theme::SPECTROGRAM_BORDER_WIDTH;
 use crate::{CurrentProject, ICON_SECONDARY, key_legend};
-use crate::{Message, NoctiG, Stage};
+use crate::{Message, NoctiG};
+use crate::scoring::stages::{Stage, StageVocabulary};
+use crate::scoring::events::EventType;
+use crate::scoring::consensus::Adjudication;
 use crate::formatting::{formatters, theme};
-use crate::views::line_chart::Liner;
+use crate::views::line_chart::{EventOverlay, Liner};
+use crate::views::hypnogram::Hypnogram;
+use crate::export::ExportFormat;
+use crate::import::ImportPreview;
+use crate::storage::epoch_reader::{ChartSignal, EpochReader};
+use crate::storage::project_settings::MAX_CONTEXT_EPOCHS;
+use crate::signals::channel::ChannelType;
+use crate::signals::scaling::{AmplitudeScale, ChannelKey, ChannelViewChange};
+use crate::statistics::sleep_stats::{AnalysisPeriod, SleepStatistics};
+use crate::statistics::agreement::Agreement;
+use crate::Scorings;
 
 static LOG_SCROLLER_ID: LazyLock<Id> = LazyLock::new(Id::unique);
 pub const SIGNAL_PADDING_VERTICAL: f32 = 12.0;
 
+// Amount of scrolled pixels (e.g. on trackpads) which are treated like a single line of a mouse wheel
+const SCROLL_PIXELS_PER_LINE: f32 = 40.0;
+
+/// Converts the given scroll delta to a horizontal and vertical amount of lines
+pub fn scroll_delta_lines(delta: mouse::ScrollDelta) -> (f32, f32) {
+    match delta {
+        mouse::ScrollDelta::Lines { x, y } => (x, y),
+        mouse::ScrollDelta::Pixels { x, y } => (x / SCROLL_PIXELS_PER_LINE, y / SCROLL_PIXELS_PER_LINE)
+    }
+}
+
 pub fn view(app: &NoctiG) -> Element<'_, Message> {
     let Some(project) = &app.current_project else {
         return space().into();
@@ -22,35 +47,55 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
     let Some(scorings) = &project.scorings else {
         return space().into();
     };
+    let vocabulary = &project.project.stage_vocabulary;
 
     let spectrogram_view = view_spectrogram(&project);
 
-    let mut index = 0;
-    let signals = Column::from_vec(
-        project.readers.iter().map(|reader| {
-            let base_index = index;
-            index += reader.signal_count();
+    let period = project.analysis_period();
+    let statistics_view: Element<'_, Message> = if app.is_showing_statistics {
+        view_statistics(project, scorings, &period)
+    } else {
+        space().into()
+    };
 
-            Column::from_iter(
-                reader.get_chart_signals().into_iter().map(|signal|
-                    Liner::from_chart_signal(signal, base_index, app.draw_ranges, project.project.epochs_before_current, project.project.epochs_after_current)).map(|l|
-                        Canvas::new(l)
-                            .width(Length::Fill)
-                            .height(Length::Fixed(100.0 + 2.0 * SIGNAL_PADDING_VERTICAL))
-                            .into()
-                )
-            ).into()
-        }
-    ).collect::<Vec<_>>()).width(Length::Fill);
+    let comparison_view: Element<'_, Message> = if let Some(preview) = &app.import_preview {
+        view_import_preview(project, preview)
+    } else if let Some(adjudication) = &app.adjudication {
+        view_adjudication(project, scorings, adjudication)
+    } else if app.is_showing_comparison {
+        view_comparison(app, project, scorings, &period)
+    } else {
+        space().into()
+    };
+
+    let epoch_millis = EpochReader::EPOCH_DURATION as i64 * 1_000;
+    let current_seg_n = project.current_epoch();
+    let window_start = (current_seg_n as i64 - project.context_epochs().0 as i64) * epoch_millis;
+    let period_millis = period.epochs.start as i64 * epoch_millis..period.epochs.end as i64 * epoch_millis;
+
+    let hypnogram = Canvas::new(Hypnogram::new(scorings, vocabulary, project.epoch_count(), current_seg_n, period.clone()))
+        .width(Length::Fill)
+        .height(Length::Fixed(48.0));
+
+    let signals = if project.active_montage().is_some() {
+        view_montage_signals(app, project, window_start, &period_millis)
+    } else {
+        view_signals(app, project, window_start, &period_millis)
+    };
 
     let default_reader = project.readers.iter().max_by(|r1, r2| r1.get_epoch_count().cmp(&r2.get_epoch_count())).unwrap();
 
-    let current_seg_n = default_reader.get_window_start_epoch();
-    let start_segment = current_seg_n.saturating_sub(project.project.epochs_before_current as u64);
-    let end_segment = current_seg_n + project.project.epochs_after_current as u64 + 1;
+    // Only the current epoch is visible while zoomed into a part of it
+    let (epochs_before, epochs_after) = match project.session.zoom {
+        Some(_) => (0, 0),
+        None => project.context_epochs()
+    };
+
+    let start_segment = current_seg_n.saturating_sub(epochs_before as u64);
+    let end_segment = current_seg_n + epochs_after as u64 + 1;
 
     // Get the amount of visible offset placeholder epochs
-    let underflow = (project.project.epochs_before_current as u64).saturating_sub(current_seg_n);
+    let underflow = (epochs_before as u64).saturating_sub(current_seg_n);
     let max_epoch = default_reader.get_epoch_count();
 
     // TODO: The start timestamp has to be taken from the least offset reader (if there are multiple,
@@ -58,7 +103,13 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
     // to clock drift, etc) there might be a trusted source file too though and then we would have to calculate
     // the actual start timestamp by reducing the timestamp by the offset between the earlist record and the selected one)
     // Potentially prompt the user after first opening the project (only when there are multiple different timestamps) which one to use
-    let time_frame = default_reader.current_timeframe();
+    let time_frame = match project.session.zoom {
+        Some(_) => {
+            let (view_start, view_duration) = project.visible_span();
+            (view_start as u64 / 1_000, (view_start + view_duration) as u64 / 1_000)
+        }
+        None => default_reader.current_timeframe()
+    };
     let start_time = default_reader.start_timestamp();
     let current_timespan = formatters::TIME_FORMATTERS.get(app.window_time_formatter_index).unwrap()(start_time, time_frame.0, time_frame.1);
 
@@ -67,15 +118,53 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
             return space().width(Length::FillPortion((end_segment - start_segment) as u16)).into();
         }
 
-        let stage = scorings.values.get(&(i - underflow)).cloned().unwrap_or(Stage::Unset);
+        let stage = scorings.values.get(&(i - underflow));
+
+        let cell = container(
+            text(vocabulary.label(stage)).style(vocabulary.foreground(stage)).size(20.0)
+        ).style(vocabulary.background(stage)).padding([4.0, 0.0]).width(Length::Fill).align_x(Alignment::Center);
+
+        // Shade epochs outside of the analysis period
+        let cell: Element<'_, Message> = if period.epochs.contains(&(i - underflow)) {
+            cell.into()
+        } else {
+            stack![
+                cell,
+                container(space()).style(theme::container_outside_period).width(Length::Fill).height(Length::Fill)
+            ].into()
+        };
 
         container(
-            container(
-                text(stage.map_str()).style(Stage::foreground(stage.clone())).size(20.0)
-            ).style(Stage::background(stage)).padding([4.0, 0.0]).width(Length::Fill).align_x(Alignment::Center)
+            mouse_area(cell).on_press(Message::SelectEpoch(i - underflow)).interaction(mouse::Interaction::Pointer)
         ).padding([0.0, 16.0]).width(Length::FillPortion((end_segment - start_segment) as u16)).into()
     }));
 
+    // Scrolling on the stages row navigates through the epochs
+    let stages = mouse_area(stages.width(Length::Fill))
+        .on_scroll(|delta| Message::ScrollAxis(-scroll_delta_lines(delta).1));
+
+    // Stage picker floating above the current epoch
+    let stage_picker: Element<'_, Message> = if app.stage_picker == Some(current_seg_n) {
+        row![
+            space().width(Length::FillPortion(epochs_before as u16)),
+            container(view_stage_picker(vocabulary)).padding([0.0, 16.0]).width(Length::FillPortion(1)),
+            space().width(Length::FillPortion(epochs_after as u16)),
+        ].into()
+    } else if app.selection.is_some() {
+        row![
+            space().width(Length::Fill),
+            view_event_picker(),
+            space().width(Length::Fill),
+        ].into()
+    } else {
+        space().into()
+    };
+
+    let rating_keys = vocabulary.stages.iter()
+        .map(|s| s.key.to_uppercase())
+        .chain(["Del".to_string()])
+        .collect::<Vec<_>>();
+
     column![
         row![
             // when hovered -> Primary-Text-Color --> when clicked --> Open Menu
@@ -88,6 +177,10 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
 
             space().width(Length::Fill),
 
+            view_channel_controls(app, project),
+
+            view_project_controls(app, project),
+
             // TODO: Add integrated windowing buttons
         ]
         .align_y(Vertical::Center)
@@ -102,6 +195,8 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
                 .style(theme::container_spectrogram)
                 .width(Length::Fill)
                 .height(Length::Fill),
+
+            view_spectrogram_channel(project),
         ]).padding(Padding {
             left: 24.0,
             right: 24.0,
@@ -110,6 +205,8 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
         })
         .height(Length::Fixed(256.0)),
 
+        container(hypnogram).padding([0.0, 24.0]),
+
         Space::new().height(12.0),
 
         stack!(
@@ -122,9 +219,17 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
                 ]
             ).id(LOG_SCROLLER_ID.clone()).anchor_top().width(Length::Fill).height(Length::Fill),
 
+            row![
+                comparison_view,
+                space().width(Length::Fill),
+                statistics_view,
+            ].padding(Padding { left: 24.0, top: 0.0, right: 24.0, bottom: 24.0 + 32.0 + 12.0 }),
+
             column![
                 Space::new().height(Length::Fill),
-                stages.width(Length::Fill),
+                stage_picker,
+                Space::new().height(6),
+                stages,
                 Space::new().height(12),
             ].width(Length::Fill),
         ).width(Length::Fill),
@@ -140,23 +245,564 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
 
                     // Legend of most relevant shortcuts
                     row![
-                        key_legend!(["W", "R", "1", "2", "3", "Del"], "Set rating"),
+                        key_legend!(dynamic rating_keys, "Set rating"),
                         key_legend!(["⏴", "⏵"], "Move axis"),
+                        key_legend!("Esc", "Clear selection"),
                         key_legend!("T", "Time format"),
+                        key_legend!("Tab", "Montage"),
+                        key_legend!(["+", "−"], "Gain"),
+                        key_legend!(["K", "B"], "Grid"),
+                        key_legend!("Z", "Zoom"),
+                        key_legend!("I", "Statistics"),
+                        key_legend!("C", "Compare scorers"),
                         key_legend!("H", "Help"),
                     ].spacing(16.0).align_y(Vertical::Center),
 
                     // Space divider
                     Space::new().width(Length::Fill),
 
+                    view_status(project),
+
+                    view_time_span_controls(project),
+
                     // Current time fragment
                     text(current_timespan).style(theme::text_secondary).size(18.0)
-                ].padding(Padding { left: 8.0, top: 6.0, right: 8.0, bottom: 6.0 })
+                ].spacing(16.0).align_y(Vertical::Center).padding(Padding { left: 8.0, top: 6.0, right: 8.0, bottom: 6.0 })
             ]
         ).style(theme::status_bar)
     ].width(Length::Fill).into()
 }
 
+/// Signals of all visible channels of the sources in file order, followed by the derived channels
+fn view_signals<'a>(app: &'a NoctiG, project: &'a CurrentProject, window_start: i64, period_millis: &Range<i64>) -> Column<'a, Message> {
+    let mut index = 0;
+    let signals = Column::from_vec(
+        project.readers.iter().enumerate().map(|(source, reader)| {
+            let base_index = index;
+            index += reader.signal_count();
+
+            Column::from_iter(
+                reader.get_chart_signals().into_iter().map(|signal|
+                    view_signal(app, project, Some(source), signal, base_index, window_start, period_millis)
+                )
+            ).into()
+        }
+    ).collect::<Vec<_>>()).width(Length::Fill);
+
+    // Derived channels are shown below the channels of all sources
+    signals.push(Column::from_iter(
+        project.derived_signals.iter().cloned().map(|signal|
+            view_signal(app, project, None, signal, index, window_start, period_millis)
+        )
+    ))
+}
+
+/// Signals of the channels of the active montage in the order of the montage
+fn view_montage_signals<'a>(app: &'a NoctiG, project: &'a CurrentProject, window_start: i64, period_millis: &Range<i64>) -> Column<'a, Message> {
+    Column::from_iter(
+        // The index of the signal already is the index across all readers
+        project.montage_signals.iter().cloned().map(|(source, signal)|
+            view_signal(app, project, source, signal, 0, window_start, period_millis)
+        )
+    ).width(Length::Fill)
+}
+
+/// Row of a single signal of the given source (none for derived channels) with the scaling, polarity and height of its channel
+fn view_signal<'a>(app: &'a NoctiG, project: &'a CurrentProject, source: Option<usize>, signal: ChartSignal, base_index: usize, window_start: i64, period_millis: &Range<i64>) -> Element<'a, Message> {
+    let view = project.channel_view(&CurrentProject::signal_key(source, &signal));
+
+    // Derived channels do not have any events
+    let events = source.map(|source| channel_events(project, source, &signal.label)).unwrap_or_default();
+    let is_focused = app.focused_signal == Some(base_index + signal.index);
+    let signal = project.scaled_signal(signal, &view);
+    let (view_start, view_duration) = project.visible_span();
+    let (epochs_before, epochs_after) = project.context_epochs();
+
+    Canvas::new(
+        Liner::from_chart_signal(signal, base_index, app.draw_ranges, epochs_before, epochs_after)
+            .with_window_start(window_start)
+            .with_analysis_period(period_millis.clone())
+            .with_selection(app.selection.clone())
+            .with_events(events)
+            .with_inverted(view.inverted)
+            .with_focus(is_focused)
+            .with_grid(project.session.grid)
+            .with_view(view_start, view_duration)
+    )
+        .width(Length::Fill)
+        .height(Length::Fixed(view.height + 2.0 * SIGNAL_PADDING_VERTICAL))
+        .into()
+}
+
+/// Result of the last action (e.g. an export), if any
+fn view_status(project: &CurrentProject) -> Element<'_, Message> {
+    let Some(status) = &project.status else {
+        return space().into();
+    };
+
+    let style = if status.is_error { theme::text_danger } else { theme::text_secondary };
+    text(&status.text).style(style).size(14.0).into()
+}
+
+/// Visible time span and the amount of context epochs before and after the current epoch
+fn view_time_span_controls(project: &CurrentProject) -> Element<'_, Message> {
+    let counts = (0..=MAX_CONTEXT_EPOCHS).collect::<Vec<_>>();
+    let (before, after) = project.context_epochs();
+
+    row![
+        text("Span").style(theme::text_secondary).size(14.0),
+        pick_list(project.time_spans(), project.time_span(), Message::SetTimeSpan)
+            .placeholder("Custom")
+            .text_size(14.0)
+            .padding([2.0, 8.0])
+            .width(Length::Fixed(96.0)),
+        text("Before").style(theme::text_secondary).size(14.0),
+        pick_list(counts.clone(), Some(before), move |before| Message::SetContextEpochs(before, after))
+            .text_size(14.0)
+            .padding([2.0, 8.0])
+            .width(Length::Fixed(56.0)),
+        text("After").style(theme::text_secondary).size(14.0),
+        pick_list(counts, Some(after), move |after| Message::SetContextEpochs(before, after))
+            .text_size(14.0)
+            .padding([2.0, 8.0])
+            .width(Length::Fixed(56.0)),
+    ].spacing(8.0).align_y(Vertical::Center).into()
+}
+
+/// Scaling, polarity and height of the focused channel. Only shown while a channel is focused.
+fn view_channel_controls<'a>(app: &'a NoctiG, project: &'a CurrentProject) -> Element<'a, Message> {
+    let Some(key) = app.focused_signal.and_then(|index| project.channel_key(index)) else {
+        return space().into();
+    };
+    let view = project.channel_view(&key);
+    let name = match &key {
+        ChannelKey::Source { source, label } => project.readers[*source].get_channels().iter()
+            .find(|c| c.label == *label)
+            .map_or(label.clone(), |c| c.display_name().to_string()),
+        ChannelKey::Derived { name } => name.clone()
+    };
+    let channel_type = project.channel_type(&key).unwrap_or(ChannelType::Other);
+
+    row![
+        text(name).style(theme::text_secondary).size(14.0),
+        pick_list(AmplitudeScale::options(), Some(view.scale), |scale| Message::ChangeChannelView(ChannelViewChange::Scale(scale), false))
+            .text_size(14.0)
+            .padding([4.0, 8.0]),
+        button(text(if view.inverted { "Inverted" } else { "Invert" }).size(14.0))
+            .on_press(Message::ChangeChannelView(ChannelViewChange::Invert, false))
+            .style(if view.inverted { theme::button_primary } else { theme::button_secondary })
+            .padding([4.0, 8.0]),
+        button(text("−").size(14.0)).on_press(Message::ChangeChannelView(ChannelViewChange::Height(-1), false)).style(theme::button_secondary).padding([4.0, 8.0]),
+        text(format!("{} px", view.height)).size(14.0),
+        button(text("+").size(14.0)).on_press(Message::ChangeChannelView(ChannelViewChange::Height(1), false)).style(theme::button_secondary).padding([4.0, 8.0]),
+        button(text(format!("Apply to all {}", channel_type.label())).size(14.0))
+            .on_press(Message::ApplyChannelViewToType)
+            .style(theme::button_text)
+            .padding([4.0, 8.0]),
+    ].spacing(8.0).align_y(Vertical::Center).into()
+}
+
+/// Gets all events bound to the channel with the given label of the source
+fn channel_events(project: &CurrentProject, source: usize, label: &str) -> Vec<EventOverlay> {
+    project.events.values.iter().enumerate()
+        .filter(|(_, e)| e.source == source && e.channel == label)
+        .map(|(index, e)| EventOverlay {
+            index,
+            event_type: e.event_type,
+            start: e.onset as i64,
+            end: e.end() as i64
+        })
+        .collect()
+}
+
+/// Controls of the project in the header (scorer sets, import, export and settings).
+/// Quick views only offer to create a project from the viewed file.
+fn view_project_controls<'a>(app: &'a NoctiG, project: &'a CurrentProject) -> Element<'a, Message> {
+    if project.is_quick_view {
+        return row![
+            text("Quick view (read-only)").style(theme::text_secondary).size(14.0),
+            button(text("Create project").size(14.0)).on_press(Message::PromoteQuickView).style(theme::button_primary).padding([4.0, 8.0]),
+        ].spacing(16.0).align_y(Vertical::Center).into();
+    }
+
+    let scorer_names = project.project.scorer_sets().into_iter().map(|s| s.name).collect::<Vec<_>>();
+
+    // Montage selection is only shown in case the project has montages
+    let montages: Element<'_, Message> = if project.project.montages.is_empty() {
+        space().into()
+    } else {
+        row![
+            text("Montage").style(theme::text_secondary).size(14.0),
+            pick_list(project.montage_pages(), Some(project.montage_page()), Message::SwitchMontage)
+                .text_size(14.0)
+                .padding([4.0, 8.0]),
+        ].spacing(8.0).align_y(Vertical::Center).into()
+    };
+
+    row![
+        montages,
+
+        // Scorer set selection
+        row![
+            text("Scorer").style(theme::text_secondary).size(14.0),
+            pick_list(scorer_names, Some(project.project.active_scorer_name()), Message::SwitchScorer)
+                .text_size(14.0)
+                .padding([4.0, 8.0]),
+            text_input("New scorer", &app.new_scorer_name)
+                .style(theme::text_input)
+                .on_input(Message::NewScorerNameChanged)
+                .on_submit(Message::AddScorer)
+                .size(14.0)
+                .width(140.0)
+                .padding([4.0, 8.0]),
+            button(text("Add").size(14.0)).on_press(Message::AddScorer).style(theme::button_secondary).padding([4.0, 8.0]),
+        ].spacing(8.0).align_y(Vertical::Center),
+
+        button(text("Import").size(14.0)).on_press(Message::LaunchImport).style(theme::button_secondary).padding([4.0, 8.0]),
+        button(text("Settings").size(14.0)).on_press(Message::OpenProjectSettings).style(theme::button_secondary).padding([4.0, 8.0]),
+        pick_list(ExportFormat::ALL, None::<ExportFormat>, Message::Export)
+            .placeholder("Export")
+            .text_size(14.0)
+            .padding([4.0, 8.0]),
+    ].spacing(16.0).align_y(Vertical::Center).into()
+}
+
+fn view_statistics<'a>(project: &CurrentProject, scorings: &Scorings, period: &AnalysisPeriod) -> Element<'a, Message> {
+    let vocabulary = &project.project.stage_vocabulary;
+    let statistics = SleepStatistics::compute(scorings, vocabulary, period);
+    let start_time = project.readers.iter().map(|r| r.start_timestamp()).min().unwrap_or(0);
+    let lights = |value: Option<u64>| value.map(|t| formatters::time_string(start_time + t / 1_000)).unwrap_or("--".to_string());
+    let minutes = |value: f64| format!("{:.1} min", value);
+    let optional_minutes = |value: Option<f64>| value.map(minutes).unwrap_or("--".to_string());
+
+    let entry = |label: &'static str, value: String| -> Element<'a, Message> {
+        row![
+            text(label).style(theme::text_secondary).size(13.0).width(Length::Fill),
+            text(value).size(13.0),
+        ].spacing(16.0).into()
+    };
+
+    container(
+        column![
+            text("Sleep statistics").font(*REGULAR_BOLD).size(15.0),
+            space().height(4.0),
+
+            entry("Lights off", lights(project.project.lights_off)),
+            entry("Lights on", lights(project.project.lights_on)),
+            row![
+                button(text("Off").size(12.0)).on_press(Message::SetLightsOff).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("On").size(12.0)).on_press(Message::SetLightsOn).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("Detect").size(12.0)).on_press(Message::DetectAnalysisPeriod).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("Reset").size(12.0)).on_press(Message::ResetAnalysisPeriod).style(theme::button_text).padding([4.0, 8.0]),
+            ].spacing(4.0),
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            entry("Time in bed", minutes(statistics.time_in_bed)),
+            entry("Total sleep time", minutes(statistics.total_sleep_time)),
+            entry("Sleep period time", minutes(statistics.sleep_period_time)),
+            entry("Sleep onset latency", optional_minutes(statistics.sleep_onset_latency)),
+            entry("Wake after sleep onset", minutes(statistics.wake_after_sleep_onset)),
+            entry("Sleep efficiency", format!("{:.1} %", statistics.sleep_efficiency)),
+            entry("REM latency", optional_minutes(statistics.rem_latency)),
+            entry("Awakenings", statistics.awakenings.to_string()),
+            entry("Unscored", minutes(statistics.unscored)),
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            Column::from_iter(statistics.stages.into_iter().map(|s| row![
+                container(
+                    text(vocabulary.label(Some(&s.stage))).style(vocabulary.foreground(Some(&s.stage))).size(12.0)
+                ).style(vocabulary.background(Some(&s.stage))).width(40.0).align_x(Alignment::Center),
+                text(minutes(s.minutes)).size(13.0).width(Length::Fill).align_x(Alignment::End),
+                text(format!("{:.1} %", s.percent)).style(theme::text_secondary).size(13.0).width(64.0).align_x(Alignment::End),
+            ].spacing(8.0).align_y(Vertical::Center).into())).spacing(4.0),
+        ].spacing(4.0)
+    ).padding([12.0, 16.0]).width(280.0).style(theme::container_tooltip).into()
+}
+
+fn view_comparison<'a>(app: &NoctiG, project: &'a CurrentProject, scorings: &'a Scorings, period: &AnalysisPeriod) -> Element<'a, Message> {
+    let vocabulary = &project.project.stage_vocabulary;
+    let active_scorer = project.project.active_scorer_name();
+    let other_scorers = project.project.scorer_sets().into_iter()
+        .map(|s| s.name)
+        .filter(|name| *name != active_scorer)
+        .collect::<Vec<_>>();
+
+    let comparison_scorer = app.comparison_scorer();
+    let (Some(other_name), Some(other)) = (comparison_scorer.clone(), comparison_scorer.and_then(|name| project.scorings_of(&name))) else {
+        return container(
+            column![
+                text("Scorer comparison").font(*REGULAR_BOLD).size(15.0),
+                text("Add another scorer to compare the scores").style(theme::text_secondary).size(13.0),
+            ].spacing(8.0)
+        ).padding([12.0, 16.0]).width(420.0).style(theme::container_tooltip).into();
+    };
+
+    let epoch_count = project.epoch_count();
+    let current_epoch = project.current_epoch();
+    let agreement = Agreement::compute(scorings, other, vocabulary, epoch_count);
+    let kappa = |value: Option<f64>| value.map(|k| format!("{:.2}", k)).unwrap_or("--".to_string());
+
+    let hypnogram = |label: String, scorings: &Scorings| -> Element<'a, Message> {
+        column![
+            text(label).style(theme::text_secondary).size(12.0),
+            Canvas::new(Hypnogram::new(scorings, vocabulary, epoch_count, current_epoch, period.clone()).with_highlights(agreement.disagreements.clone()))
+                .width(Length::Fill)
+                .height(Length::Fixed(40.0)),
+        ].spacing(2.0).into()
+    };
+
+    let entry = |label: &'static str, value: String| -> Element<'a, Message> {
+        row![
+            text(label).style(theme::text_secondary).size(13.0).width(Length::Fill),
+            text(value).size(13.0),
+        ].spacing(16.0).into()
+    };
+
+    // Confusion matrix with the active scorer as rows and the compared scorer as columns
+    let stage_cell = |stage| container(
+        text(vocabulary.label(Some(stage))).style(vocabulary.foreground(Some(stage))).size(12.0)
+    ).style(vocabulary.background(Some(stage))).width(Length::Fill).align_x(Alignment::Center);
+    let header = Row::from_iter(
+        std::iter::once(space().width(Length::Fill).into())
+            .chain(agreement.stages.iter().map(|stage| stage_cell(stage).into()))
+            .chain(std::iter::once(text("κ").style(theme::text_secondary).size(12.0).width(Length::Fill).align_x(Alignment::End).into()))
+    ).spacing(4.0);
+    let rows = Column::from_iter(agreement.stages.iter().enumerate().map(|(i, stage)| Row::from_iter(
+        std::iter::once(stage_cell(stage).into())
+            .chain(agreement.confusion[i].iter().enumerate().map(|(j, count)|
+                text(count.to_string())
+                    .size(12.0)
+                    .style(if i == j { theme::text_primary } else { theme::text_secondary })
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center)
+                    .into()
+            ))
+            .chain(std::iter::once(text(kappa(agreement.stage_kappa[i])).size(12.0).width(Length::Fill).align_x(Alignment::End).into()))
+    ).spacing(4.0).align_y(Vertical::Center).into())).spacing(4.0);
+
+    container(
+        column![
+            text("Scorer comparison").font(*REGULAR_BOLD).size(15.0),
+            space().height(4.0),
+
+            row![
+                text("Compare with").style(theme::text_secondary).size(13.0).width(Length::Fill),
+                pick_list(other_scorers, Some(other_name.clone()), Message::CompareWith).text_size(13.0).padding([4.0, 8.0]),
+            ].spacing(16.0).align_y(Vertical::Center),
+
+            hypnogram(active_scorer, scorings),
+            hypnogram(other_name, other),
+
+            row![
+                text(format!("{} disagreements", agreement.disagreements.len())).size(13.0).width(Length::Fill),
+                button(text("Previous").size(12.0)).on_press(Message::PreviousDisagreement).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("Next").size(12.0)).on_press(Message::NextDisagreement).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("Adjudicate").size(12.0)).on_press(Message::StartAdjudication).style(theme::button_primary).padding([4.0, 8.0]),
+            ].spacing(4.0).align_y(Vertical::Center),
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            entry("Epochs scored by both", agreement.compared.to_string()),
+            entry("Agreement", format!("{:.1} %", agreement.percent_agreement)),
+            entry("Cohen's kappa", kappa(agreement.kappa)),
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            header,
+            rows,
+        ].spacing(4.0)
+    ).padding([12.0, 16.0]).width(420.0).style(theme::container_tooltip).into()
+}
+
+fn view_adjudication<'a>(project: &'a CurrentProject, consensus: &'a Scorings, adjudication: &Adjudication) -> Element<'a, Message> {
+    let vocabulary = &project.project.stage_vocabulary;
+    let epoch = project.current_epoch();
+    let is_disagreement = adjudication.epochs.contains(&epoch);
+    let stage_cell = |stage: Option<&Stage>| container(
+        text(vocabulary.label(stage)).style(vocabulary.foreground(stage)).size(14.0)
+    ).style(vocabulary.background(stage)).padding([2.0, 0.0]).width(48.0).align_x(Alignment::Center);
+
+    // Label of every scorer which can be accepted for the consensus
+    let scorers = Column::from_iter(adjudication.scorers.iter().map(|name| {
+        let stage = project.scorings_of(name).and_then(|s| s.values.get(&epoch));
+        row![
+            text(name.clone()).size(13.0).width(Length::Fill),
+            button(stage_cell(stage))
+                .on_press_maybe(stage.filter(|_| is_disagreement).map(|_| Message::AcceptLabel(name.clone())))
+                .style(theme::button_text)
+                .padding(2.0),
+        ].spacing(8.0).align_y(Vertical::Center).into()
+    })).spacing(4.0);
+
+    let consensus_stage = consensus.values.get(&epoch);
+    let provenance = consensus.provenance.get(&epoch).cloned().unwrap_or("--".to_string());
+
+    container(
+        column![
+            text("Adjudication").font(*REGULAR_BOLD).size(15.0),
+            text(format!("{} of {} disagreements resolved", adjudication.resolved(consensus), adjudication.epochs.len()))
+                .style(theme::text_secondary)
+                .size(13.0),
+            space().height(4.0),
+
+            text(if is_disagreement {
+                format!("Epoch {}", epoch + 1)
+            } else {
+                format!("Epoch {} (no disagreement)", epoch + 1)
+            }).size(13.0),
+            scorers,
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            row![
+                text("Consensus").size(13.0).width(Length::Fill),
+                text(provenance).style(theme::text_secondary).size(13.0),
+                stage_cell(consensus_stage),
+            ].spacing(8.0).align_y(Vertical::Center),
+            text("Press a rating key to choose another stage")
+                .style(theme::text_secondary)
+                .size(12.0),
+
+            row![
+                button(text("Previous").size(12.0)).on_press(Message::PreviousDisagreement).style(theme::button_secondary).padding([4.0, 8.0]),
+                button(text("Next").size(12.0)).on_press(Message::NextDisagreement).style(theme::button_secondary).padding([4.0, 8.0]),
+                space().width(Length::Fill),
+                button(text("Finish").size(12.0)).on_press(Message::StopAdjudication).style(theme::button_primary).padding([4.0, 8.0]),
+            ].spacing(4.0).align_y(Vertical::Center),
+        ].spacing(4.0)
+    ).padding([12.0, 16.0]).width(320.0).style(theme::container_tooltip).into()
+}
+
+fn view_import_preview<'a>(project: &'a CurrentProject, preview: &'a ImportPreview) -> Element<'a, Message> {
+    let vocabulary = &project.project.stage_vocabulary;
+    let file_name = preview.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let entry = |label: &'static str, value: String| -> Element<'a, Message> {
+        row![
+            text(label).style(theme::text_secondary).size(13.0).width(Length::Fill),
+            text(value).size(13.0),
+        ].spacing(16.0).into()
+    };
+
+    // Amount of imported epochs per stage
+    let stages = Row::from_iter(vocabulary.stages.iter().map(|definition| {
+        let stage = definition.stage();
+        let count = preview.stages.values().filter(|s| **s == stage).count();
+        column![
+            container(
+                text(definition.label.clone()).style(vocabulary.foreground(Some(&stage))).size(12.0)
+            ).style(vocabulary.background(Some(&stage))).width(Length::Fill).align_x(Alignment::Center),
+            text(count.to_string()).size(12.0).width(Length::Fill).align_x(Alignment::Center),
+        ].spacing(2.0).into()
+    })).spacing(4.0);
+
+    let warnings = Column::from_iter(preview.warnings.iter().map(|warning|
+        text(warning.clone()).style(theme::text_danger).size(12.0).into()
+    )).spacing(4.0);
+
+    let unknown_labels = Column::from_iter(preview.unknown_labels.iter().map(|(label, count)|
+        entry_text(label.clone(), count.to_string())
+    )).spacing(2.0);
+
+    container(
+        column![
+            text("Import preview").font(*REGULAR_BOLD).size(15.0),
+            text(format!("{} ({})", file_name, preview.format)).style(theme::text_secondary).size(13.0),
+            space().height(4.0),
+
+            entry("Epoch length", preview.epoch_duration.map(|d| format!("{} s", d)).unwrap_or("--".to_string())),
+            entry("Time offset", preview.offset.map(|o| format!("{:.3} s", o)).unwrap_or("--".to_string())),
+            entry("Scored epochs", preview.stages.len().to_string()),
+            entry("Replaced epochs", preview.replaced.to_string()),
+            entry("Events", preview.events.len().to_string()),
+            entry("Markers", preview.markers.len().to_string()),
+            entry("Annotations", preview.annotations.len().to_string()),
+            entry("Lights off", preview.lights_off.map(|t| format!("{:.1} s", t as f64 / 1_000.0)).unwrap_or("--".to_string())),
+            entry("Lights on", preview.lights_on.map(|t| format!("{:.1} s", t as f64 / 1_000.0)).unwrap_or("--".to_string())),
+            stages,
+
+            space().height(4.0),
+            container(Space::new().height(1.0)).width(Length::Fill).style(theme::stroke),
+            space().height(4.0),
+
+            warnings,
+            text(if preview.unknown_labels.is_empty() { "No unknown labels" } else { "Unknown labels (skipped)" })
+                .style(theme::text_secondary)
+                .size(13.0),
+            scrollable(unknown_labels).height(Length::Shrink),
+
+            row![
+                space().width(Length::Fill),
+                button(text("Cancel").size(12.0)).on_press(Message::CancelImport).style(theme::button_text).padding([4.0, 8.0]),
+                button(text("Import").size(12.0)).on_press(Message::ApplyImport).style(theme::button_primary).padding([4.0, 8.0]),
+            ].spacing(4.0).align_y(Vertical::Center),
+        ].spacing(4.0)
+    ).padding([12.0, 16.0]).width(360.0).style(theme::container_tooltip).into()
+}
+
+fn entry_text<'a>(label: String, value: String) -> Element<'a, Message> {
+    row![
+        text(label).style(theme::text_secondary).size(12.0).width(Length::Fill),
+        text(value).size(12.0),
+    ].spacing(16.0).into()
+}
+
+fn view_stage_picker<'a>(vocabulary: &StageVocabulary) -> Element<'a, Message> {
+    // All stages of the vocabulary followed by an option to remove the stage
+    let stages = vocabulary.stages.iter().map(|s| Some(s.stage())).chain([None]);
+
+    container(
+        Row::from_iter(stages.map(|stage|
+            button(
+                container(
+                    text(vocabulary.label(stage.as_ref())).style(vocabulary.foreground(stage.as_ref())).size(16.0)
+                ).style(vocabulary.background(stage.as_ref())).padding([2.0, 0.0]).width(Length::Fill).align_x(Alignment::Center)
+            )
+            .on_press(Message::Rate(stage))
+            .style(theme::button_text)
+            .padding(2.0)
+            .width(Length::Fill)
+            .into()
+        )).spacing(4.0)
+    ).padding(4.0).style(theme::container_tooltip).into()
+}
+
+fn view_event_picker<'a>() -> Element<'a, Message> {
+    container(
+        Row::from_iter(EventType::ALL.into_iter().map(|event_type|
+            button(
+                text(event_type.label()).size(13.0).color(event_type.color())
+            )
+            .on_press(Message::AddEvent(event_type))
+            .style(theme::button_text)
+            .padding([4.0, 8.0])
+            .into()
+        )).spacing(4.0)
+    ).padding(4.0).style(theme::container_tooltip).into()
+}
+
+/// Choice of the channel the spectrogram is calculated of, shown on top of the spectrogram
+fn view_spectrogram_channel<'a>(project: &'a CurrentProject) -> Element<'a, Message> {
+    container(
+        pick_list(project.spectrogram_channels(), project.spectrogram_channel.clone(), Message::SpectrogramChannelChanged)
+            .placeholder("Channel")
+            .text_size(13.0)
+            .padding([4.0, 8.0])
+    ).padding(12.0).into()
+}
+
 fn view_spectrogram<'a>(project: &'a CurrentProject) -> Element<'a, Message> {
     if let Some(spectrogram) = &project.spectrogram {
         return container(shader(spectrogram)
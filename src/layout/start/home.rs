@@ -51,9 +51,24 @@ pub fn view(app: &NoctiG) -> Element<'_, Message> {
             ].align_y(Vertical::Center).spacing(12.0))
                 .style(theme::button_secondary)
                 .on_press(Message::LaunchOpenProject)
+                .padding([8.0, 12.0]),
+
+            button(row![
+                fa_icon_solid("eye").size(15.0),
+                text("Open EDF")
+            ].align_y(Vertical::Center).spacing(12.0))
+                .style(theme::button_secondary)
+                .on_press(Message::LaunchQuickView)
                 .padding([8.0, 12.0])
         ].spacing(8.0),
 
+        // Problem of the last file opened from the start page
+        Column::from_iter(app.start_error.iter().map(|error|
+            container(text(error.clone()).style(theme::text_danger).size(13.0))
+                .padding(Padding { top: 8.0, ..Padding::ZERO })
+                .into()
+        )),
+
         space().height(12.0),
 
         // Recent projects list
@@ -1,19 +1,21 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]  // hide console window on Windows in release
 
+use chrono::{DateTime, NaiveDateTime};
 use edf_rs::file::EDFFile;
 use iced::futures::channel::mpsc::Sender;
 use iced::futures::{SinkExt, Stream};
 use iced::window::{Id, Position, Settings};
-use iced::{Element, Point, Size, Subscription, Task, Theme, Vector, window};
+use iced::{Element, Point, Size, Subscription, Task, Theme, Vector, task, window};
 use iced::keyboard::{key::Named, Key};
 use iced::event::Status;
 use iced::event;
-use iced::widget::{self, space};
+use iced::widget::space;
 use ndarray::Array1;
 use rfd::AsyncFileDialog;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
+use std::fmt::Display;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -21,20 +23,40 @@ use std::sync::LazyLock;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use env_logger::Builder;
 use log::{LevelFilter, warn};
+use md5::{Digest, Md5};
 use iced::stream::channel;
 
-use crate::database::types::RecentProject;
-use crate::database::{get_last_browse_source_path, get_last_project_create_path, get_last_project_path, get_recently_opened, set_last_browse_source_path, set_last_project_create_path, set_last_project_path, update_recently_opened};
+use crate::database::types::{ChannelAlias, RecentProject};
+use crate::database::{get_channel_aliases, get_project_presets, get_last_browse_source_path, get_last_project_create_path, get_last_project_path, get_recently_opened, set_channel_alias, set_last_browse_source_path, set_last_project_create_path, set_last_project_path, update_recently_opened};
 use crate::external::lspopt::spectrogram_lspopt;
 use crate::external::scipy::Spectrogram;
 use crate::layout::create_project::create_viewer;
 use crate::layout::license::load_licenses;
-use crate::layout::{license, scorer, start};
-use crate::storage::epoch_reader::EpochReader;
-use crate::formatting::theme::{CLEAR_DARK_TEXT_SECONDARY, border_background_base, text_foreground_base};
-use crate::storage::project_initializer;
+use crate::layout::{license, presets as presets_viewer, project_settings as settings_viewer, relink as relink_viewer, scorer, start};
+use crate::storage::epoch_reader::{ChartSignal, EpochReader};
+use crate::formatting::theme::CLEAR_DARK_TEXT_SECONDARY;
+use crate::storage::{project_initializer, project_settings, relink, spectrogram_cache};
+use crate::storage::project_initializer::{ConfigurationError, ConfigurationField};
+use crate::storage::preset::{self, PresetEditor, ProjectPreset};
+use crate::storage::project_settings::{Fingerprints, MontageChannelField, MontageChannelSettings, MontageSettings, ProjectSettings};
+use crate::storage::relink::{MissingSources, Relink};
+use crate::storage::edf_annotations::{find_lights_annotations, read_annotations};
+use crate::statistics::sleep_stats::AnalysisPeriod;
+use crate::statistics::agreement::Agreement;
 use crate::views::collapsible::Collapsible;
+use crate::views::line_chart::GridSettings;
 use crate::views::spectrogram::widget::SpectrogramView;
+use crate::scoring::stages::{Stage, StageVocabulary};
+use crate::scoring::events::{EventType, Events, ScoredEvent};
+use crate::scoring::history::History;
+use crate::export::ExportFormat;
+use crate::import::{IMPORT_EXTENSIONS, ImportPreview};
+use crate::scoring::consensus::{Adjudication, CONSENSUS_SCORER, PROVENANCE_ADJUDICATOR};
+use crate::signals::channel::{ChannelSettings, ChannelType};
+use crate::signals::alias;
+use crate::signals::derived::DerivedChannel;
+use crate::signals::montage::{self, Montage, ResolvedChannel};
+use crate::signals::scaling::{ChannelKey, ChannelView, ChannelViewChange};
 
 mod layout;
 mod formatting;
@@ -43,6 +65,14 @@ mod views;
 mod macros;
 mod external;
 mod database;
+mod statistics;
+mod scoring;
+mod export;
+mod import;
+mod cli;
+mod signals;
+#[cfg(test)]
+mod testing;
 
 pub const ICON: &[u8] = include_bytes!("../resources/icon.svg");
 pub const ICON_SECONDARY: LazyLock<Vec<u8>> = LazyLock::new(|| include_str!("../resources/icon.svg").replace("fill:#ffffff", &format!("fill:{}", CLEAR_DARK_TEXT_SECONDARY.to_string())).replace("stroke:#ffffff", &format!("stroke:{}", CLEAR_DARK_TEXT_SECONDARY.to_string())).into_bytes());
@@ -57,8 +87,17 @@ fn main() -> iced::Result {
         .filter(Some(env!("CARGO_PKG_NAME")), LevelFilter::Info)
         .init();
 
+    // Run a command without the user interface in case the first argument is a command
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().is_some_and(|arg| cli::is_command(arg)) {
+        std::process::exit(cli::run(&args));
+    }
+
+    // All other arguments are files to open (e.g. when opened using a file association)
+    let paths = args.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+
     // Launch UI
-    iced::daemon(NoctiG::boot, NoctiG::update, NoctiG::view)
+    iced::daemon(move || NoctiG::boot(paths.clone()), NoctiG::update, NoctiG::view)
         .title("NoctiG Scorer")
         .theme(NoctiG::theme)
         .subscription(NoctiG::subscription)
@@ -74,12 +113,26 @@ fn settings() -> iced::Settings {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Scorings {
     pub epoch_duration: u64,
 
     // Mapping beteen epoch segment index and its determined stage
-    pub values: BTreeMap<u64, Stage>
+    pub values: BTreeMap<u64, Stage>,
+
+    // Mapping between epoch segment index and the origin of its stage (e.g. the scorer accepted in a consensus)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub provenance: BTreeMap<u64, String>
+}
+
+impl Scorings {
+    pub fn new(epoch_duration: u64) -> Self {
+        Self {
+            epoch_duration,
+            values: BTreeMap::new(),
+            provenance: BTreeMap::new()
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -94,15 +147,58 @@ pub struct Annotations {
     pub local: HashMap<u32, HashMap<Marker, Vec<AnnotationValue>>>
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 pub struct AnnotationValue {
     pub timestamp: u64,
     pub value: String
 }
 
+/// A time range selected by dragging across a signal trace. The timestamps are
+/// in milliseconds relative to the start of the first epoch of the project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSelection {
+    pub signal_index: usize,
+    pub start: i64,
+    pub end: i64
+}
+
+impl TimeSelection {
+    /// Gets the indices of all epochs which are (partially) covered by the selection
+    pub fn epochs(&self) -> std::ops::RangeInclusive<u64> {
+        let epoch_millis = EpochReader::EPOCH_DURATION as i64 * 1_000;
+        let first = self.start.max(0).div_euclid(epoch_millis) as u64;
+        let last = (self.end - 1).max(0).div_euclid(epoch_millis) as u64;
+
+        first..=last
+    }
+}
+
+/// Snapshot of all labels of a project which can be edited in the scorer, used to undo changes
+#[derive(Clone)]
+pub struct LabelSnapshot {
+    scorings: Option<Scorings>,
+    events: Vec<ScoredEvent>
+}
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct SessionState {
-    pub position: u64
+    pub position: u64,
+
+    // Scaling, polarity and height of the channels which differ from the defaults
+    #[serde(default)]
+    pub channel_views: Vec<ChannelView>,
+
+    // Time gridlines and amplitude reference band drawn behind the signals
+    #[serde(default)]
+    pub grid: GridSettings,
+
+    // Length in seconds of the visible part of the current epoch when zoomed in, the entire window is shown otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<u32>,
+
+    // Amount of epochs shown before and after the current epoch when it differs from the default of the project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_epochs: Option<(u8, u8)>
 
     // TODO: Save the toggle states and current settings (e.g. timeframe format, show legend, etc.)
 }
@@ -120,6 +216,30 @@ pub struct Project {
     pub signals: Vec<SignalSource>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub stage_vocabulary: StageVocabulary,
+
+    // Analysis period (lights-off / lights-on) in milliseconds relative to the first epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lights_off: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lights_on: Option<u64>,
+
+    // Named scorer sets of the project. Projects without any scorer sets use the default scores file
+    #[serde(default)]
+    pub scorers: Vec<ScorerSet>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_scorer: Option<String>,
+
+    // Channels calculated from the channels of the signal sources (e.g. re-referenced or bipolar channels)
+    #[serde(default)]
+    pub derived_channels: Vec<DerivedChannel>,
+
+    // Montages of the project. All visible channels are shown in case no montage is active
+    #[serde(default)]
+    pub montages: Vec<Montage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_montage: Option<String>,
 }
 
 impl Project {
@@ -128,28 +248,89 @@ impl Project {
     }
 
     pub fn from_config_and_signals(config: &ProjectConfiguration, signals: &Vec<ProjectSignals>) -> Self {
+        let preset = config.preset.clone().unwrap_or_else(|| ProjectPreset::new(""));
+
+        // Derived channels of the preset are only taken over if all of their channels exist
+        let channels = signals.iter().map(|s| s.channels.as_slice()).collect::<Vec<_>>();
+        let derived_channels = preset.derived_channels.into_iter()
+            .filter(|c| c.resolve(&channels).is_ok())
+            .collect();
+
         Self {
             name: config.name.clone(),
             project_type: ProjectType::SleepScoring,
             tags: config.tags.iter().cloned().collect(),
-            epoch_duration: 30,
-            epochs_before_current: 1,
-            epochs_after_current: 1,
+            epoch_duration: EpochReader::EPOCH_DURATION as u64,
+            epochs_before_current: preset.epochs_before_current,
+            epochs_after_current: preset.epochs_after_current,
             signals: signals.iter().map(SignalSource::from_config).collect(),
             filter_signal: config.filter_signal,
             auto_align_signals: config.auto_align_signals,
             clip_signal: config.clip_signal,
+            stage_vocabulary: config.stage_vocabulary.clone(),
+            lights_off: None,
+            lights_on: None,
+            scorers: vec![ScorerSet::default()],
+            active_scorer: None,
+            derived_channels,
+            montages: preset.montages,
+            active_montage: None,
+        }
+    }
+
+    pub fn scorer_sets(&self) -> Vec<ScorerSet> {
+        if self.scorers.is_empty() {
+            vec![ScorerSet::default()]
+        } else {
+            self.scorers.clone()
         }
     }
+
+    /// Gets the name of the currently active scorer set or the first one in case none is set
+    pub fn active_scorer_name(&self) -> String {
+        let sets = self.scorer_sets();
+        self.active_scorer.as_ref()
+            .filter(|name| sets.iter().any(|s| &s.name == *name))
+            .cloned()
+            .unwrap_or(sets[0].name.clone())
+    }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+/// A named set of scores within a project, e.g. of one person in case a study is scored multiple times
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScorerSet {
+    pub name: String,
+
+    // Name of the scores file within the labels directory
+    pub file: String
+}
+
+impl Default for ScorerSet {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            file: "scores.json".to_string()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct SignalSource {
     pub path: String,
     pub read_only: bool,
     pub offset: u64,
     #[serde(default)]
-    pub merge_groups: Vec<SignalMergeGroup>
+    pub merge_groups: Vec<SignalMergeGroup>,
+
+    // Size in bytes and MD5 hash of the content of the file to find it again after it was moved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+
+    // Display settings of the channels, channels without settings are shown with their detected type
+    #[serde(default)]
+    pub channels: Vec<ChannelSettings>
 }
 
 impl SignalSource {
@@ -164,9 +345,28 @@ impl SignalSource {
             path,
             read_only: config.is_reference,
             offset: 0,
-            merge_groups: Vec::new()
+            merge_groups: Vec::new(),
+            size: None,
+            hash: None,
+            channels: config.channels.clone()
         }
     }
+
+    /// Gets the size and content hash of the file at the given path, which can take a while for large files
+    pub fn fingerprint(path: &Path) -> std::io::Result<(u64, String)> {
+        Ok((fs::metadata(path)?.len(), relink::content_hash(path)?))
+    }
+
+    /// Stores the size and content hash of the file at the given path, which can take a while for large files
+    pub fn update_fingerprint(&mut self, path: &Path) -> std::io::Result<()> {
+        self.set_fingerprint(Self::fingerprint(path)?);
+        Ok(())
+    }
+
+    pub fn set_fingerprint(&mut self, (size, hash): (u64, String)) {
+        self.size = Some(size);
+        self.hash = Some(hash);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -193,7 +393,7 @@ impl WindowType {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SignalMergeGroup {
     pub signal_id: u16,
     pub group_id: u16
@@ -205,6 +405,7 @@ pub enum ProjectType {
     EKG
 }
 
+#[derive(Clone)]
 pub struct ProjectConfiguration {
     pub name: String,
     pub path: String,
@@ -214,15 +415,152 @@ pub struct ProjectConfiguration {
     pub filter_signal: bool,
     pub clip_signal: bool,
     pub auto_align_signals: bool,
+    pub stage_vocabulary: StageVocabulary,
+
+    // Preset the project is created from, which provides the remaining settings of the project
+    pub preset: Option<ProjectPreset>,
+
+    // Problems found when trying to create the project, shown next to the affected fields
+    pub errors: Vec<ConfigurationError>
+}
+
+impl ProjectConfiguration {
+    /// Validates the configuration again after a change in case problems were shown already
+    pub fn revalidate(&mut self) {
+        if !self.errors.is_empty() {
+            self.errors = project_initializer::validate(self);
+        }
+    }
+
+    /// Validates the name and location again after they were changed in case problems were shown already.
+    /// The problems of the signal sources are kept, as they did not change.
+    pub fn revalidate_details(&mut self) {
+        if !self.errors.is_empty() {
+            let mut errors = project_initializer::validate_details(self);
+            errors.extend(self.errors.drain(..).filter(|e| matches!(e.field, ConfigurationField::Sources | ConfigurationField::Source(_))));
+            self.errors = errors;
+        }
+    }
+
+    /// Takes over the settings of the preset and renames and types the channels of the added sources using its channel rules
+    pub fn apply_preset(&mut self, preset: ProjectPreset) {
+        self.filter_signal = preset.filter_signal;
+        self.clip_signal = preset.clip_signal;
+        self.auto_align_signals = preset.auto_align_signals;
+        self.stage_vocabulary = preset.stage_vocabulary.clone();
+        for signals in &mut self.data {
+            signals.apply_channel_rules(&preset.channel_rules);
+        }
+
+        self.preset = Some(preset);
+    }
+
+    /// Gets the messages of all problems of the given field
+    pub fn errors_of(&self, field: &ConfigurationField) -> Vec<&str> {
+        self.errors.iter().filter(|e| e.field == *field).map(|e| e.message.as_str()).collect()
+    }
+}
+
+impl Default for ProjectConfiguration {
+    fn default() -> Self {
+        Self {
+            name: "".to_string(),
+            path: "".to_string(),
+            new_tag: "".to_string(),
+            tags: Vec::new(),
+            filter_signal: true,
+            auto_align_signals: true,
+            clip_signal: true,
+            stage_vocabulary: StageVocabulary::default(),
+            data: Vec::new(),
+            preset: None,
+            errors: Vec::new()
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct ProjectSignals {
     pub timestamp: u64,
     pub duration: f64,
     pub signal_count: usize,
     pub path: String,
     pub name: String,
-    pub is_reference: bool
+    pub is_reference: bool,
+
+    // Size of the file in bytes, which is required in the project in case the file is copied
+    pub size: u64,
+
+    // Channels of the file (without annotations) and whether they are listed within the wizard
+    pub channels: Vec<ChannelSettings>,
+    pub is_expanded: bool,
+
+    // Reason the file cannot be used, determined once when it is added
+    pub read_error: Option<String>
+}
+
+impl ProjectSignals {
+    /// Reads the header of the EDF file at the given path. Files which cannot be read are added without any details.
+    pub fn from_path(path: String) -> Self {
+        let mut duration = 0.0;
+        let mut signal_count = 0;
+        let mut timestamp = 0;
+        let mut channels = Vec::new();
+        let mut read_error = None;
+
+        match EDFFile::open(&path) {
+            Ok(edf) => {
+                let header = edf.header;
+                duration = header.get_record_count().map(|c| c as f64 * header.get_record_duration()).unwrap_or(0.0);
+                signal_count = header.get_signals().len();
+                timestamp = header.start_date().and_time(header.get_start_time()).and_utc().timestamp() as u64;
+
+                // Known labels are renamed and typed according to the alias dictionary
+                let aliases = get_channel_aliases().unwrap_or_default();
+                channels = header.get_signals().iter()
+                    .filter(|s| !s.is_annotation())
+                    .map(|s| ChannelSettings::from_aliases(&s.label, &aliases))
+                    .collect();
+                if channels.is_empty() {
+                    read_error = Some("The file does not contain any signals".to_string());
+                }
+            }
+            // The debug representation is used, as displaying EDF errors recurses endlessly
+            Err(e) => read_error = Some(format!("The file cannot be read ({:?})", e))
+        };
+
+        // TODO: In case there already is a file with this name in the current signals, append a -<NUMERIC> to make it unique
+        let filename = Path::new(&path).file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or("--".to_string());
+
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        Self {
+            timestamp,
+            duration,
+            signal_count,
+            path,
+            name: filename,
+            is_reference: false,
+            size,
+            channels,
+            is_expanded: false,
+            read_error
+        }
+    }
+
+    /// Renames and types the channels matching one of the given rules
+    pub fn apply_channel_rules(&mut self, rules: &[ChannelAlias]) {
+        for channel in &mut self.channels {
+            if alias::find(rules, &channel.label).is_some() {
+                *channel = ChannelSettings {
+                    visible: channel.visible,
+                    ..ChannelSettings::from_aliases(&channel.label, rules)
+                };
+            }
+        }
+    }
 }
 
 pub struct CurrentProject {
@@ -233,8 +571,107 @@ pub struct CurrentProject {
     markers: Markers,
     annotations: Annotations,
     scorings: Option<Scorings>,
+    other_scorings: BTreeMap<String, Scorings>,
+    events: Events,
+    history: History<LabelSnapshot>,
     spectrogram: Option<SpectrogramView>,
-    loading_progress_spectrogram: Option<f32>
+    loading_progress_spectrogram: Option<f32>,
+    spectrogram_channel: Option<SpectrogramChannel>,
+    spectrogram_task: Option<task::Handle>,
+
+    // Derived channels calculated from the last read epochs of the readers
+    derived_signals: Vec<ChartSignal>,
+
+    // Channels of the active montage with the index of their source (none for derived channels)
+    montage_signals: Vec<(Option<usize>, ChartSignal)>,
+
+    // State of the user interface which is stored separately from the project (e.g. the scaling of the channels)
+    session: SessionState,
+
+    // Index of the visible part of the current epoch when zoomed in
+    zoom_page: u32,
+
+    // Result of the last action shown within the status bar (e.g. the path of an exported file)
+    status: Option<StatusMessage>,
+
+    // Read-only view of a single EDF file which is not stored as project
+    is_quick_view: bool
+}
+
+/// Message shown within the status bar of the scorer
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool
+}
+
+impl StatusMessage {
+    pub fn info(text: String) -> Self {
+        Self { text, is_error: false }
+    }
+
+    pub fn error(text: String) -> Self {
+        Self { text, is_error: true }
+    }
+}
+
+/// Channel the spectrogram is calculated of
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpectrogramChannel {
+    Signal { source: usize, index: usize, name: String },    // Index of the signal within the EDF file
+    Derived { index: usize, name: String }
+}
+
+impl Display for SpectrogramChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpectrogramChannel::Signal { source, name, .. } => write!(f, "{} (Source {})", name, source + 1),
+            SpectrogramChannel::Derived { name, .. } => write!(f, "{} (Derived)", name)
+        }
+    }
+}
+
+// Lengths in seconds of the parts of an epoch which can be zoomed into
+pub const ZOOM_SPANS: [u32; 3] = [5, 10, 15];
+
+/// Time span shown within the scorer, either a part of the current epoch or the current epoch with the
+/// given amount of context epochs before and after it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeSpan {
+    Part(u32),      // s
+    Epochs(u8)
+}
+
+impl Display for TimeSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSpan::Part(seconds) => write!(f, "{} s", seconds),
+            TimeSpan::Epochs(context) => {
+                let seconds = (*context as u32 * 2 + 1) * EpochReader::EPOCH_DURATION;
+                if seconds < 60 {
+                    write!(f, "{} s", seconds)
+                } else {
+                    write!(f, "{} min", seconds as f32 / 60.0)
+                }
+            }
+        }
+    }
+}
+
+/// Page of channels shown within the scorer, either all visible channels or the channels of a montage
+#[derive(Debug, Clone, PartialEq)]
+pub enum MontagePage {
+    All,
+    Montage(String)
+}
+
+impl Display for MontagePage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MontagePage::All => write!(f, "All channels"),
+            MontagePage::Montage(name) => write!(f, "{}", name)
+        }
+    }
 }
 
 pub struct LicenseData {
@@ -256,14 +693,29 @@ struct NoctiG {
     window_time_formatter_index: usize,
     draw_ranges: bool,
     is_showing_help: bool,
+    is_showing_statistics: bool,
+    is_showing_comparison: bool,
+    comparison_scorer: Option<String>,
+    new_scorer_name: String,
+    adjudication: Option<Adjudication>,
+    import_preview: Option<ImportPreview>,
+    project_settings: Option<ProjectSettings>,
+    relink: Option<Relink>,
     search_text: String,
     search_task_id: String,
     project_creation: Option<ProjectConfiguration>,
+    project_presets: Vec<ProjectPreset>,
+    preset_editor: Option<PresetEditor>,
     current_project: Option<CurrentProject>,
     recent_projects: Vec<RecentProject>,
     filtered_recent_projects: Option<Vec<RecentProject>>,
+    start_error: Option<String>,    // Problem of the last file opened from the start page
     windows: BTreeMap<Id, WindowType>,
-    licenses: LazyLock<[Vec<Collapsible<LicenseData>>; 3]>
+    licenses: LazyLock<[Vec<Collapsible<LicenseData>>; 3]>,
+    stage_picker: Option<u64>,
+    selection: Option<TimeSelection>,
+    focused_signal: Option<usize>,     // Index across all readers, the derived channels follow the channels of the readers
+    scroll_accumulator: f32
 }
 
 impl CurrentProject {
@@ -271,19 +723,55 @@ impl CurrentProject {
         let project_xml = fs::read_to_string(&path)?;
         let project = serde_xml_rs::from_str::<Project>(&project_xml)?;
         let project_name = path.as_ref().file_name().map(|p| p.to_string_lossy().to_string()).unwrap_or(String::new());
+        let project_file = path.as_ref().to_path_buf();
         let path = path.as_ref().parent().unwrap().to_string_lossy().to_string();
 
-        let readers = project.signals.iter().map(|source| {
-            let path = Path::new(&path).join(&source.path);
-            let mut reader = EpochReader::new(&path);
-            if let Ok(reader) = &mut reader {
-                reader.set_start_align_offset(project.epochs_before_current as u64 * EpochReader::EPOCH_DURATION as u64 * 1000);
-                reader.set_offset(source.offset);
-            }
-            reader
-        }).collect::<Result<Vec<_>, _>>()?;
+        // Sources which were moved (e.g. referenced files) have to be relinked before the project can be opened
+        let missing = project.signals.iter().enumerate()
+            .filter(|(_, source)| !Path::new(&path).join(&source.path).is_file())
+            .map(|(i, source)| (i, source.clone()))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Box::new(MissingSources { project_file, sources: missing }));
+        }
+
+        let mut result = Self::from_project(path, project_name, project)?;
+        result.load_labels()?;
+        result.load_session();
+
+        Ok(result)
+    }
+
+    /// Opens a single EDF file in a read-only project which only exists in memory
+    pub fn quick_view<P>(path: P) -> Result<Self, Box<dyn Error>> where P : AsRef<Path> {
+        let file = path.as_ref().to_string_lossy().to_string();
+        let mut signals = ProjectSignals::from_path(file);
+        signals.is_reference = true;
 
-        let mut result = Self {
+        let config = ProjectConfiguration {
+            name: path.as_ref().file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            data: vec![signals],
+            ..Default::default()
+        };
+        let project_name = path.as_ref().file_name().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let directory = path.as_ref().parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+
+        let mut result = Self::from_project(directory, project_name, Project::from_config(&config))?;
+        result.scorings = Some(Scorings::new(result.project.epoch_duration));
+        result.is_quick_view = true;
+
+        Ok(result)
+    }
+
+    fn from_project(path: String, project_name: String, project: Project) -> Result<Self, Box<dyn Error>> {
+        let errors = project.stage_vocabulary.validate();
+        if !errors.is_empty() {
+            return Err(format!("The stage vocabulary {} cannot be used: {}", project.stage_vocabulary.name, errors.join(", ")).into());
+        }
+
+        let readers = Self::open_readers(&path, &project.signals, project.epochs_before_current, project.filter_signal)?;
+
+        Ok(Self {
             path,
             project_name,
             readers,
@@ -291,21 +779,43 @@ impl CurrentProject {
             markers: Markers::default(),
             annotations: Annotations::default(),
             scorings: None,
+            other_scorings: BTreeMap::new(),
+            events: Events::default(),
+            history: History::default(),
             spectrogram: None,
-            loading_progress_spectrogram: None
-        };
-
-        result.load_labels()?;
+            loading_progress_spectrogram: None,
+            spectrogram_channel: None,
+            spectrogram_task: None,
+            derived_signals: Vec::new(),
+            montage_signals: Vec::new(),
+            session: SessionState::default(),
+            zoom_page: 0,
+            status: None,
+            is_quick_view: false
+        })
+    }
 
-        Ok(result)
+    /// Opens the readers of all signal sources relative to the project directory
+    fn open_readers(path: &str, signals: &[SignalSource], epochs_before_current: u8, filter_signal: bool) -> Result<Vec<EpochReader>, Box<dyn Error>> {
+        signals.iter().map(|source| {
+            let path = Path::new(path).join(&source.path);
+            let mut reader = EpochReader::new(&path);
+            if let Ok(reader) = &mut reader {
+                reader.set_start_align_offset(epochs_before_current as u64 * EpochReader::EPOCH_DURATION as u64 * 1000);
+                reader.set_offset(source.offset);
+                reader.set_channels(&source.channels);
+                reader.set_filtered(filter_signal);
+            }
+            reader
+        }).collect()
     }
 
     pub fn load_labels(&mut self) -> Result<(), Box<dyn Error>>{
         let project_path= Path::new(&self.path);
         let subdir_lables = project_path.join("lables");
-        let scores_file = subdir_lables.join("scores.json");
         let markers_file = subdir_lables.join("markers.json");
         let annotations_file = subdir_lables.join("annotations.json");
+        let events_file = subdir_lables.join("events.json");
 
         // Load stored markers collection file or get default
         self.markers = if markers_file.exists() {
@@ -325,478 +835,2372 @@ impl CurrentProject {
             Annotations::default()
         };
 
-        // Load stored scores collection file if required for project type
+        // Load the stored scores collection files of all scorer sets if required for project type
         if self.project.project_type == ProjectType::SleepScoring {
-            self.scorings = Some(if scores_file.exists() {
-                let scores_json = fs::read_to_string(scores_file)?;
-                serde_json::from_str::<Scorings>(&scores_json)?
+            let active_scorer = self.project.active_scorer_name();
+            self.other_scorings.clear();
+            for set in self.project.scorer_sets() {
+                let scores_file = subdir_lables.join(&set.file);
+                let scorings = if scores_file.exists() {
+                    let scores_json = fs::read_to_string(scores_file)?;
+                    serde_json::from_str::<Scorings>(&scores_json)?
+                }
+                else {
+                    Scorings::new(self.project.epoch_duration)
+                };
+
+                if set.name == active_scorer {
+                    self.scorings = Some(scorings);
+                } else {
+                    self.other_scorings.insert(set.name, scorings);
+                }
+            }
+
+            // Load stored events collection file or get default
+            self.events = if events_file.exists() {
+                let events_json = fs::read_to_string(events_file)?;
+                serde_json::from_str::<Events>(&events_json)?
             }
             else {
-                Scorings {
-                    epoch_duration: self.project.epoch_duration,
-                    values: BTreeMap::new()
-                }
-            });
+                Events::default()
+            };
         }
 
         Ok(())
     }
 
+    /// Loads the stored session state. Projects without a valid session state use the default one.
+    fn load_session(&mut self) {
+        let session_file = Path::new(&self.path).join("session.json");
+        let session = fs::read_to_string(&session_file).ok()
+            .and_then(|json| serde_json::from_str::<SessionState>(&json)
+                .inspect_err(|e| warn!("Error reading session state: {}", e))
+                .ok());
+
+        self.session = session.unwrap_or_default();
+        if let Some((before, after)) = self.session.context_epochs {
+            self.set_context_epochs(before, after);
+        }
+    }
+
     pub fn save(&self) -> Result<(), Box<dyn Error>> {
         let subdir_lables = Path::new(&self.path).join("lables");
-        let scores_file = subdir_lables.join("scores.json");
+        let events_file = subdir_lables.join("events.json");
+        let project_file = Path::new(&self.path).join(&self.project_name);
 
-        // Write current score collection file if required for project type
+        // Write the project file as the project settings (e.g. the analysis period) might have changed
+        fs::write(project_file, project_initializer::serialize_project(&self.project)?)?;
+        fs::write(Path::new(&self.path).join("session.json"), serde_json::to_string_pretty(&self.session)?)?;
+
+        // Markers and annotations can be imported
+        fs::write(subdir_lables.join("markers.json"), serde_json::to_string_pretty(&self.markers)?)?;
+        fs::write(subdir_lables.join("annotations.json"), serde_json::to_string_pretty(&self.annotations)?)?;
+
+        // Write the score collection files of all scorer sets if required for project type
         if self.project.project_type == ProjectType::SleepScoring {
-            let scores_json = serde_json::to_string_pretty(&self.scorings)?;
-            fs::write(scores_file, scores_json)?;
+            for set in self.project.scorer_sets() {
+                let Some(scorings) = self.scorings_of(&set.name) else {
+                    continue;
+                };
+                let scores_json = serde_json::to_string_pretty(scorings)?;
+                fs::write(subdir_lables.join(&set.file), scores_json)?;
+            }
+
+            let events_json = serde_json::to_string_pretty(&self.events)?;
+            fs::write(events_file, events_json)?;
         }
 
         Ok(())
     }
-}
 
-impl NoctiG {
-    fn boot() -> (NoctiG, Task<Message>) {
-        let (_new_id, task) = window::open(WindowType::MainWindow.settings());
+    /// Gets the scores of the scorer set with the given name
+    pub fn scorings_of(&self, name: &str) -> Option<&Scorings> {
+        if self.project.active_scorer_name() == name {
+            self.scorings.as_ref()
+        } else {
+            self.other_scorings.get(name)
+        }
+    }
 
-        (NoctiG {
-            current_page: Page::Home,
-            window_time_formatter_index: 1,
-            draw_ranges: false,
-            is_showing_help: false,
-            project_creation: None,
-            search_text: String::new(),
-            current_project: None,
-            recent_projects: Vec::new(),
-            search_task_id: String::new(),
-            filtered_recent_projects: None,
-            windows: BTreeMap::new(),
-            licenses: LazyLock::new(|| load_licenses())
-        }, Task::batch([
-            Task::done(Message::LoadStartPage),
-            task.map(move |id| Message::WindowOpened(id, WindowType::MainWindow)),
-        ]))
+    /// Makes the scorer set with the given name the one which is edited in the scorer
+    pub fn switch_scorer(&mut self, name: &str) {
+        let active_scorer = self.project.active_scorer_name();
+        if active_scorer == name {
+            return;
+        }
+        let Some(scorings) = self.other_scorings.remove(name) else {
+            return;
+        };
+
+        if let Some(previous) = self.scorings.replace(scorings) {
+            self.other_scorings.insert(active_scorer, previous);
+        }
+        self.project.active_scorer = Some(name.to_string());
+
+        // Changes of the previous scorer set can no longer be undone
+        self.history = History::default();
     }
 
-    fn calculate_spectrogram(path: String, source_path: String, signal_index: usize) -> impl Stream<Item = Message> {
-        channel(0, move |mut output: Sender<Message>| async move {
-            output.send(Message::SpectrogramLoadStart).await.unwrap();
+    /// Adds a new empty scorer set and makes it the active one
+    pub fn add_scorer(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let name = name.trim();
+        let mut sets = self.project.scorer_sets();
+        if name.is_empty() {
+            return Err("Scorer name must not be empty".into());
+        }
+        if sets.iter().any(|s| s.name == name) {
+            return Err(format!("Scorer '{}' already exists", name).into());
+        }
 
-            const SAMPLE_LOAD_PERCENTAGE: f32 = 0.75;
+        // Get a unique scores file name for the scorer set
+        let base_name = format!("scores_{}", project_initializer::sanitize_file_name(name).replace(' ', "_"));
+        let mut file = format!("{}.json", base_name);
+        let mut counter = 1;
+        while sets.iter().any(|s| s.file == file) {
+            counter += 1;
+            file = format!("{}-{}.json", base_name, counter);
+        }
 
-            let mut reader = EDFFile::open(&Path::new(&path).join(&source_path)).unwrap();
-
-            // Get the target signal header
-            let signal = reader.header.get_signals().get(signal_index).cloned().unwrap();
-
-            // Get parameters from record and signal headers
-            let sf = reader.header.get_signal_sample_frequency(signal_index).unwrap();
-            let win_sec = 30.0;
-            let nperseg = (win_sec * sf) as i32;
-            let record_count = reader.header.get_record_count().unwrap();
-            let record_samples = signal.samples_count;
-            let sample_count = record_samples * record_count;
-
-            // Collect all samples of the target signal across all records
-            let mut i = 0;
-            let mut spectro_samples = vec![0.0; sample_count];
-            let mut last_progress = 0;
-            while let Ok(Some(record)) = reader.read_record() {
-                spectro_samples[i * record_samples..(i + 1) * record_samples].copy_from_slice(&record.get_physical_samples(&signal)[signal_index]);
-                i += 1;
-                let progress = (100.0 * i as f32 / record_count as f32).round() as u16;
-                if progress > last_progress {
-                    last_progress = progress;
-                    output.send(Message::SpectrogramLoadProgress(progress as f32 * SAMPLE_LOAD_PERCENTAGE)).await.unwrap();
-                }
-            }
+        sets.push(ScorerSet { name: name.to_string(), file });
+        self.project.scorers = sets;
+        self.other_scorings.insert(name.to_string(), Scorings::new(self.project.epoch_duration));
+        self.switch_scorer(name);
 
-            // Calculate the spectrogram from the collected samples
-            let spectro_samples = Array1::<f64>::from_vec(spectro_samples);
-            let spectrogram = spectrogram_lspopt(spectro_samples, sf, nperseg);
+        Ok(())
+    }
 
-            output.send(Message::SpectrogramLoadFinish(spectrogram)).await.unwrap();
-        })
+    fn snapshot_labels(&self) -> LabelSnapshot {
+        LabelSnapshot {
+            scorings: self.scorings.clone(),
+            events: self.events.values.clone()
+        }
     }
 
-    fn update(&mut self, message: Message) -> Task<Message> {
-        match message {
-            Message::WindowClosed(id) => {
-                self.windows.remove(&id);
-                return if self.windows.is_empty() {
-                    iced::exit()
-                } else {
-                    Task::none()
-                }
-            }
-            Message::OpenWindow(window_type) => {
-                let Some(last_window) = self.windows.keys().last() else {
-                    return Task::none();
-                };
+    fn restore_labels(&mut self, snapshot: LabelSnapshot) {
+        if self.scorings.is_some() && snapshot.scorings.is_some() {
+            self.scorings = snapshot.scorings;
+        }
+        self.events.values = snapshot.events;
+    }
 
-                let window_type2 = window_type.clone();
-                return window::position(*last_window)
-                    .then(move |last_position| {
-                        let mut settings = window_type.settings();
+    /// Stores the current labels in the history. Has to be called before every change to the labels.
+    pub fn record_change(&mut self) {
+        let snapshot = self.snapshot_labels();
+        self.history.record(snapshot);
+    }
+
+    pub fn undo(&mut self) {
+        let current = self.snapshot_labels();
+        if let Some(previous) = self.history.undo(current) {
+            self.restore_labels(previous);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        let current = self.snapshot_labels();
+        if let Some(next) = self.history.redo(current) {
+            self.restore_labels(next);
+        }
+    }
+
+    /// Gets the index of the signal source and the label of the channel
+    /// for the given signal index across all readers
+    pub fn signal_at(&self, signal_index: usize) -> Option<(usize, String)> {
+        let mut base_index = 0;
+        for (source, reader) in self.readers.iter().enumerate() {
+            if signal_index < base_index + reader.signal_count() {
+                let label = reader.get_signals().iter().filter(|s| !s.is_annotation()).nth(signal_index - base_index)?.label.clone();
+                return Some((source, label));
+            }
+            base_index += reader.signal_count();
+        }
+
+        None
+    }
+
+    /// Gets the index of the signal with the given label across all sources (the inverse of `signal_at`)
+    pub fn signal_index(&self, label: &str) -> Option<usize> {
+        let mut base_index = 0;
+        for reader in &self.readers {
+            let position = reader.get_signals().iter()
+                .filter(|s| !s.is_annotation())
+                .position(|s| s.label.trim().eq_ignore_ascii_case(label.trim()));
+            if let Some(position) = position {
+                return Some(base_index + position);
+            }
+            base_index += reader.signal_count();
+        }
+
+        None
+    }
+
+    /// Calculates the derived channels from the last read epochs. Channels which cannot be calculated
+    /// (e.g. as a used channel does not exist anymore) are left out.
+    pub fn update_derived_signals(&mut self) {
+        self.derived_signals = self.project.derived_channels.iter().enumerate()
+            .filter_map(|(i, channel)| channel.chart_signal(i, &self.readers, &channel.display())
+                .inspect_err(|e| warn!("Error calculating derived channel {}: {}", channel.name, e))
+                .ok())
+            .collect();
+    }
+
+    /// Gets the active montage, if any
+    pub fn active_montage(&self) -> Option<&Montage> {
+        let name = self.project.active_montage.as_ref()?;
+        self.project.montages.iter().find(|m| m.name == *name)
+    }
+
+    /// Calculates the channels of the active montage from the last read epochs in the order of the montage. Channels
+    /// of the sources keep their index across all readers, so selections and events work like in the default view.
+    pub fn update_montage_signals(&mut self) {
+        let Some(montage) = self.active_montage() else {
+            self.montage_signals = Vec::new();
+            return;
+        };
+
+        let sources = self.readers.iter().map(|r| r.get_channels().as_slice()).collect::<Vec<_>>();
+        let base_indices = self.readers.iter()
+            .scan(0, |index, reader| {
+                let base_index = *index;
+                *index += reader.signal_count();
+                Some(base_index)
+            })
+            .collect::<Vec<_>>();
+        let derived_index = self.readers.iter().map(|r| r.signal_count()).sum::<usize>();
+
+        let signals = montage.channels.iter().filter_map(|channel| match channel.resolve(&sources, &self.project.derived_channels)? {
+            ResolvedChannel::Source(found) => {
+                let reader = &self.readers[found.source];
+                let display = channel.apply(reader.get_channels()[found.channel].display());
+                let mut signal = reader.chart_signal(found.channel, &display)?;
+                signal.index += base_indices[found.source];
+                Some((Some(found.source), signal))
+            }
+            ResolvedChannel::Derived(index) => {
+                let derived = &self.project.derived_channels[index];
+                derived.chart_signal(derived_index + index, &self.readers, &channel.apply(derived.display()))
+                    .inspect_err(|e| warn!("Error calculating derived channel {}: {}", derived.name, e))
+                    .ok()
+                    .map(|signal| (None, signal))
+            }
+        }).collect();
+
+        self.montage_signals = signals;
+    }
+
+    /// Activates the montage with the given name or the default view showing all visible channels
+    pub fn set_montage(&mut self, name: Option<String>) {
+        self.project.active_montage = name.filter(|n| self.project.montages.iter().any(|m| m.name == *n));
+        self.update_montage_signals();
+    }
+
+    /// Gets all pages of channels which can be shown, starting with the default view
+    pub fn montage_pages(&self) -> Vec<MontagePage> {
+        [MontagePage::All].into_iter()
+            .chain(self.project.montages.iter().map(|m| MontagePage::Montage(m.name.clone())))
+            .collect()
+    }
+
+    pub fn montage_page(&self) -> MontagePage {
+        self.active_montage().map_or(MontagePage::All, |m| MontagePage::Montage(m.name.clone()))
+    }
+
+    /// Switches to the next or previous montage, the default view is part of the cycle
+    pub fn cycle_montage(&mut self, step: i32) {
+        let count = self.project.montages.len() as i32 + 1;
+        let current = self.active_montage()
+            .and_then(|active| self.project.montages.iter().position(|m| m.name == active.name))
+            .map_or(0, |i| i as i32 + 1);
+
+        let next = (current + step).rem_euclid(count);
+        self.set_montage((next > 0).then(|| self.project.montages[next as usize - 1].name.clone()));
+    }
+
+    /// Gets the start in milliseconds relative to the first epoch and the duration in milliseconds of the visible time span
+    pub fn visible_span(&self) -> (i64, i64) {
+        let epoch_millis = EpochReader::EPOCH_DURATION as i64 * 1_000;
+        let current_start = self.current_epoch() as i64 * epoch_millis;
+        match self.session.zoom {
+            Some(seconds) => {
+                let duration = seconds as i64 * 1_000;
+                (current_start + self.zoom_page as i64 * duration, duration)
+            }
+            None => {
+                let (before, after) = self.context_epochs();
+                let segment_count = before as i64 + after as i64 + 1;
+                (current_start - before as i64 * epoch_millis, segment_count * epoch_millis)
+            }
+        }
+    }
+
+    /// Gets all time spans which can be shown, from the smallest part of an epoch to the most context epochs
+    pub fn time_spans(&self) -> Vec<TimeSpan> {
+        ZOOM_SPANS.iter().rev().map(|s| TimeSpan::Part(*s))
+            .chain((0..=project_settings::MAX_CONTEXT_EPOCHS).map(TimeSpan::Epochs))
+            .collect()
+    }
+
+    /// Gets the shown time span, if it is one of the time spans which can be selected (i.e. the context is symmetric)
+    pub fn time_span(&self) -> Option<TimeSpan> {
+        match self.session.zoom {
+            Some(seconds) => Some(TimeSpan::Part(seconds)),
+            None => {
+                let (before, after) = self.context_epochs();
+                (before == after).then_some(TimeSpan::Epochs(before))
+            }
+        }
+    }
+
+    /// Gets the time span which is the given amount of steps larger (positive) or smaller (negative) than the shown one
+    pub fn step_time_span(&self, steps: i32) -> TimeSpan {
+        let spans = self.time_spans();
+        let current = self.time_span()
+            .unwrap_or_else(|| {
+                let (before, after) = self.context_epochs();
+                TimeSpan::Epochs(before.max(after))
+            });
+        let index = spans.iter().position(|s| *s == current).unwrap_or(0) as i32;
+
+        spans[(index + steps).clamp(0, spans.len() as i32 - 1) as usize]
+    }
+
+    /// Zooms into the part of the current epoch with the given length. The visible part stays at the same time if possible.
+    pub fn set_zoom(&mut self, seconds: Option<u32>) {
+        let offset = self.session.zoom.map_or(0, |current| current * self.zoom_page);
+        self.session.zoom = seconds;
+        self.zoom_page = seconds.map_or(0, |seconds| offset / seconds);
+    }
+
+    /// Gets the amount of epochs shown before and after the current epoch
+    pub fn context_epochs(&self) -> (u8, u8) {
+        self.session.context_epochs.unwrap_or((self.project.epochs_before_current, self.project.epochs_after_current))
+    }
+
+    /// Changes the amount of epochs shown before and after the current epoch within the session, the project keeps
+    /// its default. The readers have to be moved to the current epoch again to read the new amount of epochs
+    /// (e.g. using `seek_epoch`).
+    pub fn set_context_epochs(&mut self, before: u8, after: u8) {
+        let default = (self.project.epochs_before_current, self.project.epochs_after_current);
+        self.session.context_epochs = Some((before, after)).filter(|c| *c != default);
+        for reader in &mut self.readers {
+            reader.set_start_align_offset(before as u64 * EpochReader::EPOCH_DURATION as u64 * 1000);
+        }
+    }
+
+    /// Moves the visible part of the current epoch when zoomed in. Returns false if the part would leave the current
+    /// epoch, i.e. the axis has to be moved to the next or previous epoch.
+    fn move_zoom_page(&mut self, direction: i8) -> bool {
+        let Some(seconds) = self.session.zoom else {
+            return false;
+        };
+
+        let page = self.zoom_page as i64 + direction as i64;
+        if page < 0 || page >= EpochReader::EPOCH_DURATION.div_ceil(seconds) as i64 {
+            return false;
+        }
+
+        self.zoom_page = page as u32;
+        true
+    }
+
+    /// Gets the key of the channel with the given index across all readers, followed by the derived channels
+    pub fn channel_key(&self, signal_index: usize) -> Option<ChannelKey> {
+        let source_count = self.readers.iter().map(|r| r.signal_count()).sum::<usize>();
+        if signal_index < source_count {
+            let (source, label) = self.signal_at(signal_index)?;
+            return Some(ChannelKey::Source { source, label });
+        }
+
+        let channel = self.project.derived_channels.get(signal_index - source_count)?;
+        Some(ChannelKey::Derived { name: channel.name.clone() })
+    }
+
+    /// Gets the key of a shown signal of the given source (none for derived channels)
+    pub fn signal_key(source: Option<usize>, signal: &ChartSignal) -> ChannelKey {
+        match source {
+            Some(source) => ChannelKey::Source { source, label: signal.label.clone() },
+            None => ChannelKey::Derived { name: signal.label.clone() }
+        }
+    }
+
+    pub fn channel_type(&self, key: &ChannelKey) -> Option<ChannelType> {
+        match key {
+            ChannelKey::Source { source, label } => self.readers.get(*source)?.get_channels().iter()
+                .find(|c| c.label == *label)
+                .map(|c| c.channel_type),
+            ChannelKey::Derived { name } => self.project.derived_channels.iter()
+                .find(|c| c.name == *name)
+                .map(|c| c.channel_type)
+        }
+    }
+
+    /// Gets the keys of all channels of the sources and derived channels with the same type as the given channel
+    fn channel_keys_of_type(&self, key: &ChannelKey) -> Vec<ChannelKey> {
+        let channel_type = self.channel_type(key);
+        let sources = self.readers.iter().enumerate().flat_map(|(source, reader)| reader.get_channels().iter()
+            .map(move |c| ChannelKey::Source { source, label: c.label.clone() }));
+        let derived = self.project.derived_channels.iter()
+            .map(|c| ChannelKey::Derived { name: c.name.clone() });
+
+        sources.chain(derived).filter(|k| self.channel_type(k) == channel_type).collect()
+    }
+
+    pub fn channel_view(&self, key: &ChannelKey) -> ChannelView {
+        self.session.channel_views.iter()
+            .find(|v| v.channel == *key)
+            .cloned()
+            .unwrap_or_else(|| ChannelView::new(key.clone()))
+    }
+
+    /// Applies the scaling of the view of the channel to the signal
+    pub fn scaled_signal(&self, signal: ChartSignal, view: &ChannelView) -> ChartSignal {
+        // Automatic scaling only considers the samples of the current epoch
+        let count = signal.points.len();
+        let (before, after) = self.context_epochs();
+        let segment_count = before as usize + after as usize + 1;
+        let before = before as usize;
+        let current = count * before / segment_count..count * (before + 1) / segment_count;
+
+        signal.scaled(view.scale, view.height, current)
+    }
+
+    /// Gets the signal of the channel as it is currently shown, without the scaling of its view
+    fn shown_signal(&self, key: &ChannelKey) -> Option<ChartSignal> {
+        if self.active_montage().is_some() {
+            return self.montage_signals.iter()
+                .find(|(source, signal)| Self::signal_key(*source, signal) == *key)
+                .map(|(_, signal)| signal.clone());
+        }
+
+        match key {
+            ChannelKey::Source { source, label } => self.readers.get(*source)?.get_chart_signals().into_iter().find(|s| s.label == *label),
+            ChannelKey::Derived { name } => self.derived_signals.iter().find(|s| s.label == *name).cloned()
+        }
+    }
+
+    /// Changes the view of the channel with the given index or of all channels of its type. Views which do not
+    /// differ from the default are removed, so the session only contains the changed channels.
+    pub fn change_channel_view(&mut self, signal_index: usize, change: ChannelViewChange, all_of_type: bool) {
+        let Some(key) = self.channel_key(signal_index) else {
+            return;
+        };
+
+        let keys = if all_of_type {
+            self.channel_keys_of_type(&key)
+        } else {
+            vec![key]
+        };
+
+        for key in keys {
+            let mut view = self.channel_view(&key);
+            let gain = self.shown_signal(&key).and_then(|s| self.scaled_signal(s, &view).gain(view.height));
+            change.apply(&mut view, gain);
+            self.set_channel_view(view);
+        }
+    }
+
+    /// Applies the scaling, polarity and height of the channel with the given index to all channels of its type
+    pub fn apply_channel_view_to_type(&mut self, signal_index: usize) {
+        let Some(key) = self.channel_key(signal_index) else {
+            return;
+        };
+
+        let view = self.channel_view(&key);
+        for other in self.channel_keys_of_type(&key) {
+            self.set_channel_view(ChannelView { channel: other, ..view.clone() });
+        }
+    }
+
+    fn set_channel_view(&mut self, view: ChannelView) {
+        self.session.channel_views.retain(|v| v.channel != view.channel);
+        if !view.is_default() {
+            self.session.channel_views.push(view);
+        }
+    }
+
+    /// Gets all channels the spectrogram can be calculated of
+    pub fn spectrogram_channels(&self) -> Vec<SpectrogramChannel> {
+        let signals = self.readers.iter().enumerate().flat_map(|(source, reader)| {
+            let channels = reader.get_channels();
+            reader.get_signals().iter().enumerate()
+                .filter(|(_, s)| !s.is_annotation())
+                .zip(channels)
+                .map(move |((index, _), channel)| SpectrogramChannel::Signal { source, index, name: channel.display_name().to_string() })
+        });
+        let derived = self.project.derived_channels.iter().enumerate()
+            .map(|(index, channel)| SpectrogramChannel::Derived { index, name: channel.name.clone() });
+
+        signals.chain(derived).collect()
+    }
+
+    /// Calculates the spectrogram of the selected channel (the first signal of the second source by default)
+    /// in the background. A spectrogram which is still being calculated is aborted.
+    fn load_spectrogram(&mut self) -> Task<Message> {
+        let channels = self.spectrogram_channels();
+        let default = channels.iter()
+            .find(|c| matches!(c, SpectrogramChannel::Signal { source, .. } if *source == self.project.signals.len().min(2) - 1))
+            .or(channels.first());
+        let Some(channel) = self.spectrogram_channel.as_ref().filter(|c| channels.contains(c)).or(default).cloned() else {
+            return Task::none();
+        };
+
+        let stream = match &channel {
+            SpectrogramChannel::Signal { source, index, .. } => {
+                let source = self.project.signals[*source].clone();
+                Task::stream(NoctiG::calculate_spectrogram(self.path.clone(), source, *index))
+            }
+            SpectrogramChannel::Derived { index, .. } => {
+                let Some(derived) = self.project.derived_channels.get(*index).cloned() else {
+                    return Task::none();
+                };
+                Task::stream(NoctiG::calculate_derived_spectrogram(self.path.clone(), self.project.signals.clone(), derived))
+            }
+        };
+
+        self.spectrogram = None;
+        self.loading_progress_spectrogram = Some(0.0);
+        self.spectrogram_channel = Some(channel);
+
+        let (task, handle) = stream.abortable();
+        self.spectrogram_task = Some(handle.abort_on_drop());
+        task
+    }
+
+    /// Gets the index of the currently focused epoch across all readers
+    pub fn current_epoch(&self) -> u64 {
+        self.readers.iter()
+            .max_by(|r1, r2| r1.get_epoch_count().cmp(&r2.get_epoch_count()))
+            .map(|r| r.get_window_start_epoch())
+            .unwrap_or(0)
+    }
+
+    /// Gets the amount of epochs of the longest reader
+    pub fn epoch_count(&self) -> u64 {
+        self.readers.iter().map(|r| r.get_epoch_count()).max().unwrap_or(0)
+    }
+
+    /// Gets the absolute time of the start of the first epoch of the project, taking the offset of the sources into account
+    pub fn start_time(&self) -> Option<NaiveDateTime> {
+        let (reader, source) = self.readers.first().zip(self.project.signals.first())?;
+        let start_millis = reader.start_timestamp() as i64 * 1_000 - source.offset as i64;
+        DateTime::from_timestamp_millis(start_millis).map(|t| t.naive_utc())
+    }
+
+    /// Finds the channel with the given label across all signal sources and gets the index of its source and its label
+    pub fn find_channel(&self, label: &str) -> Option<(usize, String)> {
+        self.readers.iter().enumerate().find_map(|(source, reader)| {
+            reader.get_signals().iter()
+                .filter(|s| !s.is_annotation())
+                .find(|s| s.label.trim().eq_ignore_ascii_case(label.trim()))
+                .map(|s| (source, s.label.clone()))
+        })
+    }
+
+    /// Gets the epochs within the lights-off / lights-on period. Epochs which are only partially
+    /// covered are included. In case no period is set, the entire recording is used.
+    pub fn analysis_period(&self) -> AnalysisPeriod {
+        let epoch_millis = EpochReader::EPOCH_DURATION as u64 * 1_000;
+        let epoch_count = self.epoch_count();
+        let start = self.project.lights_off.map(|t| t / epoch_millis).unwrap_or(0);
+        let end = self.project.lights_on.map(|t| t.div_ceil(epoch_millis)).unwrap_or(epoch_count);
+
+        AnalysisPeriod::new(start, end.min(epoch_count))
+    }
+
+    /// Reads the lights-off / lights-on annotations from all EDF+ sources of the project and
+    /// returns the earliest lights-off and the latest lights-on time relative to the first epoch
+    pub fn detect_analysis_period(path: String, sources: Vec<(String, u64)>) -> (Option<u64>, Option<u64>) {
+        let mut lights_off: Option<u64> = None;
+        let mut lights_on: Option<u64> = None;
+
+        for (source, offset) in sources {
+            let annotations = match read_annotations(Path::new(&path).join(&source)) {
+                Ok(annotations) => annotations,
+                Err(e) => {
+                    warn!("Error reading annotations of source {}: {}", source, e);
+                    continue;
+                }
+            };
+
+            let (off, on) = find_lights_annotations(&annotations);
+            let to_millis = |onset: f64| (onset * 1_000.0).max(0.0) as u64 + offset;
+            if let Some(off) = off.map(to_millis) {
+                lights_off = Some(lights_off.map_or(off, |v| v.min(off)));
+            }
+            if let Some(on) = on.map(to_millis) {
+                lights_on = Some(lights_on.map_or(on, |v| v.max(on)));
+            }
+        }
+
+        (lights_off, lights_on)
+    }
+}
+
+impl NoctiG {
+    fn boot(paths: Vec<PathBuf>) -> (NoctiG, Task<Message>) {
+        let (_new_id, task) = window::open(WindowType::MainWindow.settings());
+
+        (NoctiG {
+            current_page: Page::Home,
+            window_time_formatter_index: 1,
+            draw_ranges: false,
+            is_showing_help: false,
+            is_showing_statistics: false,
+            is_showing_comparison: false,
+            comparison_scorer: None,
+            new_scorer_name: String::new(),
+            adjudication: None,
+            import_preview: None,
+            project_settings: None,
+            relink: None,
+            project_creation: None,
+            project_presets: Vec::new(),
+            preset_editor: None,
+            search_text: String::new(),
+            current_project: None,
+            recent_projects: Vec::new(),
+            search_task_id: String::new(),
+            filtered_recent_projects: None,
+            start_error: None,
+            windows: BTreeMap::new(),
+            licenses: LazyLock::new(|| load_licenses()),
+            stage_picker: None,
+            selection: None,
+            focused_signal: None,
+            scroll_accumulator: 0.0
+        }, Task::batch([
+            Task::done(Message::LoadStartPage),
+            task.map(move |id| Message::WindowOpened(id, WindowType::MainWindow))
+                .chain(Task::done(Message::OpenPaths(paths))),
+        ]))
+    }
+
+    /// Handles a project which could not be opened. Projects with missing signal sources can be relinked.
+    fn open_project_failed(&mut self, error: Box<dyn Error>) -> Task<Message> {
+        let Some(missing) = error.downcast_ref::<MissingSources>() else {
+            eprintln!("Error opening project: {}", error);   // TODO: Show error message box
+            return Task::none();
+        };
+
+        let last_browsed = get_last_browse_source_path().ok().flatten().map(PathBuf::from);
+        self.relink = Some(Relink::new(missing, last_browsed.into_iter().collect()));
+        self.current_page = Page::RelinkSources;
+
+        Task::done(Message::RelinkSearch)
+    }
+
+    /// Opens the project creation wizard with the given configuration. The stored presets are loaded to start from one of them.
+    fn open_create_wizard(&mut self, config: ProjectConfiguration, page: CreatePage) {
+        self.project_presets = get_project_presets().unwrap_or_default();
+        self.project_creation = Some(config);
+        self.current_page = Page::CreateProject(page);
+    }
+
+    /// Gets the preset selected within the preset editor
+    fn edited_preset(&mut self) -> Option<&mut ProjectPreset> {
+        self.preset_editor.as_mut()?.selected_preset()
+    }
+
+    /// Gets the channel with the given index of the source with the given path within the project creation wizard
+    fn import_channel(&mut self, path: &str, index: usize) -> Option<&mut ChannelSettings> {
+        self.project_creation.as_mut()?
+            .data.iter_mut()
+            .find(|s| s.path == path)?
+            .channels.get_mut(index)
+    }
+
+    /// Gets the name of the scorer set the active scorer set is compared with
+    fn comparison_scorer(&self) -> Option<String> {
+        let project = self.current_project.as_ref()?;
+        let active_scorer = project.project.active_scorer_name();
+        let sets = project.project.scorer_sets();
+
+        self.comparison_scorer.clone()
+            .filter(|name| *name != active_scorer && sets.iter().any(|s| &s.name == name))
+            .or_else(|| sets.into_iter().map(|s| s.name).find(|name| *name != active_scorer))
+    }
+
+    /// Gets the agreement between the active scorer set and the one it is compared with
+    fn agreement(&self) -> Option<Agreement> {
+        let project = self.current_project.as_ref()?;
+        let other = project.scorings_of(&self.comparison_scorer()?)?;
+
+        Some(Agreement::compute(project.scorings.as_ref()?, other, &project.project.stage_vocabulary, project.epoch_count()))
+    }
+
+    fn calculate_spectrogram(path: String, source: SignalSource, signal_index: usize) -> impl Stream<Item = Message> {
+        channel(0, move |mut output: Sender<Message>| async move {
+            output.send(Message::SpectrogramLoadStart).await.unwrap();
+
+            // Use the precomputed spectrogram if available
+            let cache_path = spectrogram_cache::cache_path(Path::new(&path), &source, signal_index);
+            let cached = spectrogram_cache::load(&cache_path).ok();
+            if let Some(spectrogram) = cached {
+                output.send(Message::SpectrogramLoadFinish(spectrogram)).await.unwrap();
+                return;
+            }
+
+            const SAMPLE_LOAD_PERCENTAGE: f32 = 0.75;
+
+            // Progress updates are skipped while the previous one was not received yet
+            let result = spectrogram_cache::compute(&Path::new(&path).join(&source.path), signal_index, |progress| {
+                _ = output.try_send(Message::SpectrogramLoadProgress(progress as f32 * SAMPLE_LOAD_PERCENTAGE));
+            }).map_err(|e| e.to_string());
+            let spectrogram = match result {
+                Ok(spectrogram) => spectrogram,
+                Err(e) => {
+                    warn!("Error calculating spectrogram of {}: {}", source.path, e);
+                    return;
+                }
+            };
+            // Quick views have no processed directory to store the spectrogram in
+            if cache_path.parent().is_some_and(Path::is_dir) && let Err(e) = spectrogram_cache::save(&cache_path, &spectrogram) {
+                warn!("Error storing spectrogram in {}: {}", cache_path.display(), e);
+            }
+
+            output.send(Message::SpectrogramLoadFinish(spectrogram)).await.unwrap();
+        })
+    }
+
+    /// Builds the cache key of the spectrogram of a derived channel from its expression and the
+    /// source path, offset, content hash and label of every channel it uses
+    fn derived_cache_key(signals: &[SignalSource], readers: &[EpochReader], derived: &DerivedChannel) -> Result<String, Box<dyn Error>> {
+        let channels = readers.iter().map(|r| r.get_channels().as_slice()).collect::<Vec<_>>();
+        let mut key = derived.expression.clone();
+        for channel in derived.resolve(&channels)?.channels() {
+            let source = &signals[channel.source];
+            let label = &channels[channel.source][channel.channel].label;
+            key.push_str(&format!("\n{}|{}|{}|{}", source.path, source.offset, source.hash.as_deref().unwrap_or_default(), label));
+        }
+
+        Ok(format!("derived-{:X}", Md5::digest(key.as_bytes())))
+    }
+
+    /// Calculates the spectrogram of a derived channel by reading all epochs of the sources it is calculated from
+    fn calculate_derived_spectrogram(path: String, signals: Vec<SignalSource>, derived: DerivedChannel) -> impl Stream<Item = Message> {
+        channel(0, move |mut output: Sender<Message>| async move {
+            output.send(Message::SpectrogramLoadStart).await.unwrap();
+
+            const SAMPLE_LOAD_PERCENTAGE: f32 = 0.75;
+            const CHUNK_EPOCHS: u64 = 20;
+
+            let mut readers = match CurrentProject::open_readers(&path, &signals, 0, false) {
+                Ok(readers) => readers,
+                Err(e) => {
+                    warn!("Error opening sources of derived channel {}: {}", derived.name, e);
+                    return;
+                }
+            };
+
+            // Derived channels are identified by their expression and the channels it resolves to, as they can be renamed
+            let key = match Self::derived_cache_key(&signals, &readers, &derived) {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!("Error resolving derived channel {}: {}", derived.name, e);
+                    return;
+                }
+            };
+            let cache_path = spectrogram_cache::named_cache_path(Path::new(&path), &key);
+            let cached = spectrogram_cache::load(&cache_path).ok();
+            if let Some(spectrogram) = cached {
+                output.send(Message::SpectrogramLoadFinish(spectrogram)).await.unwrap();
+                return;
+            }
+
+            let epoch_count = readers.iter().map(|r| r.get_epoch_count()).max().unwrap_or(0);
+            for reader in &mut readers {
+                _ = reader.seek(0);
+            }
+
+            // Calculate the derived channel in chunks of epochs to limit the memory usage
+            let mut samples = Vec::new();
+            let mut sample_rate = 0.0;
+            let mut epoch = 0;
+            while epoch < epoch_count {
+                let count = CHUNK_EPOCHS.min(epoch_count - epoch);
+                for reader in &mut readers {
+                    if let Err(e) = reader.read_epochs(count as usize) {
+                        warn!("Error reading sources of derived channel {}: {}", derived.name, e);
+                        return;
+                    }
+                }
+                let (chunk, rate) = match derived.samples(&readers) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        warn!("Error calculating derived channel {}: {}", derived.name, e);
+                        return;
+                    }
+                };
+
+                // Gaps (e.g. due to offsets between the sources) are filled with zeros
+                samples.extend(chunk.into_iter().map(|v| if v.is_finite() { v } else { 0.0 }));
+                sample_rate = rate;
+                epoch += count;
+                output.send(Message::SpectrogramLoadProgress(100.0 * epoch as f32 / epoch_count as f32 * SAMPLE_LOAD_PERCENTAGE)).await.unwrap();
+            }
+
+            let nperseg = (30.0 * sample_rate) as i32;
+            let spectrogram = spectrogram_lspopt(Array1::from_vec(samples), sample_rate, nperseg);
+            if cache_path.parent().is_some_and(Path::is_dir) && let Err(e) = spectrogram_cache::save(&cache_path, &spectrogram) {
+                warn!("Error storing spectrogram in {}: {}", cache_path.display(), e);
+            }
+
+            output.send(Message::SpectrogramLoadFinish(spectrogram)).await.unwrap();
+        })
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::WindowClosed(id) => {
+                self.windows.remove(&id);
+                return if self.windows.is_empty() {
+                    iced::exit()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::OpenWindow(window_type) => {
+                let Some(last_window) = self.windows.keys().last() else {
+                    return Task::none();
+                };
+
+                let window_type2 = window_type.clone();
+                return window::position(*last_window)
+                    .then(move |last_position| {
+                        let mut settings = window_type.settings();
                         settings.position = last_position.map_or(Position::Default, |last_position| {
                             Position::Specific(last_position + Vector::new(20.0, 20.0))
                         });
                         let (_, open) = window::open(settings);
 
-                        open
-                    })
-                    .map(move |id| Message::WindowOpened(id, window_type2.clone()))
-            }
-            Message::WindowOpened(id, window_type) => {
-                self.windows.insert(id, window_type);
-            }
-            Message::ToggleExpandLicense(category_idx, idx, is_expanded) => {
-                self.licenses[category_idx][idx].set_expanded(is_expanded);
+                        open
+                    })
+                    .map(move |id| Message::WindowOpened(id, window_type2.clone()))
+            }
+            Message::WindowOpened(id, window_type) => {
+                self.windows.insert(id, window_type);
+            }
+            Message::ToggleExpandLicense(category_idx, idx, is_expanded) => {
+                self.licenses[category_idx][idx].set_expanded(is_expanded);
+            }
+            Message::LoadStartPage => {
+                if let Ok(recent) = get_recently_opened(25) {
+                    self.recent_projects = recent;
+                }
+            }
+            Message::MoveAxis(direction) => {
+                self.stage_picker = None;
+
+                // When zoomed in, the axis moves through the parts of the current epoch first
+                if let Some(project) = &mut self.current_project && project.move_zoom_page(direction) {
+                    return Task::none();
+                }
+                if !move_axis(self, direction) {
+                    return Task::none();
+                }
+
+                // Moving backwards continues with the last part of the previous epoch
+                if let Some(project) = &mut self.current_project {
+                    project.zoom_page = match project.session.zoom {
+                        Some(seconds) if direction < 0 => EpochReader::EPOCH_DURATION.div_ceil(seconds) - 1,
+                        _ => 0
+                    };
+                }
+            },
+            Message::SetTimeSpan(span) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                match span {
+                    TimeSpan::Part(seconds) => project.set_zoom(Some(seconds)),
+                    TimeSpan::Epochs(context) => {
+                        project.set_zoom(None);
+                        return Task::done(Message::SetContextEpochs(context, context));
+                    }
+                }
+            },
+            Message::StepTimeSpan(steps) => {
+                if let Some(project) = &self.current_project {
+                    return Task::done(Message::SetTimeSpan(project.step_time_span(steps)));
+                }
+            },
+            Message::SetContextEpochs(before, after) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+                if project.context_epochs() == (before, after) {
+                    return Task::none();
+                }
+
+                // Read the new amount of epochs around the current epoch
+                let current_epoch = project.current_epoch();
+                let zoom_page = project.zoom_page;
+                project.set_context_epochs(before, after);
+                seek_epoch(self, current_epoch);
+                if let Some(project) = &mut self.current_project {
+                    project.zoom_page = zoom_page;
+                }
+            },
+            Message::ScrollAxis(lines) => {
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                // Accumulate partial scroll deltas (e.g. from trackpads) until at least one full epoch is reached
+                self.scroll_accumulator += lines;
+                let steps = self.scroll_accumulator.trunc();
+                if steps == 0.0 {
+                    return Task::none();
+                }
+                self.scroll_accumulator -= steps;
+                self.stage_picker = None;
+
+                let epoch = project.current_epoch().saturating_add_signed(steps as i64);
+                seek_epoch(self, epoch);
+            },
+            Message::SelectEpoch(epoch) => {
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                // Clicking the current epoch again toggles the stage picker
+                if project.current_epoch() == epoch && self.stage_picker == Some(epoch) {
+                    self.stage_picker = None;
+                    return Task::none();
+                }
+
+                if seek_epoch(self, epoch) {
+                    self.stage_picker = self.current_project.as_ref().map(|p| p.current_epoch());
+                }
+            },
+            Message::JumpToEpoch(epoch) => {
+                self.stage_picker = None;
+                seek_epoch(self, epoch);
+            },
+            Message::SetLightsOff | Message::SetLightsOn => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                // Use the current selection or the currently focused epoch as cursor
+                let epoch_millis = EpochReader::EPOCH_DURATION as i64 * 1_000;
+                let current_start = project.current_epoch() as i64 * epoch_millis;
+                let (start, end) = self.selection.as_ref()
+                    .map(|s| (s.start, s.end))
+                    .unwrap_or((current_start, current_start + epoch_millis));
+
+                if matches!(message, Message::SetLightsOff) {
+                    project.project.lights_off = Some(start.max(0) as u64);
+                } else {
+                    project.project.lights_on = Some(end.max(0) as u64);
+                }
+            },
+            Message::ResetAnalysisPeriod => {
+                if let Some(project) = &mut self.current_project {
+                    project.project.lights_off = None;
+                    project.project.lights_on = None;
+                }
+            },
+            Message::DetectAnalysisPeriod => {
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                let path = project.path.clone();
+                let sources = project.project.signals.iter().map(|s| (s.path.clone(), s.offset)).collect();
+                return Task::future(async move {
+                    let (lights_off, lights_on) = CurrentProject::detect_analysis_period(path, sources);
+                    Message::AnalysisPeriodDetected(lights_off, lights_on)
+                });
+            },
+            Message::AnalysisPeriodDetected(lights_off, lights_on) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                if lights_off.is_none() && lights_on.is_none() {
+                    warn!("No lights-off / lights-on annotations found in the sources");
+                }
+                project.project.lights_off = lights_off.or(project.project.lights_off);
+                project.project.lights_on = lights_on.or(project.project.lights_on);
+            },
+            Message::SelectRange(selection) => {
+                if let Some(selection) = &selection {
+                    self.focused_signal = Some(selection.signal_index);
+                }
+                self.selection = selection;
+            },
+            Message::FocusSignal(signal_index) => {
+                self.stage_picker = None;
+                self.selection = None;
+                self.focused_signal = Some(signal_index);
+            },
+            Message::ChangeChannelView(change, all_of_type) => {
+                let Some(signal_index) = self.focused_signal else {
+                    return Task::none();
+                };
+                if let Some(project) = &mut self.current_project {
+                    project.change_channel_view(signal_index, change, all_of_type);
+                }
+            },
+            Message::CycleTimeGrid => {
+                if let Some(project) = &mut self.current_project {
+                    project.session.grid.time_grid = project.session.grid.time_grid.next();
+                }
+            },
+            Message::CycleReferenceBand => {
+                if let Some(project) = &mut self.current_project {
+                    project.session.grid.cycle_reference_band();
+                }
+            },
+            Message::ApplyChannelViewToType => {
+                let Some(signal_index) = self.focused_signal else {
+                    return Task::none();
+                };
+                if let Some(project) = &mut self.current_project {
+                    project.apply_channel_view_to_type(signal_index);
+                }
+            },
+            Message::ClearSelection => {
+                self.stage_picker = None;
+                self.selection = None;
+            },
+            Message::Rate(stage) => {
+                self.stage_picker = None;
+
+                let Some(project) = self.current_project.as_mut().filter(|p| !p.is_quick_view) else {
+                    return Task::none();
+                };
+                let max_epoch = project.epoch_count();
+                let current_epoch = project.current_epoch();
+                project.record_change();
+                let Some(scorings) = &mut project.scorings else {
+                    return Task::none();
+                };
+
+                // Score all epochs covered by the current selection at once
+                let epochs = match self.selection.take() {
+                    Some(selection) => selection.epochs().filter(|e| *e < max_epoch).collect(),
+                    None => vec![current_epoch]
+                };
+
+                for epoch in epochs {
+                    match &stage {
+                        Some(stage) => scorings.values.insert(epoch, stage.clone()),
+                        None => scorings.values.remove(&epoch)
+                    };
+
+                    // Stages set during an adjudication were chosen by the adjudicator
+                    match (&stage, &self.adjudication) {
+                        (Some(_), Some(_)) => scorings.provenance.insert(epoch, PROVENANCE_ADJUDICATOR.to_string()),
+                        _ => scorings.provenance.remove(&epoch)
+                    };
+                }
+
+                // Continue with the next disagreement not adjudicated yet
+                let next = self.adjudication.as_ref()
+                    .filter(|_| stage.is_some())
+                    .and_then(|a| a.next_open(scorings, current_epoch));
+                if let Some(next) = next {
+                    seek_epoch(self, next);
+                }
+            },
+            Message::StartAdjudication => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                // Adjudicate all scorer sets except for the consensus itself
+                let scorers = project.project.scorer_sets().into_iter()
+                    .map(|s| s.name)
+                    .filter(|name| name != CONSENSUS_SCORER)
+                    .collect::<Vec<_>>();
+                if scorers.len() < 2 {
+                    warn!("At least two scorers are required for an adjudication");
+                    return Task::none();
+                }
+
+                // Create the consensus scorer set or continue with the existing one
+                if project.project.scorer_sets().iter().any(|s| s.name == CONSENSUS_SCORER) {
+                    project.switch_scorer(CONSENSUS_SCORER);
+                } else if let Err(e) = project.add_scorer(CONSENSUS_SCORER) {
+                    warn!("Error creating consensus scorer: {}", e);
+                    return Task::none();
+                }
+                project.record_change();
+
+                let epoch_count = project.epoch_count();
+                let Some(mut consensus) = project.scorings.take() else {
+                    return Task::none();
+                };
+                let others = scorers.into_iter()
+                    .filter_map(|name| project.scorings_of(&name).map(|s| (name, s)))
+                    .collect::<Vec<_>>();
+                let adjudication = Adjudication::prepare(&mut consensus, &others, epoch_count);
+                let first = adjudication.next_open(&consensus, project.current_epoch());
+                project.scorings = Some(consensus);
+
+                self.adjudication = Some(adjudication);
+                self.selection = None;
+                if let Some(first) = first {
+                    seek_epoch(self, first);
+                }
+            },
+            Message::StopAdjudication => {
+                self.adjudication = None;
+
+                // Write the consensus scores file
+                if let Some(project) = &mut self.current_project {
+                    project.status = Some(match project.save() {
+                        Ok(_) => StatusMessage::info("Saved the consensus scores".to_string()),
+                        Err(e) => StatusMessage::error(format!("Error saving the consensus scores: {}", e))
+                    });
+                }
+            },
+            Message::AcceptLabel(scorer) => {
+                let Some(adjudication) = &self.adjudication else {
+                    return Task::none();
+                };
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                let epoch = project.current_epoch();
+                let Some(stage) = project.scorings_of(&scorer).and_then(|s| s.values.get(&epoch)).cloned() else {
+                    return Task::none();
+                };
+
+                project.record_change();
+                let Some(consensus) = &mut project.scorings else {
+                    return Task::none();
+                };
+                consensus.values.insert(epoch, stage);
+                consensus.provenance.insert(epoch, scorer);
+
+                // Continue with the next disagreement not adjudicated yet
+                if let Some(next) = adjudication.next_open(consensus, epoch) {
+                    seek_epoch(self, next);
+                }
+            },
+            Message::AddEvent(event_type) => {
+                let Some(project) = self.current_project.as_mut().filter(|p| !p.is_quick_view) else {
+                    return Task::none();
+                };
+                let Some(selection) = self.selection.take() else {
+                    return Task::none();
+                };
+                let Some((source, channel)) = project.signal_at(selection.signal_index) else {
+                    return Task::none();
+                };
+
+                let onset = selection.start.max(0) as u64;
+                project.record_change();
+                project.events.insert(ScoredEvent {
+                    event_type,
+                    source,
+                    channel,
+                    onset,
+                    duration: (selection.end.max(0) as u64).saturating_sub(onset)
+                });
+            },
+            Message::RemoveEvent(index) => {
+                let Some(project) = self.current_project.as_mut().filter(|p| !p.is_quick_view) else {
+                    return Task::none();
+                };
+
+                if index < project.events.values.len() {
+                    project.record_change();
+                    project.events.values.remove(index);
+                }
+            },
+            Message::Undo => {
+                if let Some(project) = &mut self.current_project {
+                    project.undo();
+                }
+            },
+            Message::Redo => {
+                if let Some(project) = &mut self.current_project {
+                    project.redo();
+                }
+            },
+            Message::SwitchScorer(name) => {
+                self.adjudication = None;
+                if let Some(project) = &mut self.current_project {
+                    project.switch_scorer(&name);
+                }
+            },
+            Message::NewScorerNameChanged(name) => {
+                self.new_scorer_name = name;
+            },
+            Message::AddScorer => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                match project.add_scorer(&self.new_scorer_name) {
+                    Ok(()) => self.new_scorer_name = String::new(),
+                    Err(e) => warn!("Error adding scorer: {}", e)
+                }
+            },
+            Message::ToggleComparison => {
+                self.is_showing_comparison = !self.is_showing_comparison;
+            },
+            Message::CompareWith(name) => {
+                self.comparison_scorer = Some(name);
+            },
+            Message::NextDisagreement | Message::PreviousDisagreement => {
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                // Step through the epochs to adjudicate or the disagreements of the compared scorers
+                let disagreements = match &self.adjudication {
+                    Some(adjudication) => adjudication.epochs.clone(),
+                    None => match self.agreement() {
+                        Some(agreement) => agreement.disagreements,
+                        None => return Task::none()
+                    }
+                };
+
+                let current = project.current_epoch();
+                let target = if matches!(message, Message::NextDisagreement) {
+                    disagreements.iter().find(|e| **e > current)
+                } else {
+                    disagreements.iter().rev().find(|e| **e < current)
+                };
+
+                if let Some(epoch) = target.copied() {
+                    self.stage_picker = None;
+                    seek_epoch(self, epoch);
+                }
+            },
+            Message::RateKey(key) => {
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                if let Some(stage) = project.project.stage_vocabulary.by_key(&key) {
+                    return Task::done(Message::Rate(Some(stage.stage())));
+                }
+            },
+            Message::SpectrogramLoadStart => {
+
+            },
+            Message::SpectrogramLoadProgress(progress) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+                project.loading_progress_spectrogram = Some(progress);
+            },
+            Message::SpectrogramLoadFinish(spectrogram) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+                project.spectrogram = Some(SpectrogramView::new(spectrogram, "lajolla".to_string()));
+                project.spectrogram_task = None;
+            },
+            Message::SwitchMontage(page) => {
+                if let Some(project) = &mut self.current_project {
+                    project.set_montage(match page {
+                        MontagePage::All => None,
+                        MontagePage::Montage(name) => Some(name)
+                    });
+                }
+            },
+            Message::CycleMontage(step) => {
+                if let Some(project) = &mut self.current_project {
+                    project.cycle_montage(step);
+                }
+            },
+            Message::SpectrogramChannelChanged(channel) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+                project.spectrogram_channel = Some(channel);
+                return project.load_spectrogram();
+            },
+            Message::SeekTo => {
+                seek_epoch(self, 1100);
+            },
+            Message::CycleTimeFormatter => {
+                self.window_time_formatter_index = (self.window_time_formatter_index + 1) % formatting::formatters::TIME_FORMATTERS.len();
+            },
+            Message::ToggleRangeDraw => {
+                self.draw_ranges = !self.draw_ranges;
+            },
+            Message::ToggleHelp => {
+                self.is_showing_help = !self.is_showing_help;
+            },
+            Message::ToggleStatistics => {
+                self.is_showing_statistics = !self.is_showing_statistics;
+            },
+            Message::SwitchPage(page) => {
+                self.current_page = page
+            },
+            Message::CreateProjectWizard => {
+                self.open_create_wizard(ProjectConfiguration::default(), CreatePage::Project);
+            },
+            Message::SaveProject => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+                if project.is_quick_view {
+                    project.status = Some(StatusMessage::error("Quick views cannot be saved, create a project from the file first".to_string()));
+                    return Task::none();
+                }
+
+                if let Err(e) = project.save() {
+                    eprintln!("Error saving project: {}", e);
+                    return Task::none()
+                };
+
+                println!("SAVED");
+            },
+            Message::Export(format) => {
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                project.status = Some(match export::export(project, format, &project.analysis_period()) {
+                    Ok(path) => StatusMessage::info(format!("Exported {} to {}", format, path.display())),
+                    Err(e) => StatusMessage::error(format!("Error exporting {}: {}", format, e))
+                });
+            },
+            Message::LaunchImport => {
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+
+                return Task::future(async {
+                    Message::ImportFile(AsyncFileDialog::new()
+                        .add_filter("Scorings", &IMPORT_EXTENSIONS)
+                        .set_directory(path)
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::ImportFile(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                // Only show what would be imported, the labels are written when the preview is applied
+                match import::preview(project, &file) {
+                    Ok(preview) => self.import_preview = Some(preview),
+                    Err(e) => project.status = Some(StatusMessage::error(format!("Error importing {}: {}", file.display(), e)))
+                }
+            },
+            Message::ApplyImport => {
+                let Some(preview) = self.import_preview.take() else {
+                    return Task::none();
+                };
+
+                if let Some(project) = &mut self.current_project {
+                    import::apply(project, preview);
+                }
+            },
+            Message::CancelImport => {
+                self.import_preview = None;
+            },
+            Message::OpenProjectPath(path) => {
+                // The project was created using the wizard or opened from the recent projects
+                self.project_creation = None;
+                self.start_error = None;
+
+                match CurrentProject::load(path) {
+                    Ok(project) => self.current_project = Some(project),
+                    Err(e) => return self.open_project_failed(e)
+                }
+                return Task::done(Message::OpenScorer);
+            },
+            Message::LaunchQuickView => {
+                self.start_error = None;
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+
+                return Task::future(async {
+                    Message::QuickView(AsyncFileDialog::new()
+                        .add_filter("EDF/EDF+ File", &["edf"])
+                        .set_directory(path)
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::QuickView(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+
+                if let Some(parent) = file.parent() && let Some(path) = parent.to_str() {
+                    _ = set_last_browse_source_path(path.to_string());
+                }
+
+                match CurrentProject::quick_view(&file) {
+                    Ok(project) => self.current_project = Some(project),
+                    Err(e) => {
+                        self.start_error = Some(format!("Error opening {}: {}", file.display(), e));
+                        return Task::none();
+                    }
+                }
+
+                return Task::done(Message::OpenScorer);
+            },
+            Message::PromoteQuickView => {
+                let Some(project) = self.current_project.take_if(|p| p.is_quick_view) else {
+                    return Task::none();
+                };
+
+                // Create a project from the viewed file using the project creation wizard
+                let path = project.project.signals.iter().map(|s| s.path.clone()).collect::<Vec<_>>();
+                self.selection = None;
+                self.stage_picker = None;
+                self.open_create_wizard(ProjectConfiguration {
+                    name: project.project.name.clone(),
+                    data: path.into_iter().map(ProjectSignals::from_path).collect(),
+                    ..Default::default()
+                }, CreatePage::Data);
+            },
+            Message::OpenPaths(paths) => {
+                let extension = |path: &PathBuf| path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+                let (projects, others) = paths.into_iter().partition::<Vec<_>, _>(|p| extension(p) == "ngp");
+                let (signals, unsupported) = others.into_iter().partition::<Vec<_>, _>(|p| extension(p) == "edf");
+
+                // Started from a file association there is no terminal to report unsupported files in
+                if !unsupported.is_empty() {
+                    let files = unsupported.iter().map(|p| p.display().to_string()).collect::<Vec<_>>();
+                    self.start_error = Some(format!("Unsupported files, only projects (.ngp) and EDF files can be opened: {}", files.join(", ")));
+                }
+
+                // Open the project directly, only a single project can be opened at once
+                if let Some(project) = projects.into_iter().next() {
+                    return Task::done(Message::OpenProject(Some(project)));
+                }
+
+                // View a single signal source without creating a project
+                if signals.len() == 1 {
+                    return Task::done(Message::QuickView(signals.into_iter().next()));
+                }
+
+                // Prefill the project creation wizard with the given signal sources
+                if !signals.is_empty() {
+                    self.open_create_wizard(ProjectConfiguration {
+                        name: signals[0].file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                        data: signals.iter()
+                            .filter_map(|path| path.to_str())
+                            .map(|path| ProjectSignals::from_path(path.to_string()))
+                            .collect(),
+                        ..Default::default()
+                    }, CreatePage::Data);
+                }
+            },
+            Message::LaunchOpenProject => {
+                self.start_error = None;
+                let path = get_last_project_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or(String::new());
+
+                return Task::future(async {
+                    Message::OpenProject(AsyncFileDialog::new()
+                        .add_filter("NoctiG Project", &["ngp"])
+                        .set_directory(path)
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            }
+            Message::OpenProject(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+
+                if let Some(parent) = file.parent() && let Some(path) = parent.to_str() {
+                    _ = set_last_project_path(path.to_string());
+                }
+
+                let path = file.to_str().map(|p| p.to_string());
+
+                match CurrentProject::load(file) {
+                    Ok(project) => {
+                        if let Some(path) = path {
+                            _ = update_recently_opened(
+                                project.project.name.clone(),
+                                path.clone()
+                            ).unwrap();
+                        }
+                        self.current_project = Some(project);
+                    },
+                    Err(e) => return self.open_project_failed(e)
+                }
+
+                return Task::done(Message::OpenScorer);
+            },
+            Message::RelinkSearch => {
+                let Some(relink) = &mut self.relink else {
+                    return Task::none();
+                };
+
+                relink.is_searching = true;
+                relink.errors.clear();
+                let directories = relink.directories.clone();
+                let sources = relink.unresolved();
+                return Task::future(async move {
+                    Message::RelinkSearchFinished(sources.into_iter()
+                        .filter_map(|(index, source)| relink::search(&directories, &source).map(|path| (index, path)))
+                        .collect())
+                });
+            },
+            Message::RelinkSearchFinished(found) => {
+                let Some(relink) = &mut self.relink else {
+                    return Task::none();
+                };
+
+                relink.is_searching = false;
+                for (index, path) in found {
+                    relink.set_found(index, path);
+                }
+            },
+            Message::RelinkLaunchAddDirectory => {
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+
+                return Task::future(async {
+                    Message::RelinkAddDirectory(AsyncFileDialog::new()
+                        .set_directory(path)
+                        .pick_folder()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                });
+            },
+            Message::RelinkAddDirectory(directory) => {
+                let Some(relink) = &mut self.relink else {
+                    return Task::none();
+                };
+                let Some(directory) = directory.filter(|d| !relink.directories.contains(d)) else {
+                    return Task::none();
+                };
+
+                relink.directories.push(directory);
+                return Task::done(Message::RelinkSearch);
+            },
+            Message::RelinkLaunchBrowse(index) => {
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+
+                return Task::future(async move {
+                    Message::RelinkBrowse(index, AsyncFileDialog::new()
+                        .add_filter("EDF/EDF+ File", &["edf"])
+                        .set_directory(path)
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                });
+            },
+            Message::RelinkBrowse(index, file) => {
+                let Some(relink) = &mut self.relink else {
+                    return Task::none();
+                };
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(source) = relink.sources.iter().find(|s| s.index == index) else {
+                    return Task::none();
+                };
+
+                // Only accept the file in case it has the same content as the original one
+                relink.errors.clear();
+                if relink::is_source_file(&source.source, &file) {
+                    relink.set_found(index, file);
+                } else {
+                    relink.errors.push(format!("{} is not the same file as {}", file.display(), source.source.path));
+                }
+            },
+            Message::ApplyRelink => {
+                let Some(relink) = &mut self.relink else {
+                    return Task::none();
+                };
+
+                if let Err(e) = relink.apply() {
+                    relink.errors = vec![format!("Error updating project: {}", e)];
+                    return Task::none();
+                }
+
+                let project_file = relink.project_file.clone();
+                self.relink = None;
+                self.current_page = Page::Home;
+                return Task::done(Message::OpenProject(Some(project_file)));
+            },
+            Message::CancelRelink => {
+                self.relink = None;
+                self.current_page = Page::Home;
+            },
+            Message::CancelCreateProject => {
+                self.project_creation = None;
+                self.current_page = Page::Home;
+            },
+            Message::CreateProject => {
+                let Some(project) = &mut self.project_creation else {
+                    return Task::none();
+                };
+
+                // Jump to the page of the first problem, all affected fields are highlighted
+                project.errors = project_initializer::validate(project);
+                if let Some(error) = project.errors.first() {
+                    if let Some(page) = wizard_page(&error.field) {
+                        self.current_page = Page::CreateProject(page);
+                    }
+                    return Task::none();
+                }
+
+                // Keep the configuration to show errors which occur while creating the project
+                return project_initializer::create_new(project.clone());
+            },
+            Message::OpenScorer => {
+                // Move the axis in direction 0, load the data without actually moving on the x-axis
+                if !move_axis(self, 0) {
+                    return Task::none();
+                }
+
+                let Some(project) = &mut self.current_project else {
+                    return Task::none();
+                };
+
+                // Change the page to the scorer and resize the window
+                self.current_page = Page::Scorer;
+                self.focused_signal = None;
+
+                // Problems of opening files from the command line remain visible in the status bar
+                if let Some(error) = self.start_error.take() {
+                    project.status = Some(StatusMessage::error(error));
+                }
+
+                return Task::batch([
+                    project.load_spectrogram(),
+                    resize_window(Size::new(1400.0, 800.0))
+                ]);
+            },
+            Message::CreateProjectWizardError(error) => {
+                let Some(project) = &mut self.project_creation else {
+                    eprintln!("{error}");
+                    return Task::none();
+                };
+
+                project.errors = vec![ConfigurationError { field: ConfigurationField::General, message: error }];
+            },
+            Message::ProjectSearchChanged(search) => {
+                self.search_text = search.clone();
+
+                // Clear the filter in case the field was cleared
+                if search.is_empty() {
+                    self.filtered_recent_projects = None;
+                    return Task::none();
+                }
+                let search = search.to_lowercase();
+
+                // Get a somewhat unique identifier for the search to prevent showing results
+                // from a previous search that took longer than a subsequent filter
+                let search_task_id = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or(Duration::ZERO)
+                    .as_nanos()
+                    .to_string();
+                self.search_task_id = search_task_id.clone();
+                let recent = self.recent_projects.clone();
+
+                // Perform the filtering asynchronously
+                return Task::future(async move {
+                    Message::ProjectSearchFiltered(search_task_id, recent.into_iter().filter(|p| {
+                        p.name.to_lowercase().contains(&search) || p.path.to_lowercase().contains(&search)
+                    }).collect())
+                });
+            }
+            Message::ProjectSearchFiltered(search_task_id, filtered) => {
+                if self.search_task_id != search_task_id {
+                    return Task::none()
+                }
+
+                self.filtered_recent_projects = Some(filtered);
+            }
+            Message::ToggleFilterSignal(checked) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.filter_signal = checked;
+                }
+            },
+            Message::ToggleClipSignal(checked) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.clip_signal = checked;
+                }
+            },
+            Message::ToggleAutoAlignSignals(checked) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.auto_align_signals = checked;
+                }
+            },
+            Message::StageVocabularyChanged(vocabulary) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.stage_vocabulary = vocabulary;
+                }
+            },
+            Message::CreatePresetChanged(preset) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.apply_preset(preset);
+                    project.revalidate();
+                }
+            },
+            Message::NewTagChanged(tag) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.new_tag = tag;
+                }
+            },
+            Message::ProjectLocationChanged(path) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.path = path;
+                    project.revalidate_details();
+                }
+            },
+            Message::ProjectNameChanged(name) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.name = name;
+                    project.revalidate_details();
+                }
+            },
+            Message::AddTag => {
+                if let Some(project) = &mut self.project_creation {
+                    if !project.new_tag.trim().is_empty() {
+                        project.tags.push(project.new_tag.clone());
+                        project.new_tag = String::new();
+                        // TODO: Focus tags input field again
+                    }
+                }
+            },
+            Message::RemoveTag(index) => {
+                if let Some(project) = &mut self.project_creation {
+                    project.tags.remove(index);
+                }
+            },
+            Message::LaunchBrowseProjectLocation => {
+                let path = get_last_project_create_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or(String::new());
+
+                return Task::future(async {
+                    Message::BrowseProjectLocation(AsyncFileDialog::new()
+                        .set_directory(path)
+                        .pick_folder()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                    )
+                });
             }
-            Message::LoadStartPage => {
-                if let Ok(recent) = get_recently_opened(25) {
-                    self.recent_projects = recent;
+            Message::BrowseProjectLocation(folder) => {
+                let Some(path) = folder.map(|p| p.to_str().map(|v| v.to_string())).flatten() else {
+                    return Task::none();
+                };
+
+                _ = set_last_project_create_path(path.to_string());
+
+                if let Some(project) = &mut self.project_creation {
+                    project.path = path.to_string();
+                    project.revalidate_details();
                 }
+            },
+            Message::LaunchBrowseImportSignal => {
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or(String::new());
+
+                return Task::future(async {
+                    Message::BrowseImportSignal(AsyncFileDialog::new()
+                        .add_filter("EDF/EDF+ File", &["edf"])
+                        .set_directory(path)
+                        .pick_files()
+                        .await
+                        .map(|h| h.iter().map(|h| h.path().to_path_buf()).collect())
+                    )
+                });
             }
-            Message::MoveAxis(direction) => {
-                if !move_axis(self, direction) {
-                    return Task::none();
+            Message::BrowseImportSignal(files) => {
+                if let Some(files) = files {
+                    if let Some(first) = files.first() && let Some(parent) = first.parent() && let Some(path) = parent.to_str() {
+                        _ = set_last_browse_source_path(path.to_string());
+                    };
+
+                    if let Some(project) = &mut self.project_creation {
+                        // TODO: Skip all files which are already present in the added data (and maybe also check for duplicates in current list
+                        //       which would probably be useless as you most likely cannot select a file twice)
+                        let mut signals = files.iter()
+                            .filter_map(|path| path.to_str())
+                            .map(|path| ProjectSignals::from_path(path.to_string()))
+                            .collect::<Vec<_>>();
+
+                        // The channel rules of the preset take precedence over the alias dictionary
+                        if let Some(preset) = &project.preset {
+                            signals.iter_mut().for_each(|s| s.apply_channel_rules(&preset.channel_rules));
+                        }
+                        project.data.append(&mut signals);
+                        project.revalidate();
+                    }
                 }
             },
-            Message::Rate(stage) => {
-                let Some(project) = &mut self.current_project else {
+            Message::ToggleImportSignalReference(path, is_reference) => {
+                if let Some(project) = &mut self.project_creation && let Some(signal) = project.data.iter_mut().find(|s| s.path == path) {
+                    signal.is_reference = is_reference;
+                    project.revalidate();
+                }
+            },
+            Message::ToggleImportSignalChannels(path) => {
+                if let Some(project) = &mut self.project_creation && let Some(signal) = project.data.iter_mut().find(|s| s.path == path) {
+                    signal.is_expanded = !signal.is_expanded;
+                }
+            },
+            Message::ToggleImportChannelVisible(path, index, is_visible) => {
+                if let Some(channel) = self.import_channel(&path, index) {
+                    channel.visible = is_visible;
+                }
+            },
+            Message::ImportChannelNameChanged(path, index, name) => {
+                if let Some(channel) = self.import_channel(&path, index) {
+                    channel.name = Some(name).filter(|n| !n.is_empty());
+                }
+            },
+            Message::ImportChannelTypeChanged(path, index, channel_type) => {
+                if let Some(channel) = self.import_channel(&path, index) {
+                    channel.channel_type = channel_type;
+                }
+            },
+            Message::SaveImportChannelAlias(path, index) => {
+                let Some(channel) = self.import_channel(&path, index) else {
                     return Task::none();
                 };
-                let Some(scorings) = &mut project.scorings else {
-                    return Task::none();
+                let alias = ChannelAlias {
+                    alias: channel.label.trim().to_string(),
+                    name: channel.display_name().trim().to_string(),
+                    channel_type: channel.channel_type
                 };
-                if let Some(reader) = project.readers.first() {
-                    let current_seg_n = reader.get_window_start_epoch();
-                    if stage == Stage::Unset {
-                        scorings.values.remove_entry(&current_seg_n);
-                    }
-                    else {
-                        scorings.values.entry(current_seg_n)
-                            .and_modify(|v| *v = stage.clone())
-                            .or_insert(stage);
-                    }
+
+                // Shown next to the source of the channel
+                if let Err(e) = set_channel_alias(&alias) && let Some(project) = &mut self.project_creation {
+                    let message = format!("Error saving the alias {} of channel {}: {:?}", alias.name, alias.alias, e);
+                    project.errors.push(ConfigurationError::new(ConfigurationField::Source(path), message));
                 }
             },
-            Message::SpectrogramLoadStart => {
+            Message::RemoveImportSignal(path) => {
+                if let Some(project) = &mut self.project_creation {
+                    if let Some(index) = project.data.iter().position(|signal| signal.path == path) {
+                        project.data.remove(index);
+                    };
+                    project.revalidate();
+                }
+            },
+            Message::OpenProjectSettings => {
+                let Some(project) = self.current_project.as_ref().filter(|p| !p.is_quick_view) else {
+                    return Task::none();
+                };
 
+                self.project_settings = Some(ProjectSettings::from_project(project));
+                self.stage_picker = None;
+                self.selection = None;
+                self.current_page = Page::ProjectSettings;
             },
-            Message::SpectrogramLoadProgress(progress) => {
+            Message::CancelProjectSettings => {
+                self.project_settings = None;
+                self.current_page = Page::Scorer;
+            },
+            Message::SaveProjectSettings => {
+                let Some(settings) = &mut self.project_settings else {
+                    return Task::none();
+                };
                 let Some(project) = &mut self.current_project else {
                     return Task::none();
                 };
-                project.loading_progress_spectrogram = Some(progress);
+
+                settings.errors = settings.validate(Path::new(&project.path));
+                if !settings.errors.is_empty() {
+                    return Task::none();
+                }
+
+                // Hashing the added sources takes a while for large files, the settings are applied afterwards
+                settings.is_saving = true;
+                let paths = settings.new_source_paths();
+                return Task::future(async move {
+                    Message::SettingsSourcesHashed(project_settings::fingerprints(&paths).map_err(|e| e.to_string()))
+                });
             },
-            Message::SpectrogramLoadFinish(spectrogram) => {
+            Message::SettingsSourcesHashed(fingerprints) => {
+                let Some(settings) = &mut self.project_settings else {
+                    return Task::none();
+                };
                 let Some(project) = &mut self.current_project else {
                     return Task::none();
                 };
-                project.spectrogram = Some(SpectrogramView::new(spectrogram, "lajolla".to_string()));
+
+                settings.is_saving = false;
+                let fingerprints = match fingerprints {
+                    Ok(fingerprints) => fingerprints,
+                    Err(e) => {
+                        settings.errors = vec![format!("Error reading the added signal sources: {}", e)];
+                        return Task::none();
+                    }
+                };
+
+                let current_epoch = project.current_epoch();
+                if let Err(e) = project_settings::apply(project, settings, &fingerprints) {
+                    settings.errors = vec![format!("Error saving project settings: {}", e)];
+                    return Task::none();
+                }
+
+                // Keep the name within the recently opened projects up to date
+                let project_file = Path::new(&project.path).join(&project.project_name);
+                if let Some(path) = project_file.to_str() {
+                    _ = update_recently_opened(project.project.name.clone(), path.to_string());
+                }
+
+                // Reload the visible samples of the changed readers at the previous position
+                self.project_settings = None;
+                seek_epoch(self, current_epoch);
+                return Task::done(Message::OpenScorer);
             },
-            Message::SeekTo => {
-                let epoch = 1100;
+            Message::SettingsNameChanged(name) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.name = name;
+                }
+            },
+            Message::SettingsNewTagChanged(tag) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.new_tag = tag;
+                }
+            },
+            Message::SettingsAddTag => {
+                if let Some(settings) = &mut self.project_settings && !settings.new_tag.trim().is_empty() {
+                    settings.tags.push(std::mem::take(&mut settings.new_tag));
+                }
+            },
+            Message::SettingsRemoveTag(index) => {
+                if let Some(settings) = &mut self.project_settings && index < settings.tags.len() {
+                    settings.tags.remove(index);
+                }
+            },
+            Message::SettingsEpochsBeforeChanged(count) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.epochs_before_current = count;
+                }
+            },
+            Message::SettingsEpochsAfterChanged(count) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.epochs_after_current = count;
+                }
+            },
+            Message::SettingsToggleFilterSignal(checked) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.filter_signal = checked;
+                }
+            },
+            Message::SettingsToggleClipSignal(checked) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.clip_signal = checked;
+                }
+            },
+            Message::SettingsToggleAutoAlignSignals(checked) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.auto_align_signals = checked;
+                }
+            },
+            Message::SettingsLaunchAddSource => {
+                let path = get_last_browse_source_path()
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
 
-                let Some(project) = &mut self.current_project else {
+                return Task::future(async {
+                    Message::SettingsAddSources(AsyncFileDialog::new()
+                        .add_filter("EDF/EDF+ File", &["edf"])
+                        .set_directory(path)
+                        .pick_files()
+                        .await
+                        .map(|h| h.iter().map(|h| h.path().to_path_buf()).collect())
+                    )
+                });
+            },
+            Message::SettingsAddSources(files) => {
+                let Some(files) = files else {
                     return Task::none();
                 };
 
-                for reader in &mut project.readers {
-                    let segment_count = project.project.epochs_before_current as usize + project.project.epochs_after_current as usize + 1;
-                    let _ = reader.seek(EpochReader::EPOCH_DURATION as u64 * 1_000 * epoch as u64);
-                    reader.read_epochs(segment_count).unwrap();
+                if let Some(first) = files.first() && let Some(parent) = first.parent() && let Some(path) = parent.to_str() {
+                    _ = set_last_browse_source_path(path.to_string());
+                }
+                if let Some(settings) = &mut self.project_settings {
+                    settings.add_sources(files);
                 }
             },
-            Message::CycleTimeFormatter => {
-                self.window_time_formatter_index = (self.window_time_formatter_index + 1) % formatting::formatters::TIME_FORMATTERS.len();
+            Message::SettingsRemoveSource(index) => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.remove_source(index);
+                }
             },
-            Message::ToggleRangeDraw => {
-                self.draw_ranges = !self.draw_ranges;
+            Message::SettingsSourceOffsetChanged(index, offset) => {
+                if let Some(settings) = &mut self.project_settings && let Some(source) = settings.sources.get_mut(index) {
+                    source.offset = offset;
+                }
             },
-            Message::ToggleHelp => {
-                self.is_showing_help = !self.is_showing_help;
+            Message::SettingsToggleSourceReference(index, is_reference) => {
+                // Only new sources can be either copied or referenced, existing ones are already stored
+                if let Some(settings) = &mut self.project_settings && let Some(source) = settings.sources.get_mut(index).filter(|s| s.is_new()) {
+                    source.signals.is_reference = is_reference;
+                }
             },
-            Message::SwitchPage(page) => {
-                self.current_page = page
+            Message::SettingsAddDerivedChannel => {
+                if let Some(settings) = &mut self.project_settings {
+                    settings.derived_channels.push(DerivedChannel::new());
+                }
             },
-            Message::CreateProjectWizard => {
-                self.project_creation = Some(ProjectConfiguration {
-                    name: "".to_string(),
-                    path: "".to_string(),
-                    new_tag: "".to_string(),
-                    tags: Vec::new(),
-                    filter_signal: true,
-                    auto_align_signals: true,
-                    clip_signal: true,
-                    data: Vec::new()
-                });
-                self.current_page = Page::CreateProject(CreatePage::Project);
+            Message::SettingsRemoveDerivedChannel(index) => {
+                if let Some(settings) = &mut self.project_settings && index < settings.derived_channels.len() {
+                    settings.derived_channels.remove(index);
+                }
             },
-            Message::SaveProject => {
-                let Some(project) = &mut self.current_project else {
+            Message::SettingsDerivedNameChanged(index, name) => {
+                if let Some(settings) = &mut self.project_settings && let Some(channel) = settings.derived_channels.get_mut(index) {
+                    channel.name = name;
+                }
+            },
+            Message::SettingsDerivedExpressionChanged(index, expression) => {
+                if let Some(settings) = &mut self.project_settings && let Some(channel) = settings.derived_channels.get_mut(index) {
+                    channel.expression = expression;
+                }
+            },
+            Message::SettingsDerivedTypeChanged(index, channel_type) => {
+                if let Some(settings) = &mut self.project_settings && let Some(channel) = settings.derived_channels.get_mut(index) {
+                    channel.channel_type = channel_type;
+                }
+            },
+            Message::SettingsAddMontage => {
+                if let Some(settings) = &mut self.project_settings {
+                    let name = format!("Montage {}", settings.montages.len() + 1);
+                    settings.montages.push(MontageSettings::from_montage(&Montage::new(&name)));
+                }
+            },
+            Message::SettingsRemoveMontage(index) => {
+                if let Some(settings) = &mut self.project_settings && index < settings.montages.len() {
+                    settings.montages.remove(index);
+                }
+            },
+            Message::SettingsMontageNameChanged(index, name) => {
+                if let Some(settings) = &mut self.project_settings && let Some(montage) = settings.montages.get_mut(index) {
+                    montage.name = name;
+                }
+            },
+            Message::SettingsAddMontageChannel(index, channel) => {
+                if let Some(settings) = &mut self.project_settings && let Some(montage) = settings.montages.get_mut(index) {
+                    montage.channels.push(MontageChannelSettings::new(channel));
+                }
+            },
+            Message::SettingsRemoveMontageChannel(index, channel) => {
+                if let Some(settings) = &mut self.project_settings && let Some(montage) = settings.montages.get_mut(index) && channel < montage.channels.len() {
+                    montage.channels.remove(channel);
+                }
+            },
+            Message::SettingsMoveMontageChannel(index, channel, step) => {
+                if let Some(settings) = &mut self.project_settings && let Some(montage) = settings.montages.get_mut(index) {
+                    let target = channel as i64 + step as i64;
+                    if channel < montage.channels.len() && target >= 0 && (target as usize) < montage.channels.len() {
+                        montage.channels.swap(channel, target as usize);
+                    }
+                }
+            },
+            Message::SettingsMontageChannelChanged(index, channel, field, value) => {
+                if let Some(settings) = &mut self.project_settings && let Some(montage) = settings.montages.get_mut(index) && let Some(channel) = montage.channels.get_mut(channel) {
+                    *channel.field_mut(field) = value;
+                }
+            },
+            Message::SettingsLaunchImportMontage => {
+                return Task::future(async {
+                    Message::SettingsImportMontage(AsyncFileDialog::new()
+                        .add_filter("NoctiG Montage", &[montage::PRESET_EXTENSION])
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::SettingsImportMontage(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(settings) = &mut self.project_settings else {
                     return Task::none();
                 };
 
-                if let Err(e) = project.save() {
-                    eprintln!("Error saving project: {}", e);
-                    return Task::none()
+                match Montage::load(&file) {
+                    Ok(mut montage) => {
+                        // Keep the names of the montages unique
+                        let name = montage.name.clone();
+                        let mut copy = 1;
+                        while settings.montages.iter().any(|m| m.name.trim() == montage.name) {
+                            copy += 1;
+                            montage.name = format!("{} ({})", name, copy);
+                        }
+                        settings.montages.push(MontageSettings::from_montage(&montage));
+                    }
+                    Err(e) => settings.errors = vec![format!("Error importing montage {}: {}", file.display(), e)]
+                }
+            },
+            Message::SettingsLaunchExportMontage(index) => {
+                let Some(montage) = self.project_settings.as_ref().and_then(|s| s.montages.get(index)) else {
+                    return Task::none();
+                };
+
+                let file_name = format!("{}.{}", montage.name.trim(), montage::PRESET_EXTENSION);
+                return Task::future(async move {
+                    Message::SettingsExportMontage(index, AsyncFileDialog::new()
+                        .add_filter("NoctiG Montage", &[montage::PRESET_EXTENSION])
+                        .set_file_name(file_name)
+                        .save_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::SettingsSaveAsPreset => {
+                let Some(settings) = &mut self.project_settings else {
+                    return Task::none();
+                };
+                let Some(project) = &self.current_project else {
+                    return Task::none();
+                };
+
+                // Existing presets are kept, the name of the new preset is made unique
+                let mut editor = PresetEditor::new(get_project_presets().unwrap_or_default());
+                editor.add(ProjectPreset::from_settings(settings.name.trim(), settings, &project.project.stage_vocabulary));
+                match editor.save() {
+                    Ok(()) => {
+                        settings.errors.clear();
+                        settings.notice = editor.presets.last().map(|p| format!("Saved as preset {}", p.name));
+                    }
+                    Err(e) => {
+                        settings.notice = None;
+                        settings.errors = vec![format!("Error saving preset: {:?}", e)];
+                    }
+                }
+            },
+            Message::SettingsExportMontage(index, file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(settings) = &mut self.project_settings else {
+                    return Task::none();
+                };
+                let Some(montage) = settings.montages.get(index) else {
+                    return Task::none();
+                };
+
+                let result = montage.to_montage().map_err(|e| e.into()).and_then(|m| m.save(&file));
+                if let Err(e) = result {
+                    settings.errors = vec![format!("Error exporting montage {}: {}", file.display(), e)];
+                }
+            },
+            Message::OpenPresetEditor => {
+                self.preset_editor = Some(PresetEditor::new(get_project_presets().unwrap_or_default()));
+                self.current_page = Page::Presets;
+            },
+            Message::ClosePresetEditor => {
+                self.preset_editor = None;
+                self.project_presets = get_project_presets().unwrap_or_default();
+                self.current_page = if self.project_creation.is_some() {
+                    Page::CreateProject(CreatePage::Project)
+                } else {
+                    Page::Home
+                };
+            },
+            Message::SavePresets => {
+                let Some(editor) = &mut self.preset_editor else {
+                    return Task::none();
                 };
 
-                println!("SAVED");
+                editor.errors = editor.validate();
+                if !editor.errors.is_empty() {
+                    return Task::none();
+                }
+                if let Err(e) = editor.save() {
+                    editor.errors = vec![format!("Error saving presets: {:?}", e)];
+                    return Task::none();
+                }
+
+                return Task::done(Message::ClosePresetEditor);
             },
-            Message::OpenProjectPath(path) => {
-                match CurrentProject::load(path) {
-                    Ok(project) => self.current_project = Some(project),
-                    Err(e) => eprintln!("Error opening project: {}", e) // TODO: Show error message box
+            Message::PresetSelected(index) => {
+                if let Some(editor) = &mut self.preset_editor && index < editor.presets.len() {
+                    editor.selected = Some(index);
                 }
-                return Task::done(Message::OpenScorer);
             },
-            Message::LaunchOpenProject => {
-                let path = get_last_project_path()
-                    .ok()
-                    .flatten()
-                    .unwrap_or(String::new());
-
+            Message::PresetAdd => {
+                if let Some(editor) = &mut self.preset_editor {
+                    editor.add(ProjectPreset::new("New preset"));
+                }
+            },
+            Message::PresetRemove => {
+                if let Some(editor) = &mut self.preset_editor && let Some(index) = editor.selected {
+                    editor.presets.remove(index);
+                    editor.selected = (!editor.presets.is_empty()).then(|| index.min(editor.presets.len() - 1));
+                }
+            },
+            Message::PresetLaunchImport => {
                 return Task::future(async {
-                    Message::OpenProject(AsyncFileDialog::new()
-                        .add_filter("NoctiG Project", &["ngp"])
-                        .set_directory(path)
+                    Message::PresetImport(AsyncFileDialog::new()
+                        .add_filter("NoctiG Preset", &[preset::PRESET_EXTENSION])
                         .pick_file()
                         .await
                         .map(|h| h.path().to_path_buf()))
                 })
-            }
-            Message::OpenProject(file) => {
+            },
+            Message::PresetImport(file) => {
                 let Some(file) = file else {
                     return Task::none();
                 };
+                let Some(editor) = &mut self.preset_editor else {
+                    return Task::none();
+                };
 
-                if let Some(parent) = file.parent() && let Some(path) = parent.to_str() {
-                    _ = set_last_project_path(path.to_string());
+                match ProjectPreset::load(&file) {
+                    Ok(preset) => editor.add(preset),
+                    Err(e) => editor.errors = vec![format!("Error importing preset {}: {}", file.display(), e)]
                 }
+            },
+            Message::PresetLaunchExport => {
+                let Some(preset) = self.edited_preset() else {
+                    return Task::none();
+                };
 
-                let path = file.to_str().map(|p| p.to_string());
+                let file_name = format!("{}.{}", project_initializer::sanitize_file_name(preset.name.trim()), preset::PRESET_EXTENSION);
+                return Task::future(async move {
+                    Message::PresetExport(AsyncFileDialog::new()
+                        .add_filter("NoctiG Preset", &[preset::PRESET_EXTENSION])
+                        .set_file_name(file_name)
+                        .save_file()
+                        .await
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::PresetExport(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(editor) = &mut self.preset_editor else {
+                    return Task::none();
+                };
+                let Some(preset) = editor.selected.and_then(|i| editor.presets.get(i)) else {
+                    return Task::none();
+                };
 
-                match CurrentProject::load(file) {
-                    Ok(project) => {
-                        if let Some(path) = path {
-                            _ = update_recently_opened(
-                                project.project.name.clone(),
-                                path.clone()
-                            ).unwrap();
-                        }
-                        self.current_project = Some(project);
-                    },
-                    Err(e) => eprintln!("Error opening project: {}", e) // TODO: Show error message box
+                if let Err(e) = preset.save(&file) {
+                    editor.errors = vec![format!("Error exporting preset {}: {}", file.display(), e)];
                 }
-
-                return Task::done(Message::OpenScorer);
             },
-            Message::CancelCreateProject => {
-                self.project_creation = None;
-                self.current_page = Page::Home;
-            },
-            Message::CreateProject => {
-                // TODO: Require a project name and a location and at least 1 added signal source with at least 1 signal
-                //       If not provided, jump to the first erroring page and highlight the field
-                if let Some(project) = self.project_creation.take() {
-                    return project_initializer::create_new(project);
+            Message::PresetNameChanged(name) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.name = name;
                 }
             },
-            Message::OpenScorer => {
-                // Move the axis in direction 0, load the data without actually moving on the x-axis
-                if !move_axis(self, 0) {
-                    return Task::none();
+            Message::PresetEpochsBeforeChanged(count) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.epochs_before_current = count;
                 }
-
-                let Some(project) = &mut self.current_project else {
-                    return Task::none();
-                };
-                project.spectrogram = None;
-                project.loading_progress_spectrogram = Some(0.0);
-                let source_path = project.project.signals.get(1).unwrap().path.clone();
-
-                // Change the page to the scorer and resize the window
-                self.current_page = Page::Scorer;
-                return Task::batch([
-                    Task::stream(Self::calculate_spectrogram(project.path.clone(), source_path, 0)),
-                    resize_window(Size::new(1400.0, 800.0))
-                ]);
             },
-            Message::CreateProjectWizardError(error) => {
-                // TODO: Open dialog box
-                eprintln!("{error}");
+            Message::PresetEpochsAfterChanged(count) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.epochs_after_current = count;
+                }
             },
-            Message::ProjectSearchChanged(search) => {
-                self.search_text = search.clone();
-
-                // Clear the filter in case the field was cleared
-                if search.is_empty() {
-                    self.filtered_recent_projects = None;
-                    return Task::none();
+            Message::PresetToggleFilterSignal(checked) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.filter_signal = checked;
                 }
-                let search = search.to_lowercase();
-
-                // Get a somewhat unique identifier for the search to prevent showing results
-                // from a previous search that took longer than a subsequent filter
-                let search_task_id = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or(Duration::ZERO)
-                    .as_nanos()
-                    .to_string();
-                self.search_task_id = search_task_id.clone();
-                let recent = self.recent_projects.clone();
-
-                // Perform the filtering asynchronously
-                return Task::future(async move {
-                    Message::ProjectSearchFiltered(search_task_id, recent.into_iter().filter(|p| {
-                        p.name.to_lowercase().contains(&search) || p.path.to_lowercase().contains(&search)
-                    }).collect())
-                });
-            }
-            Message::ProjectSearchFiltered(search_task_id, filtered) => {
-                if self.search_task_id != search_task_id {
-                    return Task::none()
+            },
+            Message::PresetToggleClipSignal(checked) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.clip_signal = checked;
                 }
-
-                self.filtered_recent_projects = Some(filtered);
-            }
-            Message::ToggleFilterSignal(checked) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.filter_signal = checked;
+            },
+            Message::PresetToggleAutoAlignSignals(checked) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.auto_align_signals = checked;
                 }
             },
-            Message::ToggleClipSignal(checked) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.clip_signal = checked;
+            Message::PresetStageVocabularyChanged(vocabulary) => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.stage_vocabulary = vocabulary;
                 }
             },
-            Message::ToggleAutoAlignSignals(checked) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.auto_align_signals = checked;
+            Message::PresetAddChannelRule => {
+                if let Some(preset) = self.edited_preset() {
+                    preset.channel_rules.push(ChannelAlias { alias: String::new(), name: String::new(), channel_type: ChannelType::Eeg });
                 }
             },
-            Message::NewTagChanged(tag) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.new_tag = tag;
+            Message::PresetRemoveChannelRule(index) => {
+                if let Some(preset) = self.edited_preset() && index < preset.channel_rules.len() {
+                    preset.channel_rules.remove(index);
                 }
             },
-            Message::ProjectLocationChanged(path) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.path = path;
+            Message::PresetChannelRuleLabelChanged(index, label) => {
+                if let Some(preset) = self.edited_preset() && let Some(rule) = preset.channel_rules.get_mut(index) {
+                    rule.alias = label;
                 }
             },
-            Message::ProjectNameChanged(name) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.name = name;
+            Message::PresetChannelRuleNameChanged(index, name) => {
+                if let Some(preset) = self.edited_preset() && let Some(rule) = preset.channel_rules.get_mut(index) {
+                    rule.name = name;
                 }
             },
-            Message::AddTag => {
-                if let Some(project) = &mut self.project_creation {
-                    if !project.new_tag.trim().is_empty() {
-                        project.tags.push(project.new_tag.clone());
-                        project.new_tag = String::new();
-                        // TODO: Focus tags input field again
-                    }
+            Message::PresetChannelRuleTypeChanged(index, channel_type) => {
+                if let Some(preset) = self.edited_preset() && let Some(rule) = preset.channel_rules.get_mut(index) {
+                    rule.channel_type = channel_type;
                 }
             },
-            Message::RemoveTag(index) => {
-                if let Some(project) = &mut self.project_creation {
-                    project.tags.remove(index);
+            Message::PresetRemoveDerivedChannel(index) => {
+                if let Some(preset) = self.edited_preset() && index < preset.derived_channels.len() {
+                    preset.derived_channels.remove(index);
                 }
             },
-            Message::LaunchBrowseProjectLocation => {
-                let path = get_last_project_create_path()
-                    .ok()
-                    .flatten()
-                    .unwrap_or(String::new());
-
+            Message::PresetLaunchImportMontage => {
                 return Task::future(async {
-                    Message::BrowseProjectLocation(AsyncFileDialog::new()
-                        .set_directory(path)
-                        .pick_folder()
+                    Message::PresetImportMontage(AsyncFileDialog::new()
+                        .add_filter("NoctiG Montage", &[montage::PRESET_EXTENSION])
+                        .pick_file()
                         .await
-                        .map(|h| h.path().to_path_buf())
-                    )
-                });
-            }
-            Message::BrowseProjectLocation(folder) => {
-                let Some(path) = folder.map(|p| p.to_str().map(|v| v.to_string())).flatten() else {
+                        .map(|h| h.path().to_path_buf()))
+                })
+            },
+            Message::PresetImportMontage(file) => {
+                let Some(file) = file else {
+                    return Task::none();
+                };
+                let Some(editor) = &mut self.preset_editor else {
                     return Task::none();
                 };
 
-                _ = set_last_project_create_path(path.to_string());
-
-                if let Some(project) = &mut self.project_creation {
-                    project.path = path.to_string();
-                }
-            },
-            Message::LaunchBrowseImportSignal => {
-                let path = get_last_browse_source_path()
-                    .ok()
-                    .flatten()
-                    .unwrap_or(String::new());
-
-                return Task::future(async {
-                    Message::BrowseImportSignal(AsyncFileDialog::new()
-                        .add_filter("EDF/EDF+ File", &["edf"])
-                        .set_directory(path)
-                        .pick_files()
-                        .await
-                        .map(|h| h.iter().map(|h| h.path().to_path_buf()).collect())
-                    )
-                });
-            }
-            Message::BrowseImportSignal(files) => {
-                if let Some(files) = files {
-                    if let Some(first) = files.first() && let Some(parent) = first.parent() && let Some(path) = parent.to_str() {
-                        _ = set_last_browse_source_path(path.to_string());
-                    };
-
-                    if let Some(project) = &mut self.project_creation {
-                        // TODO: Skip all files which are already present in the added data (and maybe also check for duplicates in current list
-                        //       which would probably be useless as you most likely cannot select a file twice)
-                        let signals = files.iter().filter_map(|path| {
-                            path.to_str().map(|s| (EDFFile::open(s.to_string()).ok(), s.to_string()))
-                        }).map(|(edf, path)| {
-                            let mut duration = 0.0;
-                            let mut signal_count = 0;
-                            let mut timestamp = 0;
-
-                            if let Some(edf) = edf {
-                                let header = edf.header;
-                                duration = header.get_record_count().map(|c| c as f64 * header.get_record_duration()).unwrap_or(0.0);
-                                signal_count = header.get_signals().len();
-                                timestamp = header.start_date().and_time(header.get_start_time()).and_utc().timestamp() as u64;
-                            };
-
-                            // TODO: In case there already is a file with this name in the current signals, append a -<NUMERIC> to make it unique
-                            let filename = Path::new(&path).file_name()
-                                .map(|name| name.to_string_lossy().to_string())
-                                .unwrap_or("--".to_string());
-
-                            ProjectSignals {
-                                timestamp,
-                                duration,
-                                signal_count,
-                                path,
-                                name: filename,
-                                is_reference: false
-                            }
-                        });
-                        project.data.append(&mut signals.collect());
-                    }
+                match Montage::load(&file) {
+                    Ok(montage) => if let Some(preset) = editor.selected_preset() {
+                        preset.montages.push(montage);
+                    },
+                    Err(e) => editor.errors = vec![format!("Error importing montage {}: {}", file.display(), e)]
                 }
             },
-            Message::RemoveImportSignal(path) => {
-                if let Some(project) = &mut self.project_creation {
-                    if let Some(index) = project.data.iter().position(|signal| signal.path == path) {
-                        project.data.remove(index);
-                    };
+            Message::PresetRemoveMontage(index) => {
+                if let Some(preset) = self.edited_preset() && index < preset.montages.len() {
+                    preset.montages.remove(index);
                 }
             },
             Message::ShowSourceCode => {
@@ -827,6 +3231,9 @@ impl NoctiG {
                 match self.current_page {
                     Page::Home | Page::Stream | Page::Help | Page::Settings => start::view(self),
                     Page::Scorer => scorer::view(self),
+                    Page::ProjectSettings => settings_viewer::view(self),
+                    Page::RelinkSources => relink_viewer::view(self),
+                    Page::Presets => presets_viewer::view(self),
                     Page::CreateProject(ref page) => create_viewer::view(self, page),
                 }
             }
@@ -857,13 +3264,44 @@ impl NoctiG {
                     }),
                     Status::Ignored,
                 ) => Some(Message::MoveAxis(-1)),
+                (
+                    iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                        key: Key::Named(Named::Escape),
+                        ..
+                    }),
+                    Status::Ignored,
+                ) => Some(Message::ClearSelection),
                 (
                     iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                         key: Key::Named(Named::Delete),
                         ..
                     }),
                     Status::Ignored,
-                ) => Some(Message::Rate(Stage::Unset)),
+                ) => Some(Message::Rate(None)),
+                (
+                    iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                        key: Key::Named(Named::Tab),
+                        modifiers,
+                        ..
+                    }),
+                    Status::Ignored,
+                ) => Some(Message::CycleMontage(if modifiers.shift() { -1 } else { 1 })),
+                (
+                    iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                        key: Key::Named(Named::ArrowUp),
+                        modifiers,
+                        ..
+                    }),
+                    Status::Ignored,
+                ) if modifiers.shift() => Some(Message::ChangeChannelView(ChannelViewChange::Height(1), modifiers.control())),
+                (
+                    iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                        key: Key::Named(Named::ArrowDown),
+                        modifiers,
+                        ..
+                    }),
+                    Status::Ignored,
+                ) if modifiers.shift() => Some(Message::ChangeChannelView(ChannelViewChange::Height(-1), modifiers.control())),
                 (
                     iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                         key: Key::Character(k),
@@ -872,16 +3310,33 @@ impl NoctiG {
                     }),
                     Status::Ignored,
                 ) => match k.to_string().to_lowercase().as_str() {
-                    "w" => Some(Message::Rate(Stage::W)),
-                    "r" => Some(Message::Rate(Stage::R)),
-                    "1" => Some(Message::Rate(Stage::N1)),
-                    "2" => Some(Message::Rate(Stage::N2)),
-                    "3" => Some(Message::Rate(Stage::N3)),
+                    // Keys bound to actions have to be listed in `RESERVED_KEYS`, so no stage can use them
                     "t" => Some(Message::CycleTimeFormatter),
                     "l" => Some(Message::ToggleRangeDraw),
                     "h" => Some(Message::ToggleHelp),
+                    "i" => Some(Message::ToggleStatistics),
+                    "c" => Some(Message::ToggleComparison),
+                    "," => Some(Message::PreviousDisagreement),
+                    "." => Some(Message::NextDisagreement),
+                    "[" => Some(Message::SetLightsOff),
+                    "]" => Some(Message::SetLightsOn),
                     "j" => Some(Message::SeekTo),
+
+                    // Scaling of the focused channel, or of all channels of its type while holding control
+                    "+" | "=" => Some(Message::ChangeChannelView(ChannelViewChange::Gain(1), modifiers.control())),
+                    "-" => Some(Message::ChangeChannelView(ChannelViewChange::Gain(-1), modifiers.control())),
+                    "g" => Some(Message::ChangeChannelView(ChannelViewChange::CycleScale, modifiers.control())),
+                    "v" => Some(Message::ChangeChannelView(ChannelViewChange::Invert, modifiers.control())),
+                    "k" => Some(Message::CycleTimeGrid),
+                    "z" if !modifiers.control() => Some(Message::StepTimeSpan(if modifiers.shift() { 1 } else { -1 })),
+                    "b" => Some(Message::CycleReferenceBand),
                     "s" if modifiers.control() => Some(Message::SaveProject),
+                    "z" if modifiers.control() && modifiers.shift() => Some(Message::Redo),
+                    "z" if modifiers.control() => Some(Message::Undo),
+                    "y" if modifiers.control() => Some(Message::Redo),
+
+                    // All other keys are resolved using the stage vocabulary of the project
+                    key if !modifiers.control() && !modifiers.alt() => Some(Message::RateKey(key.to_string())),
                     _ => None
                 },
                 _ => None,
@@ -922,6 +3377,15 @@ fn resize_window(new_size: Size) -> Task<Message> {
     })
 }
 
+/// Gets the page of the project creation wizard containing the given field
+fn wizard_page(field: &ConfigurationField) -> Option<CreatePage> {
+    match field {
+        ConfigurationField::Name | ConfigurationField::Location => Some(CreatePage::Project),
+        ConfigurationField::Sources | ConfigurationField::Source(_) => Some(CreatePage::Data),
+        ConfigurationField::General => None
+    }
+}
+
 fn move_axis(app: &mut NoctiG, direction: i8) -> bool {
     let Some(project) = &mut app.current_project else {
         return false;
@@ -937,10 +3401,35 @@ fn move_axis(app: &mut NoctiG, direction: i8) -> bool {
     }
 
     // Move and read all visible samples
+    let (before, after) = project.context_epochs();
+    let segment_count = before as usize + after as usize + 1;
     for reader in &mut project.readers {
-        let segment_count = project.project.epochs_before_current as usize + project.project.epochs_after_current as usize + 1;
         seek_segmented(reader, segment_count, direction);
     }
+    project.update_derived_signals();
+    project.update_montage_signals();
+
+    true
+}
+
+fn seek_epoch(app: &mut NoctiG, epoch: u64) -> bool {
+    let Some(project) = &mut app.current_project else {
+        return false;
+    };
+
+    // Ensure not to surpass the last possible epoch across all readers
+    let epoch = epoch.min(project.epoch_count().saturating_sub(1));
+    project.zoom_page = 0;
+
+    // Move and read all visible samples
+    let (before, after) = project.context_epochs();
+    let segment_count = before as usize + after as usize + 1;
+    for reader in &mut project.readers {
+        let _ = reader.seek(EpochReader::EPOCH_DURATION as u64 * 1_000 * epoch);
+        reader.read_epochs(segment_count).unwrap();
+    }
+    project.update_derived_signals();
+    project.update_montage_signals();
 
     true
 }
@@ -961,14 +3450,25 @@ pub enum Marker {
     Purple
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub enum Stage {
-    W,
-    N1,
-    N2,
-    N3,
-    R,
-    Unset
+impl Marker {
+    pub const ALL: [Marker; 7] = [Marker::Red, Marker::Orange, Marker::Yellow, Marker::Green, Marker::Cyan, Marker::Blue, Marker::Purple];
+
+    /// Name of the marker used within exported files (e.g. "Red")
+    pub fn label(&self) -> &'static str {
+        match self {
+            Marker::Red => "Red",
+            Marker::Orange => "Orange",
+            Marker::Yellow => "Yellow",
+            Marker::Green => "Green",
+            Marker::Cyan => "Cyan",
+            Marker::Blue => "Blue",
+            Marker::Purple => "Purple"
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.label().eq_ignore_ascii_case(label.trim()))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -979,6 +3479,9 @@ enum Page {
     CreateProject(CreatePage),
     Settings,
     Scorer,
+    ProjectSettings,
+    RelinkSources,
+    Presets,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -988,39 +3491,60 @@ enum CreatePage {
     Processing
 }
 
-impl Stage {
-    pub fn map_str(&self) -> String {
-        match self {
-            Stage::W => "W".to_string(),
-            Stage::N1 => "N1".to_string(),
-            Stage::N2 => "N2".to_string(),
-            Stage::N3 => "N3".to_string(),
-            Stage::R => "R".to_string(),
-            Stage::Unset => "?".to_string()
-        }
-    }
-
-    pub fn background(stage: Stage) -> impl Fn(&Theme) -> widget::container::Style {
-        move |theme: &Theme| border_background_base(theme, &stage)
-    }
-
-    pub fn foreground(stage: Stage) -> impl Fn(&Theme) -> widget::text::Style {
-        move |theme: &Theme| text_foreground_base(theme, &stage)
-    }
-}
-
 #[derive(Clone)]
 enum Message {
     MoveAxis(i8),
-    Rate(Stage),
+    ScrollAxis(f32),
+    SelectEpoch(u64),
+    JumpToEpoch(u64),
+    SetLightsOff,
+    SetLightsOn,
+    ResetAnalysisPeriod,
+    DetectAnalysisPeriod,
+    AnalysisPeriodDetected(Option<u64>, Option<u64>),
+    SelectRange(Option<TimeSelection>),
+    FocusSignal(usize),
+    ChangeChannelView(ChannelViewChange, bool),     // Change and whether it applies to all channels of the type of the focused channel
+    ApplyChannelViewToType,
+    SetTimeSpan(TimeSpan),
+    StepTimeSpan(i32),          // Positive steps show more time
+    SetContextEpochs(u8, u8),   // Epochs before and after the current epoch
+    CycleTimeGrid,
+    CycleReferenceBand,
+    ClearSelection,
+    Rate(Option<Stage>),
+    RateKey(String),
+    SwitchScorer(String),
+    NewScorerNameChanged(String),
+    AddScorer,
+    ToggleComparison,
+    CompareWith(String),
+    NextDisagreement,
+    PreviousDisagreement,
+    StartAdjudication,
+    StopAdjudication,
+    AcceptLabel(String),
+    AddEvent(EventType),
+    RemoveEvent(usize),
+    Undo,
+    Redo,
     SpectrogramLoadStart,
     SpectrogramLoadProgress(f32),
     SpectrogramLoadFinish(Spectrogram),
+    SpectrogramChannelChanged(SpectrogramChannel),
+    SwitchMontage(MontagePage),
+    CycleMontage(i32),
     CycleTimeFormatter,
     ToggleRangeDraw,
     ToggleHelp,
+    ToggleStatistics,
     SeekTo,
     SaveProject,
+    Export(ExportFormat),
+    LaunchImport,
+    ImportFile(Option<PathBuf>),
+    ApplyImport,
+    CancelImport,
     SwitchPage(Page),
     WindowClosed(Id),
     ToggleExpandLicense(usize, usize, bool),
@@ -1036,6 +3560,18 @@ enum Message {
     OpenProjectPath(String),
     LaunchOpenProject,
     OpenProject(Option<PathBuf>),
+    OpenPaths(Vec<PathBuf>),
+    LaunchQuickView,
+    QuickView(Option<PathBuf>),
+    PromoteQuickView,
+    RelinkSearch,
+    RelinkSearchFinished(Vec<(usize, PathBuf)>),
+    RelinkLaunchAddDirectory,
+    RelinkAddDirectory(Option<PathBuf>),
+    RelinkLaunchBrowse(usize),
+    RelinkBrowse(usize, Option<PathBuf>),
+    ApplyRelink,
+    CancelRelink,
     ShowSourceCode,
     OpenURL(String),
     ShowPrivacyPolicy,
@@ -1055,7 +3591,78 @@ enum Message {
     LaunchBrowseImportSignal,
     BrowseImportSignal(Option<Vec<PathBuf>>),
     RemoveImportSignal(String),
+    ToggleImportSignalReference(String, bool),
+    ToggleImportSignalChannels(String),
+    ToggleImportChannelVisible(String, usize, bool),
+    ImportChannelNameChanged(String, usize, String),
+    ImportChannelTypeChanged(String, usize, ChannelType),
+    SaveImportChannelAlias(String, usize),
     ToggleFilterSignal(bool),
     ToggleClipSignal(bool),
-    ToggleAutoAlignSignals(bool)
+    ToggleAutoAlignSignals(bool),
+    StageVocabularyChanged(StageVocabulary),
+
+    // Project settings
+    OpenProjectSettings,
+    CancelProjectSettings,
+    SaveProjectSettings,
+    SettingsSourcesHashed(Result<Fingerprints, String>),
+    SettingsNameChanged(String),
+    SettingsNewTagChanged(String),
+    SettingsAddTag,
+    SettingsRemoveTag(usize),
+    SettingsEpochsBeforeChanged(u8),
+    SettingsEpochsAfterChanged(u8),
+    SettingsToggleFilterSignal(bool),
+    SettingsToggleClipSignal(bool),
+    SettingsToggleAutoAlignSignals(bool),
+    SettingsLaunchAddSource,
+    SettingsAddSources(Option<Vec<PathBuf>>),
+    SettingsRemoveSource(usize),
+    SettingsSourceOffsetChanged(usize, String),
+    SettingsToggleSourceReference(usize, bool),
+    SettingsAddDerivedChannel,
+    SettingsRemoveDerivedChannel(usize),
+    SettingsDerivedNameChanged(usize, String),
+    SettingsDerivedExpressionChanged(usize, String),
+    SettingsDerivedTypeChanged(usize, ChannelType),
+    SettingsAddMontage,
+    SettingsRemoveMontage(usize),
+    SettingsMontageNameChanged(usize, String),
+    SettingsAddMontageChannel(usize, String),
+    SettingsRemoveMontageChannel(usize, usize),
+    SettingsMoveMontageChannel(usize, usize, i32),
+    SettingsMontageChannelChanged(usize, usize, MontageChannelField, String),
+    SettingsLaunchImportMontage,
+    SettingsImportMontage(Option<PathBuf>),
+    SettingsLaunchExportMontage(usize),
+    SettingsExportMontage(usize, Option<PathBuf>),
+    SettingsSaveAsPreset,
+    CreatePresetChanged(ProjectPreset),
+    OpenPresetEditor,
+    ClosePresetEditor,
+    SavePresets,
+    PresetSelected(usize),
+    PresetAdd,
+    PresetRemove,
+    PresetLaunchImport,
+    PresetImport(Option<PathBuf>),
+    PresetLaunchExport,
+    PresetExport(Option<PathBuf>),
+    PresetNameChanged(String),
+    PresetEpochsBeforeChanged(u8),
+    PresetEpochsAfterChanged(u8),
+    PresetToggleFilterSignal(bool),
+    PresetToggleClipSignal(bool),
+    PresetToggleAutoAlignSignals(bool),
+    PresetStageVocabularyChanged(StageVocabulary),
+    PresetAddChannelRule,
+    PresetRemoveChannelRule(usize),
+    PresetChannelRuleLabelChanged(usize, String),
+    PresetChannelRuleNameChanged(usize, String),
+    PresetChannelRuleTypeChanged(usize, ChannelType),
+    PresetRemoveDerivedChannel(usize),
+    PresetLaunchImportMontage,
+    PresetImportMontage(Option<PathBuf>),
+    PresetRemoveMontage(usize)
 }
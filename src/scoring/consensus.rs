@@ -0,0 +1,153 @@
+use crate::Scorings;
+
+// Name of the scorer set holding the consensus scores
+pub const CONSENSUS_SCORER: &str = "Consensus";
+
+// Provenance of epochs which were scored equally by all scorers
+pub const PROVENANCE_AGREEMENT: &str = "Agreement";
+
+// Provenance of epochs which were scored by the adjudicator with a stage none of the scorers chose
+pub const PROVENANCE_ADJUDICATOR: &str = "Adjudicator";
+
+/// State of an ongoing adjudication of the disagreements between multiple scorer sets
+#[derive(Debug, Clone)]
+pub struct Adjudication {
+    // Names of all scorer sets which are adjudicated
+    pub scorers: Vec<String>,
+
+    // Epochs where the scorers disagree
+    pub epochs: Vec<u64>
+}
+
+impl Adjudication {
+    /// Fills the consensus with all epochs the scorers agree on and collects all epochs where they
+    /// disagree. Epochs which were already adjudicated before are kept as they are.
+    pub fn prepare(consensus: &mut Scorings, scorers: &[(String, &Scorings)], epoch_count: u64) -> Self {
+        let mut epochs = Vec::new();
+        for epoch in 0..epoch_count {
+            let mut stages = scorers.iter().map(|(_, s)| s.values.get(&epoch));
+            let Some(first) = stages.next() else {
+                break;
+            };
+
+            // Epochs which were not scored by anyone do not require adjudication
+            if scorers.iter().all(|(_, s)| !s.values.contains_key(&epoch)) {
+                continue;
+            }
+
+            match first {
+                Some(stage) if stages.all(|s| s == Some(stage)) => {
+                    consensus.values.insert(epoch, stage.clone());
+                    consensus.provenance.insert(epoch, PROVENANCE_AGREEMENT.to_string());
+                }
+                _ => {
+                    // Epochs filled automatically before, which are no longer agreed on, have to be adjudicated again
+                    if consensus.provenance.get(&epoch).is_some_and(|p| p == PROVENANCE_AGREEMENT) {
+                        consensus.values.remove(&epoch);
+                        consensus.provenance.remove(&epoch);
+                    }
+                    epochs.push(epoch);
+                }
+            }
+        }
+
+        Self {
+            scorers: scorers.iter().map(|(name, _)| name.clone()).collect(),
+            epochs
+        }
+    }
+
+    /// Gets the next disagreeing epoch after the given one which was not adjudicated yet.
+    /// In case there is none, the first one not adjudicated yet is used.
+    pub fn next_open(&self, consensus: &Scorings, after: u64) -> Option<u64> {
+        let is_open = |epoch: &&u64| !consensus.values.contains_key(*epoch);
+        self.epochs.iter().filter(is_open).find(|e| **e > after)
+            .or_else(|| self.epochs.iter().find(is_open))
+            .copied()
+    }
+
+    /// Gets the amount of disagreeing epochs which were already adjudicated
+    pub fn resolved(&self, consensus: &Scorings) -> usize {
+        self.epochs.iter().filter(|e| consensus.values.contains_key(*e)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::stages::Stage;
+
+    /// Creates scorings from the given stage ids, where "-" leaves the epoch unscored
+    fn scorings(stages: &[&str]) -> Scorings {
+        let mut scorings = Scorings::new(30);
+        for (epoch, stage) in stages.iter().enumerate() {
+            if *stage != "-" {
+                scorings.values.insert(epoch as u64, Stage::new(stage));
+            }
+        }
+        scorings
+    }
+
+    fn prepare(consensus: &mut Scorings, first: &Scorings, second: &Scorings) -> Adjudication {
+        Adjudication::prepare(consensus, &[("A".to_string(), first), ("B".to_string(), second)], 6)
+    }
+
+    #[test]
+    fn fills_agreements_and_collects_disagreements() {
+        let first = scorings(&["W", "N1", "N2", "-", "R", "N2"]);
+        let second = scorings(&["W", "N2", "N2", "-", "-", "N2"]);
+        let mut consensus = Scorings::new(30);
+        let adjudication = prepare(&mut consensus, &first, &second);
+
+        // Epochs scored by only one scorer have to be adjudicated as well
+        assert_eq!(adjudication.scorers, vec!["A", "B"]);
+        assert_eq!(adjudication.epochs, vec![1, 4]);
+        assert_eq!(consensus.values, scorings(&["W", "-", "N2", "-", "-", "N2"]).values);
+        assert!(consensus.provenance.values().all(|p| p == PROVENANCE_AGREEMENT));
+        assert_eq!(adjudication.resolved(&consensus), 0);
+    }
+
+    #[test]
+    fn keeps_adjudicated_epochs_and_removes_outdated_agreements() {
+        let first = scorings(&["W", "N1", "N2", "N2", "R", "N2"]);
+        let second = scorings(&["W", "N2", "N2", "N2", "R", "N2"]);
+        let mut consensus = Scorings::new(30);
+        prepare(&mut consensus, &first, &second);
+        consensus.values.insert(1, Stage::new("N1"));
+        consensus.provenance.insert(1, "A".to_string());
+
+        // The second scorer changed epochs 1 and 3 afterwards
+        let second = scorings(&["W", "W", "N2", "N3", "R", "N2"]);
+        let adjudication = prepare(&mut consensus, &first, &second);
+
+        assert_eq!(adjudication.epochs, vec![1, 3]);
+        assert_eq!(consensus.values.get(&1), Some(&Stage::new("N1")));
+        assert_eq!(consensus.provenance.get(&1).map(String::as_str), Some("A"));
+        assert_eq!(consensus.values.get(&3), None);
+        assert_eq!(consensus.provenance.get(&3), None);
+        assert_eq!(adjudication.resolved(&consensus), 1);
+    }
+
+    #[test]
+    fn finds_the_next_open_epoch() {
+        let first = scorings(&["W", "N1", "N2", "N1", "R", "N1"]);
+        let second = scorings(&["N1", "N1", "W", "N1", "N2", "N1"]);
+        let mut consensus = Scorings::new(30);
+        let adjudication = prepare(&mut consensus, &first, &second);
+        assert_eq!(adjudication.epochs, vec![0, 2, 4]);
+
+        assert_eq!(adjudication.next_open(&consensus, 0), Some(2));
+        assert_eq!(adjudication.next_open(&consensus, 2), Some(4));
+
+        // Continues with the first open epoch after the last one
+        assert_eq!(adjudication.next_open(&consensus, 4), Some(0));
+        consensus.values.insert(0, Stage::new("W"));
+        consensus.values.insert(4, Stage::new("R"));
+        assert_eq!(adjudication.next_open(&consensus, 2), Some(2));
+        assert_eq!(adjudication.resolved(&consensus), 2);
+
+        consensus.values.insert(2, Stage::new("N2"));
+        assert_eq!(adjudication.next_open(&consensus, 0), None);
+        assert_eq!(adjudication.resolved(&consensus), 3);
+    }
+}
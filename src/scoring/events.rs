@@ -0,0 +1,101 @@
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+/// Type of a scored sub-epoch event
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    Arousal,
+    ObstructiveApnea,
+    CentralApnea,
+    MixedApnea,
+    Hypopnea,
+    Desaturation,
+    LimbMovement,
+    PeriodicLimbMovement
+}
+
+impl EventType {
+    pub const ALL: [EventType; 8] = [
+        EventType::Arousal,
+        EventType::ObstructiveApnea,
+        EventType::CentralApnea,
+        EventType::MixedApnea,
+        EventType::Hypopnea,
+        EventType::Desaturation,
+        EventType::LimbMovement,
+        EventType::PeriodicLimbMovement
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventType::Arousal => "Arousal",
+            EventType::ObstructiveApnea => "Obstructive apnea",
+            EventType::CentralApnea => "Central apnea",
+            EventType::MixedApnea => "Mixed apnea",
+            EventType::Hypopnea => "Hypopnea",
+            EventType::Desaturation => "Desaturation",
+            EventType::LimbMovement => "Limb movement",
+            EventType::PeriodicLimbMovement => "Periodic limb movement"
+        }
+    }
+
+    /// Short label used for drawing the event on top of a signal
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            EventType::Arousal => "Ar",
+            EventType::ObstructiveApnea => "OA",
+            EventType::CentralApnea => "CA",
+            EventType::MixedApnea => "MA",
+            EventType::Hypopnea => "H",
+            EventType::Desaturation => "Desat",
+            EventType::LimbMovement => "LM",
+            EventType::PeriodicLimbMovement => "PLM"
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            EventType::Arousal => Color::from_rgb8(240, 200, 80),
+            EventType::ObstructiveApnea => Color::from_rgb8(230, 90, 90),
+            EventType::CentralApnea => Color::from_rgb8(200, 110, 220),
+            EventType::MixedApnea => Color::from_rgb8(235, 130, 170),
+            EventType::Hypopnea => Color::from_rgb8(240, 150, 80),
+            EventType::Desaturation => Color::from_rgb8(90, 180, 230),
+            EventType::LimbMovement => Color::from_rgb8(110, 200, 130),
+            EventType::PeriodicLimbMovement => Color::from_rgb8(70, 170, 150)
+        }
+    }
+}
+
+/// A sub-epoch event bound to a single channel of a signal source. The onset is stored
+/// in milliseconds relative to the start of the first epoch of the project.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoredEvent {
+    pub event_type: EventType,
+
+    // Index of the signal source within the project and the label of the channel
+    pub source: usize,
+    pub channel: String,
+
+    pub onset: u64,
+    pub duration: u64
+}
+
+impl ScoredEvent {
+    pub fn end(&self) -> u64 {
+        self.onset + self.duration
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Events {
+    pub values: Vec<ScoredEvent>
+}
+
+impl Events {
+    /// Inserts the event while keeping all events ordered by their onset
+    pub fn insert(&mut self, event: ScoredEvent) {
+        let index = self.values.partition_point(|e| e.onset <= event.onset);
+        self.values.insert(index, event);
+    }
+}
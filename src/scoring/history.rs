@@ -0,0 +1,42 @@
+// Maximum amount of changes which can be undone
+const HISTORY_LIMIT: usize = 200;
+
+/// Undo / redo stacks of snapshots of an editable state
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new()
+        }
+    }
+}
+
+impl<T> History<T> {
+    /// Stores the state before a change. Any previously undone changes can no longer be redone.
+    pub fn record(&mut self, state: T) {
+        if self.undo.len() >= HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(state);
+        self.redo.clear();
+    }
+
+    /// Gets the state before the last change and stores the given current state for redoing
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Gets the state of the last undone change and stores the given current state for undoing
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+}
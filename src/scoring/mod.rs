@@ -0,0 +1,4 @@
+pub mod stages;
+pub mod events;
+pub mod history;
+pub mod consensus;
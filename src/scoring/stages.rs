@@ -0,0 +1,251 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use iced::{Color, Theme, widget};
+use serde::{Deserialize, Serialize};
+
+use crate::formatting::theme::{border_background_base, text_foreground_base};
+
+// Keys bound to actions within the scorer (see `NoctiG::subscription`), which therefore cannot be used to score stages
+pub const RESERVED_KEYS: [&str; 18] = ["t", "l", "h", "i", "c", ",", ".", "[", "]", "j", "+", "=", "-", "g", "v", "k", "z", "b"];
+
+/// Identifier of a stage within the stage vocabulary of a project. This is the value
+/// stored in the scores file (e.g. `"N2"`), which keeps existing scores files compatible.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct Stage(pub String);
+
+impl Stage {
+    pub fn new(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum StageCategory {
+    Wake,
+    NonRem,
+    Rem,
+    Other
+}
+
+impl StageCategory {
+    pub fn is_sleep(&self) -> bool {
+        matches!(self, StageCategory::NonRem | StageCategory::Rem)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StageDefinition {
+    pub id: String,
+    pub label: String,
+    pub key: String,
+    pub color: String,
+    pub category: StageCategory,
+
+    // Row of the stage in the hypnogram, starting with 0 at the top
+    pub hypnogram_level: u8,
+
+    // Integer code used for exporting hypnograms (e.g. YASA convention)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<i8>,
+
+    // Text of the stage in EDF+ annotations. Defaults to "Sleep stage <id>"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<String>
+}
+
+impl StageDefinition {
+    fn new(id: &str, key: &str, color: Color, category: StageCategory, hypnogram_level: u8, code: Option<i8>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: id.to_string(),
+            key: key.to_string(),
+            color: color.to_string(),
+            category,
+            hypnogram_level,
+            code,
+            annotation: None
+        }
+    }
+
+    fn with_annotation(mut self, annotation: &str) -> Self {
+        self.annotation = Some(annotation.to_string());
+        self
+    }
+
+    pub fn stage(&self) -> Stage {
+        Stage(self.id.clone())
+    }
+
+    pub fn color(&self) -> Color {
+        Color::from_str(&self.color).unwrap_or(Color::from_rgb8(130, 130, 130))
+    }
+
+    /// Gets the text of the stage used in EDF+ annotations (e.g. "Sleep stage N2")
+    pub fn annotation_text(&self) -> String {
+        self.annotation.clone().unwrap_or(format!("Sleep stage {}", self.id))
+    }
+}
+
+/// The set of stages available for scoring in a project
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StageVocabulary {
+    pub name: String,
+    #[serde(default)]
+    pub stages: Vec<StageDefinition>
+}
+
+impl StageVocabulary {
+    pub fn aasm() -> Self {
+        use StageCategory::*;
+
+        Self {
+            name: "AASM".to_string(),
+            stages: vec![
+                StageDefinition::new("W",  "w", Color::from_rgb8(213, 150,  68), Wake,   0, Some(0)),
+                StageDefinition::new("N1", "1", Color::from_rgb8( 80, 165, 255), NonRem, 2, Some(1)),
+                StageDefinition::new("N2", "2", Color::from_rgb8(130, 105, 235), NonRem, 3, Some(2)),
+                StageDefinition::new("N3", "3", Color::from_rgb8(180,  75, 215), NonRem, 4, Some(3)),
+                StageDefinition::new("R",  "r", Color::from_rgb8(215,  74,  47), Rem,    1, Some(4)),
+            ]
+        }
+    }
+
+    pub fn rechtschaffen_kales() -> Self {
+        use StageCategory::*;
+
+        Self {
+            name: "R&K".to_string(),
+            stages: vec![
+                StageDefinition::new("W",  "w", Color::from_rgb8(213, 150,  68), Wake,   0, Some(0)),
+                StageDefinition::new("S1", "1", Color::from_rgb8( 80, 165, 255), NonRem, 3, Some(1)).with_annotation("Sleep stage 1"),
+                StageDefinition::new("S2", "2", Color::from_rgb8(130, 105, 235), NonRem, 4, Some(2)).with_annotation("Sleep stage 2"),
+                StageDefinition::new("S3", "3", Color::from_rgb8(165,  85, 225), NonRem, 5, Some(3)).with_annotation("Sleep stage 3"),
+                StageDefinition::new("S4", "4", Color::from_rgb8(190,  65, 205), NonRem, 6, Some(3)).with_annotation("Sleep stage 4"),
+                StageDefinition::new("R",  "r", Color::from_rgb8(215,  74,  47), Rem,    2, Some(4)),
+                StageDefinition::new("MT", "m", Color::from_rgb8(120, 120, 120), Other,  1, Some(-1)).with_annotation("Movement time"),
+            ]
+        }
+    }
+
+    pub fn rodent() -> Self {
+        use StageCategory::*;
+
+        Self {
+            name: "Rodent".to_string(),
+            stages: vec![
+                StageDefinition::new("Wake",     "w", Color::from_rgb8(213, 150,  68), Wake,   0, Some(0)),
+                StageDefinition::new("NREM",     "n", Color::from_rgb8(130, 105, 235), NonRem, 2, Some(2)),
+                StageDefinition::new("REM",      "r", Color::from_rgb8(215,  74,  47), Rem,    1, Some(4)),
+                StageDefinition::new("Artifact", "a", Color::from_rgb8(120, 120, 120), Other,  3, Some(-1)),
+            ]
+        }
+    }
+
+    /// Gets all built-in vocabularies
+    pub fn presets() -> Vec<Self> {
+        vec![Self::aasm(), Self::rechtschaffen_kales(), Self::rodent()]
+    }
+
+    pub fn get(&self, stage: &Stage) -> Option<&StageDefinition> {
+        self.stages.iter().find(|s| s.id == stage.0)
+    }
+
+    pub fn by_key(&self, key: &str) -> Option<&StageDefinition> {
+        self.stages.iter().find(|s| s.key.eq_ignore_ascii_case(key))
+    }
+
+    pub fn label(&self, stage: Option<&Stage>) -> String {
+        match stage {
+            Some(stage) => self.get(stage).map(|s| s.label.clone()).unwrap_or(stage.0.clone()),
+            None => "?".to_string()
+        }
+    }
+
+    pub fn color(&self, stage: &Stage) -> Option<Color> {
+        self.get(stage).map(StageDefinition::color)
+    }
+
+    pub fn category(&self, stage: &Stage) -> Option<&StageCategory> {
+        self.get(stage).map(|s| &s.category)
+    }
+
+    pub fn background(&self, stage: Option<&Stage>) -> impl Fn(&Theme) -> widget::container::Style + use<> {
+        let color = stage.and_then(|s| self.color(s));
+        move |theme: &Theme| border_background_base(theme, color)
+    }
+
+    pub fn foreground(&self, stage: Option<&Stage>) -> impl Fn(&Theme) -> widget::text::Style + use<> {
+        let is_set = stage.is_some();
+        move |theme: &Theme| text_foreground_base(theme, is_set)
+    }
+
+    /// Gets all problems which prevent scoring with the vocabulary, i.e. stages without a key, keys bound
+    /// to multiple stages and keys which are reserved for other actions of the scorer
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            let key = stage.key.trim().to_lowercase();
+            if key.is_empty() {
+                errors.push(format!("Stage {} has no key", stage.id));
+            } else if RESERVED_KEYS.contains(&key.as_str()) {
+                errors.push(format!("Stage {} uses the key {} which is reserved for another action", stage.id, stage.key));
+            } else if self.stages[..i].iter().any(|s| s.key.trim().eq_ignore_ascii_case(&key)) {
+                errors.push(format!("Stage {} uses the key {} of another stage", stage.id, stage.key));
+            }
+        }
+
+        errors
+    }
+
+    /// Gets the amount of rows required to draw the hypnogram
+    pub fn hypnogram_levels(&self) -> u8 {
+        self.stages.iter().map(|s| s.hypnogram_level + 1).max().unwrap_or(0)
+    }
+}
+
+impl Default for StageVocabulary {
+    fn default() -> Self {
+        Self::aasm()
+    }
+}
+
+impl PartialEq for StageVocabulary {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Display for StageVocabulary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_vocabularies_are_valid() {
+        for vocabulary in StageVocabulary::presets() {
+            assert_eq!(vocabulary.validate(), Vec::<String>::new(), "{}", vocabulary.name);
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_and_duplicate_keys() {
+        let mut vocabulary = StageVocabulary::aasm();
+        vocabulary.stages[1].key = "K".to_string();
+        vocabulary.stages[2].key = "w".to_string();
+        vocabulary.stages[3].key = " ".to_string();
+
+        assert_eq!(vocabulary.validate(), vec![
+            "Stage N1 uses the key K which is reserved for another action",
+            "Stage N2 uses the key w of another stage",
+            "Stage N3 has no key"
+        ]);
+    }
+}
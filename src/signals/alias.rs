@@ -0,0 +1,33 @@
+use crate::database::types::ChannelAlias;
+
+// Prefixes devices commonly put in front of the actual derivation (e.g. "EEG C4-M1")
+const TYPE_PREFIXES: [&str; 6] = ["EEG", "EOG", "EMG", "ECG", "EKG", "RESP"];
+
+/// Normalizes the spelling of a channel label, so that e.g. "C4-A1", "c4:a1" and "C4 - A1" are the same
+pub fn normalize(label: &str) -> String {
+    let label = label.trim().to_uppercase().replace([':', '/', '_'], "-");
+    let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+    label.replace(" -", "-").replace("- ", "-")
+}
+
+/// Gets the normalized label and the normalized label without its type prefix (if it has one)
+fn spellings(label: &str) -> Vec<String> {
+    let label = normalize(label);
+    let unprefixed = TYPE_PREFIXES.iter()
+        .find_map(|prefix| label.strip_prefix(prefix).and_then(|l| l.strip_prefix([' ', '-'])))
+        .map(str::to_string);
+
+    std::iter::once(label).chain(unprefixed).collect()
+}
+
+/// Finds the alias matching the given label. Labels with a type prefix (e.g. "EEG C4-A1") are
+/// matched without it in case there is no alias for the entire label.
+pub fn find<'a>(aliases: &'a [ChannelAlias], label: &str) -> Option<&'a ChannelAlias> {
+    spellings(label).into_iter().find_map(|label| aliases.iter().find(|a| normalize(&a.alias) == label))
+}
+
+/// Checks whether the label refers to the given channel label, ignoring the spelling and type prefix (e.g. "C4" and "EEG C4")
+pub fn is_same_channel(label: &str, channel: &str) -> bool {
+    let label = normalize(label);
+    spellings(channel).contains(&label)
+}
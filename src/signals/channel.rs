@@ -0,0 +1,182 @@
+use std::fmt::Display;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::database::types::ChannelAlias;
+use crate::signals::alias;
+
+/// Type of the measured signal of a channel, which determines its default filter, scaling and color
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelType {
+    Eeg,
+    Eog,
+    Emg,
+    Ecg,
+    Resp,
+    SpO2,
+    Other
+}
+
+impl ChannelType {
+    pub const ALL: [ChannelType; 7] = [
+        ChannelType::Eeg,
+        ChannelType::Eog,
+        ChannelType::Emg,
+        ChannelType::Ecg,
+        ChannelType::Resp,
+        ChannelType::SpO2,
+        ChannelType::Other
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChannelType::Eeg => "EEG",
+            ChannelType::Eog => "EOG",
+            ChannelType::Emg => "EMG",
+            ChannelType::Ecg => "ECG",
+            ChannelType::Resp => "Resp",
+            ChannelType::SpO2 => "SpO2",
+            ChannelType::Other => "Other"
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.label().eq_ignore_ascii_case(label.trim()))
+    }
+
+    /// Guesses the type of a channel by common parts of its label (e.g. "EEG Fpz-Cz" or "Chin EMG")
+    pub fn detect(label: &str) -> Self {
+        const KEYWORDS: [(ChannelType, &[&str]); 6] = [
+            (ChannelType::SpO2, &["spo2", "sao2", "sat", "oxy", "pleth"]),
+            (ChannelType::Ecg, &["ecg", "ekg"]),
+            (ChannelType::Eog, &["eog", "loc", "roc", "e1", "e2"]),
+            (ChannelType::Emg, &["emg", "chin", "leg", "tib", "submental"]),
+            (ChannelType::Resp, &["resp", "thor", "abd", "flow", "nasal", "cannula", "pressure", "snore", "effort"]),
+            (ChannelType::Eeg, &["eeg", "fp", "fz", "cz", "pz", "oz", "f3", "f4", "c3", "c4", "o1", "o2", "m1", "m2", "a1", "a2"])
+        ];
+
+        let label = label.to_lowercase();
+        KEYWORDS.iter()
+            .find(|(_, keywords)| keywords.iter().any(|k| label.contains(k)))
+            .map(|(channel_type, _)| *channel_type)
+            .unwrap_or(ChannelType::Other)
+    }
+
+    /// Default filter of the channel type (AASM recommendations) as high-pass and low-pass cutoff frequency in Hz
+    pub fn default_filter(&self) -> (Option<f64>, Option<f64>) {
+        match self {
+            ChannelType::Eeg | ChannelType::Eog => (Some(0.3), Some(35.0)),
+            ChannelType::Emg => (Some(10.0), Some(100.0)),
+            ChannelType::Ecg => (Some(0.3), Some(70.0)),
+            ChannelType::Resp => (Some(0.1), Some(15.0)),
+            ChannelType::SpO2 | ChannelType::Other => (None, None)
+        }
+    }
+
+    /// Default amplitude shown above and below the baseline in µV. Channels without a default
+    /// amplitude (or without voltage unit) are shown using their entire physical range.
+    pub fn default_amplitude(&self) -> Option<f64> {
+        match self {
+            ChannelType::Eeg => Some(100.0),
+            ChannelType::Eog => Some(200.0),
+            ChannelType::Emg => Some(50.0),
+            ChannelType::Ecg => Some(1_000.0),
+            ChannelType::Resp | ChannelType::SpO2 | ChannelType::Other => None
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            ChannelType::Eeg => Color::from_rgb(0.26, 0.46, 0.86),
+            ChannelType::Eog => Color::from_rgb8(110, 200, 130),
+            ChannelType::Emg => Color::from_rgb8(240, 150, 80),
+            ChannelType::Ecg => Color::from_rgb8(230, 90, 90),
+            ChannelType::Resp => Color::from_rgb8(90, 180, 230),
+            ChannelType::SpO2 => Color::from_rgb8(200, 110, 220),
+            ChannelType::Other => Color::from_rgb8(150, 150, 150)
+        }
+    }
+}
+
+impl Display for ChannelType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Gets the factor to convert a value of the given physical dimension to µV, if it is a voltage
+pub fn microvolt_factor(dimension: &str) -> Option<f64> {
+    match dimension.trim() {
+        "uV" | "µV" | "μV" => Some(1.0),
+        "mV" => Some(1_000.0),
+        "V" => Some(1_000_000.0),
+        _ => None
+    }
+}
+
+/// Display settings of a single channel of a signal source, identified by its label within the EDF file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChannelSettings {
+    pub label: String,
+
+    // Name shown instead of the label, e.g. "Fpz-Cz" instead of "EEG Fpz-Cz"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    pub channel_type: ChannelType,
+    pub visible: bool
+}
+
+impl ChannelSettings {
+    /// Default settings of a channel, the type is guessed from the label
+    pub fn detect(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            name: None,
+            channel_type: ChannelType::detect(label),
+            visible: true
+        }
+    }
+
+    /// Default settings of a channel using the name and type of the matching alias. Channels
+    /// without alias keep their label and the type is guessed.
+    pub fn from_aliases(label: &str, aliases: &[ChannelAlias]) -> Self {
+        match alias::find(aliases, label) {
+            Some(alias) => Self {
+                label: label.to_string(),
+                name: Some(alias.name.clone()).filter(|n| n != label),
+                channel_type: alias.channel_type,
+                visible: true
+            },
+            None => Self::detect(label)
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().filter(|n| !n.trim().is_empty()).unwrap_or(&self.label)
+    }
+
+    /// Default display of the channel using the filter, amplitude and color of its type
+    pub fn display(&self) -> SignalDisplay {
+        let (high_pass, low_pass) = self.channel_type.default_filter();
+        SignalDisplay {
+            name: self.display_name().to_string(),
+            color: self.channel_type.color(),
+            high_pass,
+            low_pass,
+            amplitude: self.channel_type.default_amplitude()
+        }
+    }
+}
+
+/// How the samples of a channel are shown, i.e. its name, color, filter and the amplitude in µV
+/// shown above and below the baseline (channels without amplitude show their entire range)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDisplay {
+    pub name: String,
+    pub color: Color,
+    pub high_pass: Option<f64>,
+    pub low_pass: Option<f64>,
+    pub amplitude: Option<f64>
+}
@@ -0,0 +1,421 @@
+use std::error::Error;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use serde::{Deserialize, Serialize};
+
+use crate::signals::alias;
+use crate::signals::channel::{ChannelSettings, ChannelType, SignalDisplay};
+use crate::storage::epoch_reader::{ChartSignal, EpochReader};
+
+/// Channel calculated from other channels of the project, e.g. `[C4] - [M1]` for re-referencing C4
+/// to M1 or `[E1] - [E2]` for a bipolar EOG. Channels are referenced by their label or name in square
+/// brackets, optionally prefixed with the number of the source (e.g. `[2:Fpz]`) to disambiguate them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DerivedChannel {
+    pub name: String,
+    pub expression: String,
+    pub channel_type: ChannelType
+}
+
+impl DerivedChannel {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            expression: String::new(),
+            channel_type: ChannelType::Eeg
+        }
+    }
+
+    /// Parses the expression and finds the referenced channels within the channels of the sources
+    pub fn resolve(&self, sources: &[&[ChannelSettings]]) -> Result<Expression, Box<dyn Error>> {
+        let mut expression = Parser::new(&self.expression).parse()?;
+        expression.resolve(sources)?;
+        Ok(expression)
+    }
+
+    /// Calculates the samples of the last read epochs of the readers and their sample rate. The highest
+    /// sample rate of the used channels is used, all other channels are resampled to it.
+    pub fn samples(&self, readers: &[EpochReader]) -> Result<(Vec<f64>, f64), Box<dyn Error>> {
+        self.calculate(readers).map(|(samples, sample_rate, _)| (samples, sample_rate))
+    }
+
+    /// Calculates the samples, their sample rate and gets the first used channel
+    fn calculate(&self, readers: &[EpochReader]) -> Result<(Vec<f64>, f64, ChannelRef), Box<dyn Error>> {
+        let channels = readers.iter().map(|r| r.get_channels().as_slice()).collect::<Vec<_>>();
+        let expression = self.resolve(&channels)?;
+        let used = expression.channels();
+        let (length, sample_rate) = used.iter()
+            .filter_map(|c| Some((readers[c.source].get_samples(c.channel)?.len(), readers[c.source].sample_rate(c.channel)?)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .ok_or("The expression does not use any channel")?;
+
+        let samples = expression.evaluate(length, &|c| readers.get(c.source)?.get_samples(c.channel).cloned());
+        Ok((samples, sample_rate, used[0]))
+    }
+
+    /// Default display of the derived channel using the filter, amplitude and color of its type
+    pub fn display(&self) -> SignalDisplay {
+        let channel = ChannelSettings {
+            label: self.name.clone(),
+            name: None,
+            channel_type: self.channel_type,
+            visible: true
+        };
+
+        channel.display()
+    }
+
+    /// Calculates the derived channel from the last read epochs of the readers. The unit and range are taken from the first used channel.
+    pub fn chart_signal(&self, index: usize, readers: &[EpochReader], display: &SignalDisplay) -> Result<ChartSignal, Box<dyn Error>> {
+        let (samples, sample_rate, first) = self.calculate(readers)?;
+        let reader = &readers[first.source];
+        let signal = reader.get_signals().iter().filter(|s| !s.is_annotation()).nth(first.channel).ok_or("Channel does not exist")?;
+
+        // Differences can exceed the range of the original channel, therefore the range is made symmetric
+        let range = signal.physical_minimum.abs().max(signal.physical_maximum.abs());
+
+        Ok(ChartSignal::from_samples(index, self.name.clone(), display, &signal.physical_dimension, (-range, range), samples, reader.is_filtered().then_some(sample_rate)))
+    }
+}
+
+/// Splits a reference to a channel into the index of its source (given as number before a colon, e.g. `2:C4`) and its label or name
+pub fn split_reference(reference: &str) -> (Option<usize>, &str) {
+    if let Some((source, name)) = reference.split_once(':') && let Some(source) = source.trim().parse::<usize>().ok().filter(|s| *s > 0) {
+        return (Some(source - 1), name.trim());
+    }
+
+    (None, reference.trim())
+}
+
+/// Rewrites a reference to a channel after the source at the given index was removed. References to later sources are
+/// moved to their new number, references to the removed source are marked (e.g. `removed 2:Fpz`), so they cannot be
+/// resolved to a channel of another source.
+pub fn remove_source_reference(reference: &str, removed: usize) -> String {
+    match split_reference(reference) {
+        (Some(source), name) if source == removed => format!("removed {}:{}", source + 1, name),
+        (Some(source), name) if source > removed => format!("{}:{}", source, name),
+        _ => reference.to_string()
+    }
+}
+
+/// Rewrites all channel references in square brackets within the expression after the source at the given index was removed
+pub fn remove_source_references(expression: &str, removed: usize) -> String {
+    let mut result = String::new();
+    let mut rest = expression;
+    while let Some((before, after)) = rest.split_once('[') {
+        let Some((reference, after)) = after.split_once(']') else {
+            break;
+        };
+        result.push_str(before);
+        result.push_str(&format!("[{}]", remove_source_reference(reference, removed)));
+        rest = after;
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Finds the channel with the given label or name within the channels of the sources, optionally only within a single source
+pub fn find_channel(sources: &[&[ChannelSettings]], source: Option<usize>, name: &str) -> Option<ChannelRef> {
+    let matches = |c: &ChannelSettings| alias::is_same_channel(name, &c.label) || alias::is_same_channel(name, c.display_name());
+    sources.iter().enumerate()
+        .filter(|(i, _)| source.is_none_or(|s| s == *i))
+        .find_map(|(i, channels)| channels.iter().position(matches).map(|channel| ChannelRef { source: i, channel }))
+}
+
+/// Reference to a channel by the index of its source and the index of the channel within the signals (without annotations)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelRef {
+    pub source: usize,
+    pub channel: usize
+}
+
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Number(f64),
+    Channel { name: String, source: Option<usize>, resolved: Option<ChannelRef> },
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, char, Box<Expression>),
+    Average(Vec<Expression>)
+}
+
+impl Expression {
+    /// Gets all channels used within the expression
+    pub fn channels(&self) -> Vec<ChannelRef> {
+        let mut channels = Vec::new();
+        self.collect_channels(&mut channels);
+        channels
+    }
+
+    fn collect_channels(&self, channels: &mut Vec<ChannelRef>) {
+        match self {
+            Expression::Number(_) => {}
+            Expression::Channel { resolved, .. } => {
+                if let Some(channel) = resolved && !channels.contains(channel) {
+                    channels.push(*channel);
+                }
+            }
+            Expression::Negate(inner) => inner.collect_channels(channels),
+            Expression::Binary(left, _, right) => {
+                left.collect_channels(channels);
+                right.collect_channels(channels);
+            }
+            Expression::Average(items) => items.iter().for_each(|i| i.collect_channels(channels))
+        }
+    }
+
+    fn resolve(&mut self, sources: &[&[ChannelSettings]]) -> Result<(), Box<dyn Error>> {
+        match self {
+            Expression::Number(_) => Ok(()),
+            Expression::Channel { name, source, resolved } => {
+                let found = find_channel(sources, *source, name).ok_or(format!("Channel [{}] does not exist", name))?;
+                *resolved = Some(found);
+                Ok(())
+            }
+            Expression::Negate(inner) => inner.resolve(sources),
+            Expression::Binary(left, _, right) => {
+                left.resolve(sources)?;
+                right.resolve(sources)
+            }
+            Expression::Average(items) => items.iter_mut().try_for_each(|i| i.resolve(sources))
+        }
+    }
+
+    /// Evaluates the expression sample by sample. The samples of the referenced channels are resampled
+    /// to the given length in case they have a different sample rate.
+    pub fn evaluate(&self, length: usize, samples: &dyn Fn(ChannelRef) -> Option<Vec<f64>>) -> Vec<f64> {
+        match self {
+            Expression::Number(value) => vec![*value; length],
+            Expression::Channel { resolved, .. } => resolved.and_then(samples)
+                .map(|s| resample(&s, length))
+                .unwrap_or_else(|| vec![f64::NAN; length]),
+            Expression::Negate(inner) => inner.evaluate(length, samples).into_iter().map(|v| -v).collect(),
+            Expression::Binary(left, operator, right) => {
+                let right = right.evaluate(length, samples);
+                left.evaluate(length, samples).into_iter().zip(right).map(|(l, r)| match operator {
+                    '+' => l + r,
+                    '-' => l - r,
+                    '*' => l * r,
+                    _ => l / r
+                }).collect()
+            }
+            Expression::Average(items) => {
+                let mut sum = vec![0.0; length];
+                for item in items {
+                    sum.iter_mut().zip(item.evaluate(length, samples)).for_each(|(s, v)| *s += v);
+                }
+                sum.into_iter().map(|s| s / items.len() as f64).collect()
+            }
+        }
+    }
+}
+
+/// Linearly interpolates the samples to the given amount of samples covering the same time
+fn resample(samples: &[f64], length: usize) -> Vec<f64> {
+    if samples.len() == length || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let step = samples.len() as f64 / length as f64;
+    (0..length).map(|i| {
+        let position = i as f64 * step;
+        let index = position.floor() as usize;
+        let next = samples.get(index + 1).unwrap_or(&samples[index]);
+        samples[index] + (next - samples[index]) * position.fract()
+    }).collect()
+}
+
+/// Recursive descent parser of derivation expressions with the usual operator precedence
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>
+}
+
+impl<'a> Parser<'a> {
+    fn new(expression: &'a str) -> Self {
+        Self { chars: expression.chars().peekable() }
+    }
+
+    fn parse(mut self) -> Result<Expression, Box<dyn Error>> {
+        let expression = self.sum()?;
+        match self.peek() {
+            None => Ok(expression),
+            Some(c) => Err(format!("Unexpected '{}'", c).into())
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Box<dyn Error>> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            Some(c) => Err(format!("Expected '{}' instead of '{}'", expected, c).into()),
+            None => Err(format!("Expected '{}'", expected).into())
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut expression = self.product()?;
+        while let Some(operator @ ('+' | '-')) = self.peek() {
+            self.chars.next();
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(self.product()?));
+        }
+        Ok(expression)
+    }
+
+    fn product(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut expression = self.unary()?;
+        while let Some(operator @ ('*' | '/')) = self.peek() {
+            self.chars.next();
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(self.unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(&mut self) -> Result<Expression, Box<dyn Error>> {
+        match self.peek() {
+            Some('-') => {
+                self.chars.next();
+                Ok(Expression::Negate(Box::new(self.unary()?)))
+            }
+            Some('(') => {
+                self.chars.next();
+                let expression = self.sum()?;
+                self.expect(')')?;
+                Ok(expression)
+            }
+            Some('[') => {
+                self.chars.next();
+                let mut reference = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some(']') => break,
+                        Some(c) => reference.push(c),
+                        None => return Err("Expected ']'".into())
+                    }
+                }
+                let (source, name) = split_reference(&reference);
+                if name.is_empty() {
+                    return Err("Empty channel reference".into());
+                }
+                Ok(Expression::Channel { name: name.to_string(), source, resolved: None })
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                Ok(Expression::Number(number.parse().map_err(|_| format!("Invalid number '{}'", number))?))
+            }
+            Some(c) if c.is_alphabetic() => {
+                let mut function = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric()) {
+                    function.push(c);
+                }
+                if !matches!(function.to_lowercase().as_str(), "avg" | "mean") {
+                    return Err(format!("Unknown function '{}', channels have to be put in square brackets", function).into());
+                }
+
+                self.expect('(')?;
+                let mut items = vec![self.sum()?];
+                while self.peek() == Some(',') {
+                    self.chars.next();
+                    items.push(self.sum()?);
+                }
+                self.expect(')')?;
+                Ok(Expression::Average(items))
+            }
+            Some(c) => Err(format!("Unexpected '{}'", c).into()),
+            None => Err("Unexpected end of the expression".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels(labels: &[&str]) -> Vec<ChannelSettings> {
+        labels.iter().map(|label| ChannelSettings {
+            label: label.to_string(),
+            name: None,
+            channel_type: ChannelType::Eeg,
+            visible: true
+        }).collect()
+    }
+
+    /// Evaluates the expression on two sources, the first with C4 and M1 and the second with a C4 of half the sample rate
+    fn evaluate(expression: &str) -> Result<Vec<f64>, Box<dyn Error>> {
+        let sources = [channels(&["C4", "M1"]), channels(&["C4"])];
+        let sources = sources.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let derived = DerivedChannel { name: "Derived".to_string(), expression: expression.to_string(), channel_type: ChannelType::Eeg };
+
+        let samples = |channel: ChannelRef| match (channel.source, channel.channel) {
+            (0, 0) => Some(vec![10.0, 20.0, 30.0, 40.0]),
+            (0, 1) => Some(vec![1.0, 2.0, 3.0, 4.0]),
+            (1, 0) => Some(vec![0.0, 10.0]),
+            _ => None
+        };
+        Ok(derived.resolve(&sources)?.evaluate(4, &samples))
+    }
+
+    fn error(expression: &str) -> String {
+        evaluate(expression).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn evaluates_with_operator_precedence() {
+        assert_eq!(evaluate("[C4] - [M1] * 2").unwrap(), vec![8.0, 16.0, 24.0, 32.0]);
+        assert_eq!(evaluate("([C4] - [M1]) * 2").unwrap(), vec![18.0, 36.0, 54.0, 72.0]);
+        assert_eq!(evaluate("[C4] - [M1] - 1").unwrap(), vec![8.0, 17.0, 26.0, 35.0]);
+        assert_eq!(evaluate("-[C4] / 4 + 1").unwrap(), vec![-1.5, -4.0, -6.5, -9.0]);
+        assert_eq!(evaluate("[C4] / [M1]").unwrap(), vec![10.0, 10.0, 10.0, 10.0]);
+        assert_eq!(evaluate(" [ C4 ]*0.5 ").unwrap(), vec![5.0, 10.0, 15.0, 20.0]);
+    }
+
+    #[test]
+    fn evaluates_averages() {
+        assert_eq!(evaluate("avg([C4], [M1])").unwrap(), vec![5.5, 11.0, 16.5, 22.0]);
+        assert_eq!(evaluate("MEAN([C4], [M1], 3) * 3").unwrap(), vec![14.0, 25.0, 36.0, 47.0]);
+        assert_eq!(evaluate("[C4] - mean([M1])").unwrap(), vec![9.0, 18.0, 27.0, 36.0]);
+    }
+
+    #[test]
+    fn resamples_channels_of_different_sample_rates() {
+        assert_eq!(evaluate("[2:C4]").unwrap(), vec![0.0, 5.0, 10.0, 10.0]);
+        assert_eq!(evaluate("[1:C4] - [2:C4]").unwrap(), vec![10.0, 15.0, 20.0, 30.0]);
+
+        assert_eq!(resample(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], 3), vec![0.0, 2.0, 4.0]);
+        assert_eq!(resample(&[0.0, 2.0], 4), vec![0.0, 1.0, 2.0, 2.0]);
+        assert_eq!(resample(&[], 4), Vec::<f64>::new());
+    }
+
+    #[test]
+    fn reports_invalid_expressions() {
+        assert_eq!(error("sum([C4])"), "Unknown function 'sum', channels have to be put in square brackets");
+        assert_eq!(error("[2:M1]"), "Channel [M1] does not exist");
+        assert_eq!(error("[3:C4]"), "Channel [C4] does not exist");
+        assert_eq!(error("[Fpz] - [C4]"), "Channel [Fpz] does not exist");
+        assert_eq!(error("[C4] -"), "Unexpected end of the expression");
+        assert_eq!(error("([C4] - [M1]"), "Expected ')'");
+        assert_eq!(error("avg([C4] [M1])"), "Expected ')' instead of '['");
+        assert_eq!(error("[C4"), "Expected ']'");
+        assert_eq!(error("[2:]"), "Empty channel reference");
+        assert_eq!(error("[C4] [M1]"), "Unexpected '['");
+    }
+
+    #[test]
+    fn remaps_references_of_removed_source() {
+        let expression = "([1:C3] + [3:C4]) / 2 - [2:M1] + [Fpz]";
+        assert_eq!(remove_source_references(expression, 1), "([1:C3] + [2:C4]) / 2 - [removed 2:M1] + [Fpz]");
+        assert_eq!(remove_source_reference("3:Flow", 0), "2:Flow");
+        assert_eq!(remove_source_reference("1:Flow", 0), "removed 1:Flow");
+        assert_eq!(remove_source_reference("Flow", 0), "Flow");
+    }
+}
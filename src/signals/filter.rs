@@ -0,0 +1,72 @@
+use std::f64::consts::{PI, SQRT_2};
+
+/// Second order IIR filter section (Butterworth response)
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2]
+}
+
+impl Biquad {
+    fn low_pass(cutoff: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::coefficients(cutoff, sample_rate);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b: [(1.0 - cos) / 2.0 / a0, (1.0 - cos) / a0, (1.0 - cos) / 2.0 / a0],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0]
+        }
+    }
+
+    fn high_pass(cutoff: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::coefficients(cutoff, sample_rate);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b: [(1.0 + cos) / 2.0 / a0, -(1.0 + cos) / a0, (1.0 + cos) / 2.0 / a0],
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0]
+        }
+    }
+
+    fn coefficients(cutoff: f64, sample_rate: f64) -> (f64, f64) {
+        let omega = 2.0 * PI * cutoff / sample_rate;
+        (omega.cos(), omega.sin() / SQRT_2)
+    }
+
+    /// Filters the samples in place (transposed direct form II), starting from the first sample to avoid a step response
+    fn apply<'a>(&self, samples: impl Iterator<Item = &'a mut f64>) {
+        let mut samples = samples.peekable();
+        let Some(first) = samples.peek().map(|s| **s) else {
+            return;
+        };
+
+        // Start in the steady state of the first sample
+        let gain = (self.b[0] + self.b[1] + self.b[2]) / (1.0 + self.a[0] + self.a[1]);
+        let mut z1 = (gain - self.b[0]) * first;
+        let mut z2 = (self.b[2] - self.a[1] * gain) * first;
+
+        for sample in samples {
+            let x = *sample;
+            let y = self.b[0] * x + z1;
+            z1 = self.b[1] * x - self.a[0] * y + z2;
+            z2 = self.b[2] * x - self.a[1] * y;
+            *sample = y;
+        }
+    }
+}
+
+/// Applies a zero-phase band-pass filter (forward and backward) to the samples. Cutoff frequencies
+/// above the Nyquist frequency are ignored. Gaps (NaN values) split the samples into separately filtered parts.
+pub fn band_pass(samples: &mut [f64], sample_rate: f64, high_pass: Option<f64>, low_pass: Option<f64>) {
+    let nyquist = sample_rate / 2.0;
+    let sections = [
+        high_pass.filter(|f| *f > 0.0 && *f < nyquist).map(|f| Biquad::high_pass(f, sample_rate)),
+        low_pass.filter(|f| *f > 0.0 && *f < nyquist).map(|f| Biquad::low_pass(f, sample_rate))
+    ];
+
+    for part in samples.split_mut(|s| !s.is_finite()) {
+        for section in sections.iter().flatten() {
+            section.apply(part.iter_mut());
+            section.apply(part.iter_mut().rev());
+        }
+    }
+}
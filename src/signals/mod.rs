@@ -0,0 +1,6 @@
+pub mod alias;
+pub mod channel;
+pub mod derived;
+pub mod filter;
+pub mod montage;
+pub mod scaling;
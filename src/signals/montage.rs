@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::signals::alias;
+use crate::signals::channel::{ChannelSettings, SignalDisplay};
+use crate::signals::derived::{self, ChannelRef, DerivedChannel};
+
+// Extension of montage preset files
+pub const PRESET_EXTENSION: &str = "ngm";
+
+/// Named and ordered selection of channels with their own gain, filter and color, e.g. a page only
+/// showing the EEG derivations with a higher gain. Montages are stored within the project and can be
+/// shared with other projects as preset files.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Montage {
+    pub name: String,
+    #[serde(default)]
+    pub channels: Vec<MontageChannel>
+}
+
+impl Montage {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            channels: Vec::new()
+        }
+    }
+
+    /// Reads a montage from a preset file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes the montage to a preset file
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MontageChannel {
+    // Channel of a source or derived channel referenced by its label or name, optionally prefixed
+    // with the number of the source (e.g. "2:C4") like within the expressions of derived channels
+    pub channel: String,
+
+    // Amplitude in µV shown above and below the baseline, replaces the default of the channel type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gain: Option<f64>,
+
+    // Cutoff frequencies in Hz, replacing the default filter of the channel type (0 disables the filter)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_pass: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_pass: Option<f64>,
+
+    // Hex code of the color, e.g. "#4275DB"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>
+}
+
+/// Channel of a montage found within the project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolvedChannel {
+    Source(ChannelRef),
+    Derived(usize)
+}
+
+impl MontageChannel {
+    pub fn new(channel: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            gain: None,
+            high_pass: None,
+            low_pass: None,
+            color: None
+        }
+    }
+
+    /// Finds the referenced channel within the channels of the sources. Derived channels are only
+    /// used if no channel of the sources matches, as they cannot be referenced by their source.
+    pub fn resolve(&self, sources: &[&[ChannelSettings]], derived: &[DerivedChannel]) -> Option<ResolvedChannel> {
+        let (source, name) = derived::split_reference(&self.channel);
+        if name.is_empty() {
+            return None;
+        }
+
+        derived::find_channel(sources, source, name)
+            .map(ResolvedChannel::Source)
+            .or_else(|| derived.iter()
+                .position(|d| source.is_none() && alias::is_same_channel(name, &d.name))
+                .map(ResolvedChannel::Derived))
+    }
+
+    /// Replaces the default display of the channel with the settings of the montage
+    pub fn apply(&self, mut display: SignalDisplay) -> SignalDisplay {
+        if self.gain.is_some() {
+            display.amplitude = self.gain;
+        }
+        if self.high_pass.is_some() {
+            display.high_pass = self.high_pass;
+        }
+        if self.low_pass.is_some() {
+            display.low_pass = self.low_pass;
+        }
+        if let Some(color) = self.color.as_deref().and_then(parse_color) {
+            display.color = color;
+        }
+
+        display
+    }
+}
+
+/// Parses a color given as hex code (e.g. "#4275DB" or "4275DB")
+pub fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
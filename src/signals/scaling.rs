@@ -0,0 +1,239 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+// Height of a single division of the grid in pixels, the gain of a channel is given in µV per division
+pub const DIVISION_HEIGHT: f32 = 25.0;
+
+// Heights of the rows of the channels in pixels (without padding) which can be selected
+pub const ROW_HEIGHTS: [f32; 7] = [50.0, 75.0, 100.0, 150.0, 200.0, 250.0, 300.0];
+pub const DEFAULT_ROW_HEIGHT: f32 = 100.0;
+
+// Gains in µV per division which can be selected, in increasing order
+pub const GAINS: [f64; 19] = [1.0, 2.0, 3.0, 5.0, 7.5, 10.0, 15.0, 20.0, 25.0, 37.5, 50.0, 75.0, 100.0, 150.0, 200.0, 300.0, 500.0, 750.0, 1000.0];
+
+// Share of the samples of the current epoch within the automatically scaled range
+const AUTO_PERCENTILE: f64 = 0.98;
+
+/// How the amplitude of a channel is mapped onto the height of its row
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum AmplitudeScale {
+    // Amplitude of the channel type or the active montage
+    #[default]
+    Default,
+
+    // Fixed gain in µV per division, only used for voltage channels
+    Gain(f64),
+
+    // Fitted to the samples of the current epoch
+    Auto,
+
+    // Entire physical range of the channel as given in the EDF file
+    Physical
+}
+
+impl AmplitudeScale {
+    /// Gets all scales which can be selected
+    pub fn options() -> Vec<Self> {
+        [Self::Default, Self::Auto, Self::Physical].into_iter()
+            .chain(GAINS.iter().map(|g| Self::Gain(*g)))
+            .collect()
+    }
+
+    /// Gets the next scale when cycling through the modes (default, auto, physical range)
+    pub fn next_mode(self) -> Self {
+        match self {
+            Self::Default | Self::Gain(_) => Self::Auto,
+            Self::Auto => Self::Physical,
+            Self::Physical => Self::Default
+        }
+    }
+}
+
+impl Display for AmplitudeScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "Default"),
+            Self::Gain(gain) => write!(f, "{} µV/div", gain),
+            Self::Auto => write!(f, "Auto"),
+            Self::Physical => write!(f, "Physical range")
+        }
+    }
+}
+
+/// Gets the gain which is the given amount of steps more sensitive than the given gain in µV per division.
+/// Positive steps enlarge the signal (i.e. lower the gain), negative steps shrink it.
+pub fn step_gain(gain: f64, steps: i32) -> f64 {
+    let current = GAINS.iter().position(|g| *g >= gain - 1e-9).unwrap_or(GAINS.len());
+
+    // Gains between two steps count as the next higher step, so shrinking the signal has to skip it
+    let is_between = GAINS.get(current).is_none_or(|g| *g > gain + 1e-9);
+    let current = if steps < 0 && is_between { current as i32 - 1 } else { current as i32 };
+    GAINS[(current - steps).clamp(0, GAINS.len() as i32 - 1) as usize]
+}
+
+/// Gets the range of the given samples around their median, which contains the most samples except for outliers
+pub fn percentile_range(samples: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    let mut samples = samples.filter(|s| s.is_finite()).collect::<Vec<_>>();
+    if samples.is_empty() {
+        return None;
+    }
+
+    samples.sort_by(f64::total_cmp);
+    let median = samples[samples.len() / 2];
+
+    let mut deviations = samples.into_iter().map(|s| (s - median).abs()).collect::<Vec<_>>();
+    deviations.sort_by(f64::total_cmp);
+    let deviation = deviations[((deviations.len() - 1) as f64 * AUTO_PERCENTILE).round() as usize];
+    if deviation <= 0.0 {
+        return None;
+    }
+
+    Some((median - deviation, median + deviation))
+}
+
+/// Channel of a source identified by its label or a derived channel identified by its name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ChannelKey {
+    Source { source: usize, label: String },
+    Derived { name: String }
+}
+
+/// Scaling, polarity and height of a channel within the scorer, stored within the session
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChannelView {
+    pub channel: ChannelKey,
+    #[serde(default)]
+    pub scale: AmplitudeScale,
+    #[serde(default)]
+    pub inverted: bool,
+    #[serde(default = "default_row_height")]
+    pub height: f32
+}
+
+fn default_row_height() -> f32 {
+    DEFAULT_ROW_HEIGHT
+}
+
+impl ChannelView {
+    pub fn new(channel: ChannelKey) -> Self {
+        Self {
+            channel,
+            scale: AmplitudeScale::Default,
+            inverted: false,
+            height: DEFAULT_ROW_HEIGHT
+        }
+    }
+
+    /// Whether the view does not change anything and therefore does not have to be stored
+    pub fn is_default(&self) -> bool {
+        self.scale == AmplitudeScale::Default && !self.inverted && self.height == DEFAULT_ROW_HEIGHT
+    }
+
+    /// Changes the height of the row by the given amount of steps
+    pub fn step_height(&mut self, steps: i32) {
+        let current = ROW_HEIGHTS.iter().position(|h| *h >= self.height).unwrap_or(ROW_HEIGHTS.len() - 1) as i32;
+        self.height = ROW_HEIGHTS[(current + steps).clamp(0, ROW_HEIGHTS.len() as i32 - 1) as usize];
+    }
+}
+
+/// Change of the view of a channel, applied to the focused channel or all channels of its type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChannelViewChange {
+    Gain(i32),      // Steps of the gain, positive steps enlarge the signal
+    Scale(AmplitudeScale),
+    CycleScale,
+    Invert,
+    Height(i32)     // Steps of the row height
+}
+
+impl ChannelViewChange {
+    /// Applies the change to the view of a channel which is currently displayed with the given gain in µV per division.
+    /// Gains can only be changed for voltage channels.
+    pub fn apply(&self, view: &mut ChannelView, gain: Option<f64>) {
+        match self {
+            Self::Gain(steps) => if let Some(gain) = gain {
+                view.scale = AmplitudeScale::Gain(step_gain(gain, *steps));
+            },
+            Self::Scale(scale) => view.scale = *scale,
+            Self::CycleScale => view.scale = view.scale.next_mode(),
+            Self::Invert => view.inverted = !view.inverted,
+            Self::Height(steps) => view.step_height(*steps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view() -> ChannelView {
+        ChannelView::new(ChannelKey::Derived { name: "C3-A2".to_string() })
+    }
+
+    #[test]
+    fn steps_gains_within_bounds() {
+        assert_eq!(step_gain(10.0, 1), 7.5);
+        assert_eq!(step_gain(10.0, -2), 20.0);
+
+        // The most and least sensitive gains are kept when stepping beyond them
+        assert_eq!(step_gain(1.0, 1), 1.0);
+        assert_eq!(step_gain(2.0, 5), 1.0);
+        assert_eq!(step_gain(1000.0, -1), 1000.0);
+        assert_eq!(step_gain(750.0, -5), 1000.0);
+        assert_eq!(step_gain(0.5, -1), 1.0);
+        assert_eq!(step_gain(5000.0, 1), 1000.0);
+        assert_eq!(step_gain(5000.0, -1), 1000.0);
+
+        // Gains between two steps move to the next step in the direction of the change
+        assert_eq!(step_gain(12.0, 1), 10.0);
+        assert_eq!(step_gain(12.0, -1), 15.0);
+    }
+
+    #[test]
+    fn scales_automatically_around_the_median() {
+        let samples = (0..=100).map(|i| i as f64 - 50.0);
+        assert_eq!(percentile_range(samples), Some((-49.0, 49.0)));
+
+        // Flat signals and signals without any finite samples have no range
+        assert_eq!(percentile_range(std::iter::repeat_n(12.5, 100)), None);
+        assert_eq!(percentile_range(std::iter::empty()), None);
+        assert_eq!(percentile_range([f64::NAN, f64::INFINITY].into_iter()), None);
+        assert_eq!(percentile_range([f64::NAN, 3.0, 3.0, f64::NAN].into_iter()), None);
+
+        // Samples which are not finite are ignored
+        let samples = [f64::NAN, -10.0, 0.0, f64::NEG_INFINITY, 10.0, f64::NAN];
+        assert_eq!(percentile_range(samples.into_iter()), Some((-10.0, 10.0)));
+    }
+
+    #[test]
+    fn applies_view_changes() {
+        let mut view = view();
+        ChannelViewChange::Gain(1).apply(&mut view, None);
+        assert_eq!(view.scale, AmplitudeScale::Default);
+        ChannelViewChange::Gain(1).apply(&mut view, Some(50.0));
+        assert_eq!(view.scale, AmplitudeScale::Gain(37.5));
+
+        ChannelViewChange::CycleScale.apply(&mut view, None);
+        assert_eq!(view.scale, AmplitudeScale::Auto);
+        ChannelViewChange::CycleScale.apply(&mut view, None);
+        assert_eq!(view.scale, AmplitudeScale::Physical);
+        ChannelViewChange::CycleScale.apply(&mut view, None);
+        assert_eq!(view.scale, AmplitudeScale::Default);
+
+        ChannelViewChange::Invert.apply(&mut view, None);
+        assert!(view.inverted);
+        ChannelViewChange::Height(2).apply(&mut view, None);
+        assert_eq!(view.height, 200.0);
+        ChannelViewChange::Height(10).apply(&mut view, None);
+        assert_eq!(view.height, 300.0);
+        ChannelViewChange::Height(-10).apply(&mut view, None);
+        assert_eq!(view.height, 50.0);
+        assert!(!view.is_default());
+
+        ChannelViewChange::Invert.apply(&mut view, None);
+        ChannelViewChange::Scale(AmplitudeScale::Default).apply(&mut view, None);
+        ChannelViewChange::Height(2).apply(&mut view, None);
+        assert!(view.is_default());
+    }
+}
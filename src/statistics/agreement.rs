@@ -0,0 +1,229 @@
+use crate::Scorings;
+use crate::scoring::stages::{Stage, StageVocabulary};
+
+/// Inter-rater agreement between two scorer sets of the same recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agreement {
+    // Stages of the rows and columns of the confusion matrix
+    pub stages: Vec<Stage>,
+
+    // Amount of epochs per stage of the first (rows) and the second scorer (columns)
+    pub confusion: Vec<Vec<u32>>,
+
+    // Amount of epochs which are scored by both scorers
+    pub compared: u32,
+
+    // Percentage of equally scored epochs in the range 0 to 100
+    pub percent_agreement: f64,
+
+    // Cohen's kappa across all stages and for every stage (one stage against all others)
+    pub kappa: Option<f64>,
+    pub stage_kappa: Vec<Option<f64>>,
+
+    // Epochs which are scored differently or only scored by one of the scorers
+    pub disagreements: Vec<u64>
+}
+
+impl Agreement {
+    /// Compares the scores of the given scorer sets for all epochs up to the epoch count.
+    /// Only epochs scored by both scorers are considered for the confusion matrix and kappa.
+    pub fn compute(first: &Scorings, second: &Scorings, vocabulary: &StageVocabulary, epoch_count: u64) -> Self {
+        // Use the stages of the vocabulary followed by unknown stages found in either scorer set
+        let mut stages = vocabulary.stages.iter().map(|s| s.stage()).collect::<Vec<_>>();
+        for stage in first.values.values().chain(second.values.values()) {
+            if !stages.contains(stage) {
+                stages.push(stage.clone());
+            }
+        }
+
+        let mut confusion = vec![vec![0; stages.len()]; stages.len()];
+        let mut disagreements = Vec::new();
+        for epoch in 0..epoch_count {
+            let (a, b) = (first.values.get(&epoch), second.values.get(&epoch));
+            if a != b {
+                disagreements.push(epoch);
+            }
+
+            let (Some(a), Some(b)) = (a, b) else {
+                continue;
+            };
+            let row = stages.iter().position(|s| s == a).unwrap();
+            let column = stages.iter().position(|s| s == b).unwrap();
+            confusion[row][column] += 1;
+        }
+
+        let compared = confusion.iter().flatten().sum::<u32>();
+        let agreeing = (0..stages.len()).map(|i| confusion[i][i]).sum::<u32>();
+        let stage_kappa = (0..stages.len()).map(|i| stage_kappa(&confusion, i, compared)).collect();
+
+        Self {
+            percent_agreement: if compared > 0 { 100.0 * agreeing as f64 / compared as f64 } else { 0.0 },
+            kappa: kappa(&confusion, compared),
+            stage_kappa,
+            stages,
+            confusion,
+            compared,
+            disagreements
+        }
+    }
+}
+
+fn kappa(confusion: &[Vec<u32>], total: u32) -> Option<f64> {
+    if total == 0 {
+        return None;
+    }
+
+    let total = total as f64;
+    let observed = (0..confusion.len()).map(|i| confusion[i][i]).sum::<u32>() as f64 / total;
+    let expected = (0..confusion.len()).map(|i| {
+        let row = confusion[i].iter().sum::<u32>() as f64;
+        let column = confusion.iter().map(|r| r[i]).sum::<u32>() as f64;
+        row * column
+    }).sum::<f64>() / (total * total);
+
+    cohens_kappa(observed, expected)
+}
+
+/// Cohen's kappa of a single stage, treating all other stages as one category
+fn stage_kappa(confusion: &[Vec<u32>], index: usize, total: u32) -> Option<f64> {
+    if total == 0 {
+        return None;
+    }
+
+    let both = confusion[index][index];
+    let first = confusion[index].iter().sum::<u32>();
+    let second = confusion.iter().map(|r| r[index]).sum::<u32>();
+    if first == 0 && second == 0 {
+        return None;
+    }
+    let neither = total + both - first - second;
+
+    let total = total as f64;
+    let observed = (both + neither) as f64 / total;
+    let expected = (first as f64 * second as f64 + (total - first as f64) * (total - second as f64)) / (total * total);
+
+    cohens_kappa(observed, expected)
+}
+
+fn cohens_kappa(observed: f64, expected: f64) -> Option<f64> {
+    if expected >= 1.0 {
+        // Both scorers only used a single category, kappa is only defined for perfect agreement
+        return if observed >= 1.0 { Some(1.0) } else { None };
+    }
+
+    Some((observed - expected) / (1.0 - expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates scorings from the given stage ids, where "-" leaves the epoch unscored
+    fn scorings(stages: &[&str]) -> Scorings {
+        let mut scorings = Scorings::new(30);
+        for (epoch, stage) in stages.iter().enumerate() {
+            if *stage != "-" {
+                scorings.values.insert(epoch as u64, Stage::new(stage));
+            }
+        }
+        scorings
+    }
+
+    /// Creates the scorings of both scorers from the amount of epochs of every pair of stages
+    fn scoring_pairs(pairs: &[(&str, &str, usize)]) -> (Scorings, Scorings) {
+        let epochs = pairs.iter().flat_map(|(a, b, count)| std::iter::repeat_n((*a, *b), *count)).collect::<Vec<_>>();
+        let first = epochs.iter().map(|(a, _)| *a).collect::<Vec<_>>();
+        let second = epochs.iter().map(|(_, b)| *b).collect::<Vec<_>>();
+        (scorings(&first), scorings(&second))
+    }
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        assert!(value.is_some_and(|v| (v - expected).abs() < 1e-9), "{:?} != {}", value, expected);
+    }
+
+    #[test]
+    fn computes_kappa_of_two_categories() {
+        let first = scorings(&["W", "W", "W", "W", "W", "N2", "N2", "N2", "N2", "N2"]);
+        let second = scorings(&["W", "W", "W", "W", "N2", "N2", "N2", "N2", "N2", "W"]);
+        let agreement = Agreement::compute(&first, &second, &StageVocabulary::aasm(), 10);
+
+        // Observed agreement 0.8, expected agreement (5 * 5 + 5 * 5) / 100 = 0.5
+        assert_eq!(agreement.compared, 10);
+        assert_eq!(agreement.percent_agreement, 80.0);
+        assert_close(agreement.kappa, 0.6);
+        assert_eq!(agreement.confusion[0], vec![4, 0, 1, 0, 0]);
+        assert_eq!(agreement.confusion[2], vec![1, 0, 4, 0, 0]);
+        assert_eq!(agreement.disagreements, vec![4, 9]);
+
+        // With only two categories every stage against all others is the same as the overall kappa
+        assert_close(agreement.stage_kappa[0], 0.6);
+        assert_close(agreement.stage_kappa[2], 0.6);
+        assert_eq!(agreement.stage_kappa[1], None);
+    }
+
+    #[test]
+    fn computes_kappa_of_three_categories() {
+        let (first, second) = scoring_pairs(&[
+            ("W", "W", 5), ("W", "N2", 1),
+            ("N2", "W", 1), ("N2", "N2", 7), ("N2", "R", 1),
+            ("R", "N2", 1), ("R", "R", 4)
+        ]);
+        let agreement = Agreement::compute(&first, &second, &StageVocabulary::aasm(), 20);
+
+        // Observed agreement 16 / 20, expected agreement (6 * 6 + 9 * 9 + 5 * 5) / 400
+        assert_eq!(agreement.compared, 20);
+        assert_eq!(agreement.percent_agreement, 80.0);
+        assert_close(agreement.kappa, (0.8 - 0.355) / (1.0 - 0.355));
+
+        // REM against all others: 4 epochs scored as REM by both, 14 by neither
+        let expected = (5.0 * 5.0 + 15.0 * 15.0) / 400.0;
+        assert_close(agreement.stage_kappa[4], (18.0 / 20.0 - expected) / (1.0 - expected));
+    }
+
+    #[test]
+    fn perfect_agreement_on_a_single_category() {
+        let first = scorings(&["N2", "N2", "N2", "N2"]);
+        let agreement = Agreement::compute(&first, &first, &StageVocabulary::aasm(), 4);
+
+        assert_eq!(agreement.percent_agreement, 100.0);
+        assert_eq!(agreement.kappa, Some(1.0));
+        assert_eq!(agreement.stage_kappa, vec![None, None, Some(1.0), None, None]);
+        assert!(agreement.disagreements.is_empty());
+
+        // Kappa is undefined if a single category is expected but the scorers do not agree
+        assert_eq!(cohens_kappa(1.0, 1.0), Some(1.0));
+        assert_eq!(cohens_kappa(0.5, 1.0), None);
+    }
+
+    #[test]
+    fn counts_epochs_scored_by_one_scorer_as_disagreements() {
+        let first = scorings(&["W", "N2", "-", "-", "-"]);
+        let second = scorings(&["-", "-", "N2", "R", "-"]);
+        let agreement = Agreement::compute(&first, &second, &StageVocabulary::aasm(), 5);
+
+        assert_eq!(agreement.compared, 0);
+        assert_eq!(agreement.percent_agreement, 0.0);
+        assert_eq!(agreement.kappa, None);
+        assert!(agreement.stage_kappa.iter().all(Option::is_none));
+        assert_eq!(agreement.disagreements, vec![0, 1, 2, 3]);
+
+        // Only epochs scored by both scorers are compared
+        let agreement = Agreement::compute(&scorings(&["W", "N2", "-"]), &scorings(&["W", "-", "R"]), &StageVocabulary::aasm(), 3);
+        assert_eq!(agreement.compared, 1);
+        assert_eq!(agreement.disagreements, vec![1, 2]);
+    }
+
+    #[test]
+    fn appends_unknown_stages_to_the_vocabulary() {
+        let first = scorings(&["W", "S4", "N3"]);
+        let second = scorings(&["W", "N3", "MT"]);
+        let agreement = Agreement::compute(&first, &second, &StageVocabulary::aasm(), 3);
+
+        assert_eq!(agreement.stages.len(), 7);
+        assert_eq!(agreement.stages[5..], [Stage::new("S4"), Stage::new("MT")]);
+        assert_eq!(agreement.confusion[5][3], 1);
+        assert_eq!(agreement.confusion[3][6], 1);
+        assert_eq!(agreement.compared, 3);
+        assert_eq!(agreement.disagreements, vec![1, 2]);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod sleep_stats;
+pub mod agreement;
\ No newline at end of file
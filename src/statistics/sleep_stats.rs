@@ -0,0 +1,277 @@
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::Scorings;
+use crate::scoring::stages::{Stage, StageCategory, StageVocabulary};
+
+/// The period of the recording which is considered for the statistics.
+/// The range contains epoch indices with an exclusive end (lights-off until lights-on).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisPeriod {
+    pub epochs: Range<u64>
+}
+
+impl AnalysisPeriod {
+    pub fn new(lights_off: u64, lights_on: u64) -> Self {
+        Self {
+            epochs: lights_off..lights_on.max(lights_off)
+        }
+    }
+
+    /// Gets the period in milliseconds relative to the first epoch for epochs of the given duration in seconds
+    pub fn millis(&self, epoch_duration: u64) -> Range<u64> {
+        let epoch_millis = epoch_duration * 1_000;
+        self.epochs.start * epoch_millis..self.epochs.end * epoch_millis
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StageStatistics {
+    pub stage: Stage,
+    pub minutes: f64,
+
+    // Percentage of the total sleep time. For non-sleep stages, this is the percentage of the time in bed
+    pub percent: f64
+}
+
+/// Summary metrics of a hypnogram as defined by the AASM scoring manual.
+/// All durations are in minutes and all percentages are in the range 0 to 100.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SleepStatistics {
+    pub time_in_bed: f64,
+    pub total_sleep_time: f64,
+    pub sleep_period_time: f64,
+    pub sleep_onset_latency: Option<f64>,
+    pub wake_after_sleep_onset: f64,
+    pub sleep_efficiency: f64,
+    pub rem_latency: Option<f64>,
+    pub unscored: f64,
+    pub awakenings: u32,
+    pub stages: Vec<StageStatistics>
+}
+
+impl SleepStatistics {
+    /// Calculates the statistics of the given scorings within the analysis period. The stage
+    /// categories of the vocabulary determine which stages are considered as wake, sleep and REM.
+    pub fn compute(scorings: &Scorings, vocabulary: &StageVocabulary, period: &AnalysisPeriod) -> Self {
+        let epoch_minutes = scorings.epoch_duration as f64 / 60.0;
+        let category_at = |epoch: u64| scorings.values.get(&epoch).and_then(|stage| vocabulary.category(stage));
+        let is_sleep_at = |epoch: u64| category_at(epoch).is_some_and(StageCategory::is_sleep);
+        let is_wake_at = |epoch: u64| category_at(epoch) == Some(&StageCategory::Wake);
+
+        // Sleep onset is the first epoch of any sleep stage, the sleep period ends with the last epoch of sleep
+        let sleep_epochs = period.epochs.clone().filter(|e| is_sleep_at(*e));
+        let sleep_onset = sleep_epochs.clone().next();
+        let sleep_end = sleep_epochs.clone().next_back();
+        let rem_onset = period.epochs.clone().find(|e| category_at(*e) == Some(&StageCategory::Rem));
+
+        let count_stage = |stage: &Stage| period.epochs.clone().filter(|e| scorings.values.get(e) == Some(stage)).count() as f64;
+
+        let time_in_bed = (period.epochs.end - period.epochs.start) as f64 * epoch_minutes;
+        let unscored = period.epochs.clone().filter(|e| category_at(*e).is_none()).count() as f64 * epoch_minutes;
+
+        let (sleep_period_time, wake_after_sleep_onset, awakenings) = match (sleep_onset, sleep_end) {
+            (Some(onset), Some(end)) => {
+                let sleep_period = onset..end + 1;
+                let waso = sleep_period.clone().filter(|e| is_wake_at(*e)).count() as f64 * epoch_minutes;
+
+                // Every transition from a sleep stage into wake within the sleep period is an awakening
+                let awakenings = sleep_period.clone()
+                    .zip(sleep_period.clone().skip(1))
+                    .filter(|(prev, next)| is_sleep_at(*prev) && is_wake_at(*next))
+                    .count() as u32;
+
+                ((end + 1 - onset) as f64 * epoch_minutes, waso, awakenings)
+            }
+            _ => (0.0, 0.0, 0)
+        };
+
+        let stage_minutes = vocabulary.stages.iter()
+            .map(|definition| (definition, count_stage(&definition.stage()) * epoch_minutes))
+            .collect::<Vec<_>>();
+        let total_sleep_time = stage_minutes.iter()
+            .filter(|(definition, _)| definition.category.is_sleep())
+            .map(|(_, minutes)| minutes)
+            .sum::<f64>();
+
+        let stages = stage_minutes.into_iter().map(|(definition, minutes)| {
+            let reference = if definition.category.is_sleep() { total_sleep_time } else { time_in_bed };
+            StageStatistics {
+                stage: definition.stage(),
+                minutes,
+                percent: percentage(minutes, reference)
+            }
+        }).collect();
+
+        Self {
+            time_in_bed,
+            total_sleep_time,
+            sleep_period_time,
+            sleep_onset_latency: sleep_onset.map(|onset| (onset - period.epochs.start) as f64 * epoch_minutes),
+            wake_after_sleep_onset,
+            sleep_efficiency: percentage(total_sleep_time, time_in_bed),
+            rem_latency: sleep_onset.zip(rem_onset).map(|(onset, rem)| (rem - onset) as f64 * epoch_minutes),
+            unscored,
+            awakenings,
+            stages
+        }
+    }
+}
+
+fn percentage(value: f64, total: f64) -> f64 {
+    if total > 0.0 { 100.0 * value / total } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates scorings from the given stage ids, where "-" leaves the epoch unscored
+    fn scorings(epoch_duration: u64, stages: &[&str]) -> Scorings {
+        let mut scorings = Scorings::new(epoch_duration);
+        for (epoch, stage) in stages.iter().enumerate() {
+            if *stage != "-" {
+                scorings.values.insert(epoch as u64, Stage::new(stage));
+            }
+        }
+        scorings
+    }
+
+    fn stage_statistics<'a>(statistics: &'a SleepStatistics, id: &str) -> &'a StageStatistics {
+        statistics.stages.iter().find(|s| s.stage == Stage::new(id)).unwrap()
+    }
+
+    const NIGHT: [&str; 10] = ["W", "W", "N1", "N2", "W", "N2", "N3", "R", "W", "W"];
+
+    #[test]
+    fn computes_aasm_metrics() {
+        let statistics = SleepStatistics::compute(&scorings(30, &NIGHT), &StageVocabulary::aasm(), &AnalysisPeriod::new(0, 10));
+
+        assert_eq!(statistics.time_in_bed, 5.0);
+        assert_eq!(statistics.total_sleep_time, 2.5);
+        assert_eq!(statistics.sleep_period_time, 3.0);
+        assert_eq!(statistics.sleep_onset_latency, Some(1.0));
+        assert_eq!(statistics.wake_after_sleep_onset, 0.5);
+        assert_eq!(statistics.sleep_efficiency, 50.0);
+        assert_eq!(statistics.rem_latency, Some(2.5));
+        assert_eq!(statistics.unscored, 0.0);
+        assert_eq!(statistics.awakenings, 1);
+
+        // Wake is relative to the time in bed, sleep stages are relative to the total sleep time
+        assert_eq!(stage_statistics(&statistics, "W").minutes, 2.5);
+        assert_eq!(stage_statistics(&statistics, "W").percent, 50.0);
+        assert_eq!(stage_statistics(&statistics, "N1").minutes, 0.5);
+        assert_eq!(stage_statistics(&statistics, "N1").percent, 20.0);
+        assert_eq!(stage_statistics(&statistics, "N2").minutes, 1.0);
+        assert_eq!(stage_statistics(&statistics, "N2").percent, 40.0);
+        assert_eq!(stage_statistics(&statistics, "N3").minutes, 0.5);
+        assert_eq!(stage_statistics(&statistics, "R").minutes, 0.5);
+        assert_eq!(stage_statistics(&statistics, "R").percent, 20.0);
+    }
+
+    #[test]
+    fn counts_every_awakening_within_the_sleep_period() {
+        let scorings = scorings(30, &["N2", "W", "N2", "R", "W", "W", "N1", "W"]);
+        let statistics = SleepStatistics::compute(&scorings, &StageVocabulary::aasm(), &AnalysisPeriod::new(0, 8));
+
+        assert_eq!(statistics.awakenings, 2);
+        assert_eq!(statistics.wake_after_sleep_onset, 1.5);
+        assert_eq!(statistics.sleep_period_time, 3.5);
+        assert_eq!(statistics.rem_latency, Some(1.5));
+    }
+
+    #[test]
+    fn handles_empty_hypnogram() {
+        let vocabulary = StageVocabulary::aasm();
+
+        let statistics = SleepStatistics::compute(&Scorings::new(30), &vocabulary, &AnalysisPeriod::new(0, 0));
+        assert_eq!(statistics.time_in_bed, 0.0);
+        assert_eq!(statistics.total_sleep_time, 0.0);
+        assert_eq!(statistics.sleep_efficiency, 0.0);
+        assert_eq!(statistics.sleep_onset_latency, None);
+        assert_eq!(statistics.rem_latency, None);
+        assert!(statistics.stages.iter().all(|s| s.minutes == 0.0 && s.percent == 0.0));
+
+        // Unscored epochs count towards the time in bed, but not towards sleep
+        let statistics = SleepStatistics::compute(&Scorings::new(30), &vocabulary, &AnalysisPeriod::new(0, 10));
+        assert_eq!(statistics.time_in_bed, 5.0);
+        assert_eq!(statistics.unscored, 5.0);
+        assert_eq!(statistics.total_sleep_time, 0.0);
+        assert_eq!(statistics.sleep_efficiency, 0.0);
+    }
+
+    #[test]
+    fn handles_hypnogram_without_sleep() {
+        let statistics = SleepStatistics::compute(&scorings(30, &["W", "W", "-", "W"]), &StageVocabulary::aasm(), &AnalysisPeriod::new(0, 4));
+
+        assert_eq!(statistics.time_in_bed, 2.0);
+        assert_eq!(statistics.total_sleep_time, 0.0);
+        assert_eq!(statistics.sleep_period_time, 0.0);
+        assert_eq!(statistics.sleep_onset_latency, None);
+        assert_eq!(statistics.wake_after_sleep_onset, 0.0);
+        assert_eq!(statistics.sleep_efficiency, 0.0);
+        assert_eq!(statistics.rem_latency, None);
+        assert_eq!(statistics.unscored, 0.5);
+        assert_eq!(statistics.awakenings, 0);
+        assert_eq!(stage_statistics(&statistics, "W").percent, 75.0);
+        assert_eq!(stage_statistics(&statistics, "N2").percent, 0.0);
+    }
+
+    #[test]
+    fn only_considers_epochs_within_lights_off_and_lights_on() {
+        let scorings = scorings(30, &NIGHT);
+        let vocabulary = StageVocabulary::aasm();
+
+        // Lights-off after the first sleep epoch and lights-on before the REM epoch
+        let statistics = SleepStatistics::compute(&scorings, &vocabulary, &AnalysisPeriod::new(4, 7));
+        assert_eq!(statistics.time_in_bed, 1.5);
+        assert_eq!(statistics.total_sleep_time, 1.0);
+        assert_eq!(statistics.sleep_onset_latency, Some(0.5));
+        assert_eq!(statistics.sleep_period_time, 1.0);
+        assert_eq!(statistics.wake_after_sleep_onset, 0.0);
+        assert_eq!(statistics.rem_latency, None);
+        assert_eq!(statistics.awakenings, 0);
+        assert_eq!(stage_statistics(&statistics, "N1").minutes, 0.0);
+        assert_eq!(stage_statistics(&statistics, "W").minutes, 0.5);
+
+        // Lights-on beyond the scored epochs counts the remaining epochs as unscored
+        let statistics = SleepStatistics::compute(&scorings, &vocabulary, &AnalysisPeriod::new(8, 12));
+        assert_eq!(statistics.time_in_bed, 2.0);
+        assert_eq!(statistics.unscored, 1.0);
+        assert_eq!(statistics.sleep_onset_latency, None);
+
+        // Lights-on before lights-off results in an empty period
+        assert_eq!(AnalysisPeriod::new(8, 4).epochs, 8..8);
+        let statistics = SleepStatistics::compute(&scorings, &vocabulary, &AnalysisPeriod::new(8, 4));
+        assert_eq!(statistics.time_in_bed, 0.0);
+        assert_eq!(statistics.sleep_efficiency, 0.0);
+    }
+
+    #[test]
+    fn uses_the_categories_of_the_vocabulary() {
+        let scorings = scorings(60, &["Wake", "NREM", "NREM", "Artifact", "REM", "Wake", "NREM"]);
+        let statistics = SleepStatistics::compute(&scorings, &StageVocabulary::rodent(), &AnalysisPeriod::new(0, 7));
+
+        assert_eq!(statistics.time_in_bed, 7.0);
+        assert_eq!(statistics.total_sleep_time, 4.0);
+        assert_eq!(statistics.sleep_period_time, 6.0);
+        assert_eq!(statistics.sleep_onset_latency, Some(1.0));
+        assert_eq!(statistics.wake_after_sleep_onset, 1.0);
+        assert_eq!(statistics.rem_latency, Some(3.0));
+        assert_eq!(statistics.unscored, 0.0);
+        assert_eq!(statistics.awakenings, 1);
+        assert!((statistics.sleep_efficiency - 400.0 / 7.0).abs() < 1e-9);
+
+        // Stages of other categories are neither sleep nor wake and are relative to the time in bed
+        assert_eq!(stage_statistics(&statistics, "Artifact").minutes, 1.0);
+        assert!((stage_statistics(&statistics, "Artifact").percent - 100.0 / 7.0).abs() < 1e-9);
+        assert_eq!(stage_statistics(&statistics, "NREM").percent, 75.0);
+        assert_eq!(stage_statistics(&statistics, "REM").percent, 25.0);
+
+        // Stages of other vocabularies are not counted at all
+        let statistics = SleepStatistics::compute(&scorings, &StageVocabulary::aasm(), &AnalysisPeriod::new(0, 7));
+        assert_eq!(statistics.total_sleep_time, 0.0);
+        assert_eq!(statistics.unscored, 7.0);
+    }
+}
@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use chrono::{NaiveDateTime, Timelike};
+use edf_rs::EDFSpecifications;
+use edf_rs::file::EDFFile;
+use edf_rs::headers::annotation_list::AnnotationList;
+use edf_rs::headers::signal_header::SignalHeader;
+
+// Duration of a single data-record of written annotation files in seconds
+const ANNOTATION_RECORD_DURATION: f64 = 30.0;
+
+/// A single annotation of an EDF+ annotation signal (TAL)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdfAnnotation {
+    pub onset: f64,     // seconds relative to the start of the file
+    pub duration: f64,  // seconds
+    pub text: String
+}
+
+/// Reads all annotations of all annotation signals in the given EDF+ file. The time keeping
+/// annotations are skipped. Regular EDF files will always return an empty list.
+pub fn read_annotations<P: AsRef<Path>>(path: P) -> Result<Vec<EdfAnnotation>, Box<dyn Error>> {
+    let mut file = EDFFile::open(path)?;
+    if !file.header.get_signals().iter().any(|s| s.is_annotation()) {
+        return Ok(Vec::new());
+    }
+
+    let mut annotations = Vec::new();
+    while let Some(record) = file.read_record()? {
+        for list in record.annotations.iter().flatten() {
+            annotations.extend(list.get_annotations().iter().filter(|text| !text.is_empty()).map(|text| EdfAnnotation {
+                onset: list.onset,
+                duration: list.duration,
+                text: text.clone()
+            }));
+        }
+    }
+
+    Ok(annotations)
+}
+
+/// Writes the given annotations into a new annotation-only EDF+ file. An existing file at the path will
+/// be replaced. The onsets of the annotations are relative to the start time, which may contain fractions
+/// of a second, as they are stored in the time keeping annotations. The duration is in seconds.
+pub fn write_annotations<P: AsRef<Path>>(path: P, start: NaiveDateTime, duration: f64, annotations: &[EdfAnnotation]) -> Result<(), Box<dyn Error>> {
+    if path.as_ref().exists() {
+        fs::remove_file(&path)?;
+    }
+
+    // The header only supports whole seconds, the remainder is stored as offset of the data-records
+    let start_offset = start.and_utc().timestamp_subsec_millis() as f64 / 1_000.0;
+    let start_second = start.with_nanosecond(0).unwrap_or(start);
+    let record_count = ((duration + start_offset) / ANNOTATION_RECORD_DURATION).ceil().max(1.0) as usize;
+
+    // Every annotation is stored in the data-record it starts in
+    let mut records = (0..record_count)
+        .map(|i| vec![AnnotationList::new_time_keeping(i as f64 * ANNOTATION_RECORD_DURATION + start_offset)])
+        .collect::<Vec<_>>();
+    for annotation in annotations {
+        let onset = annotation.onset + start_offset;
+        let index = ((onset / ANNOTATION_RECORD_DURATION).floor().max(0.0) as usize).min(record_count - 1);
+        records[index].push(AnnotationList::new(onset, annotation.duration, vec![annotation.text.clone()])?);
+    }
+
+    // The annotation signal has to be large enough to hold the annotations of the fullest data-record
+    let record_bytes = records.iter()
+        .map(|tals| tals.iter().map(|tal| tal.serialize().len()).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+
+    let mut file = EDFFile::new(&path)?;
+    file.header
+        .with_specification(EDFSpecifications::EDFPlus)
+        .with_is_continuous(true)
+        .with_start_date(start_second.date())
+        .with_start_time(start_second.time())
+        .with_record_duration(ANNOTATION_RECORD_DURATION);
+
+    let mut signal = SignalHeader::new_annotation(0);
+    signal.with_samples_count(record_bytes.div_ceil(2));
+    file.insert_signal(0, signal)?;
+
+    for tals in records {
+        let mut record = file.header.create_record();
+        record.annotations = vec![tals];
+        file.append_record(record)?;
+    }
+    file.save()?;
+
+    Ok(())
+}
+
+/// Searches the given annotations for the first "Lights off" and the last "Lights on" annotation
+/// and returns their onsets in seconds relative to the start of the file
+pub fn find_lights_annotations(annotations: &[EdfAnnotation]) -> (Option<f64>, Option<f64>) {
+    let lights_off = annotations.iter()
+        .filter(|a| is_any_of(&a.text, &LIGHTS_OFF_TEXTS))
+        .map(|a| a.onset)
+        .reduce(f64::min);
+    let lights_on = annotations.iter()
+        .filter(|a| is_any_of(&a.text, &LIGHTS_ON_TEXTS))
+        .map(|a| a.onset)
+        .reduce(f64::max);
+
+    (lights_off, lights_on)
+}
+
+/// Checks whether the text is a "Lights off" or "Lights on" annotation
+pub fn is_lights_annotation(text: &str) -> bool {
+    is_any_of(text, &LIGHTS_OFF_TEXTS) || is_any_of(text, &LIGHTS_ON_TEXTS)
+}
+
+// Spellings of the lights annotations with only the lowercase letters
+const LIGHTS_OFF_TEXTS: [&str; 2] = ["lightsoff", "lightoff"];
+const LIGHTS_ON_TEXTS: [&str; 2] = ["lightson", "lighton"];
+
+fn is_any_of(text: &str, values: &[&str]) -> bool {
+    let normalized = text.to_lowercase().chars().filter(|c| c.is_alphabetic()).collect::<String>();
+    values.contains(&normalized.as_str())
+}
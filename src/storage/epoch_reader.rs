@@ -1,14 +1,96 @@
 use edf_rs::record::{RelativeRecordData, SpanningRecord};
 use edf_rs::{file::EDFFile, headers::signal_header::SignalHeader};
-use std::{error::Error, iter::repeat_n, path::Path};
+use iced::Color;
+use std::{error::Error, iter::repeat_n, ops::Range, path::Path};
 
+use crate::signals::channel::{ChannelSettings, SignalDisplay, microvolt_factor};
+use crate::signals::filter;
+use crate::signals::scaling::{self, AmplitudeScale, DIVISION_HEIGHT};
+
+#[derive(Clone)]
 pub struct ChartSignal {
     pub index: usize,
-    pub label: String,
+    pub label: String,  // Label within the EDF file, which identifies the channel
+    pub name: String,   // Name shown to the user
+    pub color: Color,
     pub range: [String; 2],
     pub points: Vec<(f32, f32)>,
-    pub physical_min: f64,
+    pub physical_min: f64,  // Displayed range
     pub physical_max: f64,
+    pub dimension: String,
+    pub physical_range: (f64, f64),    // Range of the channel within the EDF file
+}
+
+impl ChartSignal {
+    /// Creates the signal from the physical samples. The samples are filtered with the filter of the display
+    /// if a sample rate is given and voltages are scaled to the amplitude of the display.
+    pub fn from_samples(index: usize, label: String, display: &SignalDisplay, dimension: &str, physical_range: (f64, f64), mut samples: Vec<f64>, filter_sample_rate: Option<f64>) -> Self {
+        if let Some(sample_rate) = filter_sample_rate {
+            filter::band_pass(&mut samples, sample_rate, display.high_pass, display.low_pass);
+        }
+
+        // Voltage channels are scaled to the amplitude of the display, all others show their entire range
+        let (min, max) = match (display.amplitude, microvolt_factor(dimension)) {
+            (Some(amplitude), Some(factor)) => (-amplitude / factor, amplitude / factor),
+            _ => physical_range
+        };
+
+        Self {
+            index,
+            label,
+            name: display.name.clone(),
+            color: display.color,
+            range: format_range(min, max, dimension),
+            points: samples.into_iter()
+                .enumerate()
+                .map(|(i, value)| (i as f32, value as f32))
+                .collect::<Vec<_>>(),
+            physical_min: min,
+            physical_max: max,
+            dimension: dimension.to_string(),
+            physical_range,
+        }
+    }
+
+    /// Replaces the displayed range with the given scale for a row of the given height. Automatic scaling
+    /// fits the range to the samples within the given range of points (i.e. the current epoch).
+    pub fn scaled(mut self, scale: AmplitudeScale, height: f32, current: Range<usize>) -> Self {
+        let range = match scale {
+            AmplitudeScale::Default => None,
+            AmplitudeScale::Gain(gain) => microvolt_factor(&self.dimension).map(|factor| {
+                let amplitude = gain * (height / DIVISION_HEIGHT) as f64 / 2.0 / factor;
+                (-amplitude, amplitude)
+            }),
+            AmplitudeScale::Auto => scaling::percentile_range(
+                self.points.get(current).unwrap_or_default().iter().map(|(_, y)| *y as f64)
+            ),
+            AmplitudeScale::Physical => Some(self.physical_range)
+        };
+
+        if let Some((min, max)) = range {
+            self.physical_min = min;
+            self.physical_max = max;
+            self.range = format_range(min, max, &self.dimension);
+        }
+
+        self
+    }
+
+    /// Gets the displayed gain in µV per division for a row of the given height, if the channel is a voltage
+    pub fn gain(&self, height: f32) -> Option<f64> {
+        let factor = microvolt_factor(&self.dimension)?;
+        Some((self.physical_max - self.physical_min) * factor / (height / DIVISION_HEIGHT) as f64)
+    }
+}
+
+fn format_range(min: f64, max: f64, dimension: &str) -> [String; 2] {
+    // Limit the precision of calculated ranges (e.g. of automatic scaling) without changing round values
+    let format = |value: f64| {
+        let rounded = (value * 100.0).round() / 100.0;
+        format!("{} {}", rounded, dimension)
+    };
+
+    [format(min), format(max)]
 }
 
 pub struct EpochReader {
@@ -17,7 +99,13 @@ pub struct EpochReader {
     offset: u64,
     position: i128,  // ms
     last_epoch_count: usize,
-    record: SpanningRecord
+    record: SpanningRecord,
+    channels: Vec<ChannelSettings>,
+    is_filtered: bool,
+
+    // Physical samples of all channels of the last read epochs and the visible channels with the channel settings applied
+    samples: Vec<Vec<f64>>,
+    chart_signals: Vec<ChartSignal>
 }
 
 impl EpochReader {
@@ -25,6 +113,10 @@ impl EpochReader {
 
     pub fn new<P: AsRef<Path>>(path: P) -> std::result::Result<Self, Box<dyn Error>> {
         let file = EDFFile::open(path)?;
+        let channels = file.header.get_signals().iter()
+            .filter(|s| !s.is_annotation())
+            .map(|s| ChannelSettings::detect(&s.label))
+            .collect();
 
         Ok(Self {
             record: SpanningRecord::new(&file.header),
@@ -33,9 +125,37 @@ impl EpochReader {
             offset: 0,
             position: 0,
             last_epoch_count: 0,
+            channels,
+            is_filtered: false,
+            samples: Vec::new(),
+            chart_signals: Vec::new()
         })
     }
 
+    /// Applies the given settings to the channels with the same label. Channels without settings keep their detected defaults.
+    pub fn set_channels(&mut self, channels: &[ChannelSettings]) {
+        for channel in &mut self.channels {
+            if let Some(settings) = channels.iter().find(|c| c.label == channel.label) {
+                *channel = settings.clone();
+            }
+        }
+        self.update_chart_signals();
+    }
+
+    pub fn get_channels(&self) -> &Vec<ChannelSettings> {
+        &self.channels
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.is_filtered
+    }
+
+    /// Sets whether the default filter of the type of each channel is applied to its samples
+    pub fn set_filtered(&mut self, is_filtered: bool) {
+        self.is_filtered = is_filtered;
+        self.update_chart_signals();
+    }
+
     pub fn set_start_align_offset(&mut self, offset: u64) {
         self.position += self.start_align_offset as i128;
         self.start_align_offset = offset;
@@ -108,29 +228,50 @@ impl EpochReader {
     }
 
     pub fn get_chart_signals(&self) -> Vec<ChartSignal> {
-        self.get_signals()
-            .iter()
-            .filter(|s| !s.is_annotation())
+        self.chart_signals.clone()
+    }
+
+    /// Gets the unfiltered physical samples of the last read epochs of the channel with the given index (without annotations)
+    pub fn get_samples(&self, channel: usize) -> Option<&Vec<f64>> {
+        self.samples.get(channel)
+    }
+
+    /// Gets the sample rate in Hz of the channel with the given index (without annotations)
+    pub fn sample_rate(&self, channel: usize) -> Option<f64> {
+        let signal = self.get_signals().iter().filter(|s| !s.is_annotation()).nth(channel)?;
+        Some(signal.samples_count as f64 / self.file.header.get_record_duration())
+    }
+
+    fn update_chart_signals(&mut self) {
+        let signals = self.file.header.get_signals().iter().filter(|s| !s.is_annotation());
+
+        self.samples = signals
+            .enumerate()
+            .map(|(i, signal)| self.record.raw_signal_samples.get(i)
+                .map(|records| records.iter().flat_map(|record_data| to_physical(signal, record_data)).collect::<Vec<_>>())  // TODO: In case of discontinuous EDFs, fill gaps or similar
+                .unwrap_or_default())
+            .collect();
+
+        self.chart_signals = self.channels.iter()
             .enumerate()
-            .map(|(i, signal)| ChartSignal {
-                index: i,
-                label: signal.label.clone(),
-                range: [
-                    format!("{} {}", signal.physical_minimum, signal.physical_dimension),
-                    format!("{} {}", signal.physical_maximum, signal.physical_dimension)
-                ],
-                points: self.record.raw_signal_samples.get(i)
-                    .unwrap_or(&Vec::new())
-                    .iter()
-                    .map(|record_data| to_physical(signal, record_data))  // TODO: In case of discontinuous EDFs, fill gaps or similar
-                    .flatten()
-                    .enumerate()
-                    .map(|(i, value)| (i as f32, value.clone() as f32))
-                    .collect::<Vec<_>>(),
-                physical_min: signal.physical_minimum,
-                physical_max: signal.physical_maximum,
-            })
-            .collect::<Vec<_>>()
+            .filter(|(_, channel)| channel.visible)
+            .filter_map(|(i, channel)| self.chart_signal(i, &channel.display()))
+            .collect();
+    }
+
+    /// Creates the chart signal of the last read epochs of the channel with the given index (without annotations) using the given display
+    pub fn chart_signal(&self, channel: usize, display: &SignalDisplay) -> Option<ChartSignal> {
+        let signal = self.get_signals().iter().filter(|s| !s.is_annotation()).nth(channel)?;
+
+        Some(ChartSignal::from_samples(
+            channel,
+            signal.label.clone(),
+            display,
+            &signal.physical_dimension,
+            (signal.physical_minimum, signal.physical_maximum),
+            self.samples.get(channel)?.clone(),
+            self.is_filtered.then_some(signal.samples_count as f64 / self.file.header.get_record_duration())
+        ))
     }
 
     pub fn get_signals(&self) -> &Vec<SignalHeader> {
@@ -188,6 +329,7 @@ impl EpochReader {
 
         self.record = spanning_record;
         self.position += read_millis as i128;
+        self.update_chart_signals();
 
         Ok(())
     }
@@ -1,2 +1,7 @@
+pub mod edf_annotations;
 pub mod epoch_reader;
+pub mod preset;
 pub mod project_initializer;
+pub mod project_settings;
+pub mod relink;
+pub mod spectrogram_cache;
@@ -0,0 +1,221 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::database::{DBError, remove_project_preset, set_project_preset};
+use crate::database::types::ChannelAlias;
+use crate::scoring::stages::StageVocabulary;
+use crate::signals::alias;
+use crate::signals::derived::DerivedChannel;
+use crate::signals::montage::Montage;
+use crate::storage::project_settings::{MAX_CONTEXT_EPOCHS, ProjectSettings};
+
+// Extension of exported project preset files
+pub const PRESET_EXTENSION: &str = "ngt";
+
+/// Reusable template for new projects, so all projects of a team are created with the same displayed
+/// epochs, processing options, channel names and types, derived channels, montages and stage vocabulary.
+/// The epoch length is not part of a preset, as the readers only support epochs of `EpochReader::EPOCH_DURATION`
+/// (30 s), which every project uses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectPreset {
+    pub name: String,
+
+    pub epochs_before_current: u8,
+    pub epochs_after_current: u8,
+    pub filter_signal: bool,
+    pub clip_signal: bool,
+    pub auto_align_signals: bool,
+    #[serde(default)]
+    pub stage_vocabulary: StageVocabulary,
+
+    // Names and types of channels by their label, which take precedence over the alias dictionary
+    #[serde(default)]
+    pub channel_rules: Vec<ChannelAlias>,
+    #[serde(default)]
+    pub derived_channels: Vec<DerivedChannel>,
+    #[serde(default)]
+    pub montages: Vec<Montage>
+}
+
+impl ProjectPreset {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            epochs_before_current: 1,
+            epochs_after_current: 1,
+            filter_signal: true,
+            clip_signal: true,
+            auto_align_signals: true,
+            stage_vocabulary: StageVocabulary::default(),
+            channel_rules: Vec::new(),
+            derived_channels: Vec::new(),
+            montages: Vec::new()
+        }
+    }
+
+    /// Creates a preset of the (unsaved) settings of a project. The names and types of all channels of the sources become channel rules.
+    pub fn from_settings(name: &str, settings: &ProjectSettings, stage_vocabulary: &StageVocabulary) -> Self {
+        let mut channel_rules: Vec<ChannelAlias> = Vec::new();
+        for channel in settings.sources.iter().flat_map(|s| &s.signals.channels) {
+            if channel_rules.iter().any(|r| alias::normalize(&r.alias) == alias::normalize(&channel.label)) {
+                continue;
+            }
+            channel_rules.push(ChannelAlias {
+                alias: channel.label.clone(),
+                name: channel.display_name().to_string(),
+                channel_type: channel.channel_type
+            });
+        }
+
+        Self {
+            name: name.to_string(),
+            epochs_before_current: settings.epochs_before_current,
+            epochs_after_current: settings.epochs_after_current,
+            filter_signal: settings.filter_signal,
+            clip_signal: settings.clip_signal,
+            auto_align_signals: settings.auto_align_signals,
+            stage_vocabulary: stage_vocabulary.clone(),
+            channel_rules,
+            derived_channels: settings.derived_channels.clone(),
+            montages: settings.montages.iter().filter_map(|m| m.to_montage().ok()).collect()
+        }
+    }
+
+    /// Reads a preset from an exported file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        let preset: Self = serde_json::from_str(&content)?;
+
+        let errors = preset.stage_vocabulary.validate();
+        if !errors.is_empty() {
+            return Err(format!("The stage vocabulary {} cannot be used: {}", preset.stage_vocabulary.name, errors.join(", ")).into());
+        }
+
+        Ok(preset)
+    }
+
+    /// Writes the preset to a file which can be imported by others
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Gets the channel rules of the preset followed by the given aliases of the alias dictionary
+    pub fn aliases(&self, aliases: Vec<ChannelAlias>) -> Vec<ChannelAlias> {
+        self.channel_rules.iter().cloned().chain(aliases).collect()
+    }
+}
+
+impl Display for ProjectPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Editable copy of all stored presets. The changes are only written once they are validated and saved.
+pub struct PresetEditor {
+    pub presets: Vec<ProjectPreset>,
+    pub selected: Option<usize>,
+    pub errors: Vec<String>,
+
+    // Names of the presets when the editor was opened, to remove deleted and renamed presets
+    pub stored_names: Vec<String>
+}
+
+impl PresetEditor {
+    pub fn new(presets: Vec<ProjectPreset>) -> Self {
+        Self {
+            stored_names: presets.iter().map(|p| p.name.clone()).collect(),
+            selected: (!presets.is_empty()).then_some(0),
+            presets,
+            errors: Vec::new()
+        }
+    }
+
+    pub fn selected_preset(&mut self) -> Option<&mut ProjectPreset> {
+        self.presets.get_mut(self.selected?)
+    }
+
+    /// Adds the preset and selects it. The name is made unique by appending a number if required.
+    pub fn add(&mut self, mut preset: ProjectPreset) {
+        let name = preset.name.trim().to_string();
+        preset.name = name.clone();
+        let mut copy = 1;
+        while self.presets.iter().any(|p| p.name.trim().eq_ignore_ascii_case(&preset.name)) {
+            copy += 1;
+            preset.name = format!("{} ({})", name, copy);
+        }
+
+        self.presets.push(preset);
+        self.selected = Some(self.presets.len() - 1);
+    }
+
+    /// Writes the presets to the settings database and removes the deleted or renamed presets
+    pub fn save(&mut self) -> Result<(), DBError> {
+        for preset in &mut self.presets {
+            preset.name = preset.name.trim().to_string();
+        }
+        for name in &self.stored_names {
+            if !self.presets.iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+                remove_project_preset(name)?;
+            }
+        }
+        for preset in &self.presets {
+            set_project_preset(preset)?;
+        }
+
+        self.stored_names = self.presets.iter().map(|p| p.name.clone()).collect();
+        Ok(())
+    }
+
+    /// Gets all problems which prevent the presets from being saved
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for (i, preset) in self.presets.iter().enumerate() {
+            let name = preset.name.trim();
+            if name.is_empty() {
+                errors.push(format!("The name of preset {} must not be empty", i + 1));
+                continue;
+            }
+            if self.presets.iter().filter(|p| p.name.trim().eq_ignore_ascii_case(name)).count() > 1 {
+                errors.push(format!("There are multiple presets named {}", name));
+            }
+            if preset.epochs_before_current > MAX_CONTEXT_EPOCHS || preset.epochs_after_current > MAX_CONTEXT_EPOCHS {
+                errors.push(format!("Preset {} shows more than {} epochs before or after the current epoch", name, MAX_CONTEXT_EPOCHS));
+            }
+            for error in preset.stage_vocabulary.validate() {
+                errors.push(format!("The stage vocabulary of preset {} cannot be used: {}", name, error));
+            }
+            for rule in &preset.channel_rules {
+                if rule.alias.trim().is_empty() || rule.name.trim().is_empty() {
+                    errors.push(format!("The label and name of all channel rules of preset {} must not be empty", name));
+                    break;
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn added_presets_get_unique_trimmed_names() {
+        let mut editor = PresetEditor::new(vec![ProjectPreset::new("Lab")]);
+        editor.add(ProjectPreset::new("  Lab "));
+        editor.add(ProjectPreset::new("lab"));
+        editor.add(ProjectPreset::new(" Clinic"));
+
+        let names = editor.presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["Lab", "Lab (2)", "lab (3)", "Clinic"]);
+        assert_eq!(editor.selected, Some(3));
+    }
+}
@@ -1,28 +1,126 @@
 use std::path::Path;
 use std::error::Error;
-use std::collections::BTreeMap;
 use std::fs::{self, create_dir_all, read_dir};
 
 use iced::Task;
 use xml::EmitterConfig;
 use serde_xml_rs::SerdeXml;
 
+use crate::scoring::events::Events;
 use crate::{Annotations, Markers, Message, Project, ProjectConfiguration, ProjectType, Scorings, SessionState};
 
 const ILLEGAL_PATH_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
 
+/// Field of the project configuration a validation error refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigurationField {
+    Name,
+    Location,
+    Sources,
+
+    // Signal source with the given path
+    Source(String),
+
+    // Errors not caused by a single field, e.g. while writing the project
+    General
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigurationError {
+    pub field: ConfigurationField,
+    pub message: String
+}
+
+impl ConfigurationError {
+    pub fn new(field: ConfigurationField, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into()
+        }
+    }
+}
+
 pub fn create_new(project: ProjectConfiguration) -> Task<Message> {
     Task::future(create_new_handler(project))
 }
 
 async fn create_new_handler(project: ProjectConfiguration) -> Message {
-    match create_new_async(project).await {
+    match create_project(project) {
         Ok(path) => Message::OpenProjectPath(path),
         Err(e) => Message::CreateProjectWizardError(e.to_string())
     }
 }
 
-async fn create_new_async(config: ProjectConfiguration) -> Result<String, Box<dyn Error>> {
+/// Gets all problems which prevent a project from being created with the given configuration
+pub fn validate(config: &ProjectConfiguration) -> Vec<ConfigurationError> {
+    let mut errors = validate_details(config);
+    errors.append(&mut validate_sources(config));
+    errors
+}
+
+/// Gets all problems of the name and location of the project
+pub fn validate_details(config: &ProjectConfiguration) -> Vec<ConfigurationError> {
+    let mut errors = Vec::new();
+
+    let name = config.name.trim();
+    let mut illegal_chars = Vec::new();
+    for c in illegal_path_char_positions(name).into_iter().filter_map(|i| name[i..].chars().next()) {
+        let c = if c <= '\u{1F}' { "control characters".to_string() } else { c.to_string() };
+        if !illegal_chars.contains(&c) {
+            illegal_chars.push(c);
+        }
+    }
+    if name.is_empty() {
+        errors.push(ConfigurationError::new(ConfigurationField::Name, "A project name is required"));
+    } else if !illegal_chars.is_empty() {
+        errors.push(ConfigurationError::new(ConfigurationField::Name, format!("The project name must not contain {}", illegal_chars.join(" "))));
+    }
+
+    let location = Path::new(&config.path);
+    if config.path.trim().is_empty() {
+        errors.push(ConfigurationError::new(ConfigurationField::Location, "A location is required"));
+    } else if location.exists() && !location.is_dir() {
+        errors.push(ConfigurationError::new(ConfigurationField::Location, "The location is not a directory"));
+    } else if !name.is_empty() && read_dir(location.join(sanitize_file_name(&config.name))).is_ok_and(|mut r| r.next().is_some()) {
+        errors.push(ConfigurationError::new(ConfigurationField::Name, format!("The directory {} already exists at the location and is not empty", sanitize_file_name(&config.name))));
+    }
+
+    errors
+}
+
+/// Gets all problems of the added signal sources. Whether a file can be read is determined once when it is added.
+pub fn validate_sources(config: &ProjectConfiguration) -> Vec<ConfigurationError> {
+    let mut errors = Vec::new();
+    if config.data.is_empty() {
+        errors.push(ConfigurationError::new(ConfigurationField::Sources, "At least one signal source is required"));
+    }
+
+    for (i, source) in config.data.iter().enumerate() {
+        let field = ConfigurationField::Source(source.path.clone());
+        if config.data[..i].iter().any(|s| Path::new(&s.path) == Path::new(&source.path)) {
+            errors.push(ConfigurationError::new(field, "The file was added multiple times"));
+            continue;
+        }
+
+        if let Some(error) = &source.read_error {
+            errors.push(ConfigurationError::new(field.clone(), error));
+        }
+
+        // Copies are stored by their file name in the sources directory of the project
+        if !source.is_reference && config.data[..i].iter().any(|s| !s.is_reference && s.name == source.name) {
+            errors.push(ConfigurationError::new(field, format!("Another copied file is named {}, reference one of them instead", source.name)));
+        }
+    }
+
+    errors
+}
+
+/// Creates the directory structure of a new project and returns the path of the project file
+pub fn create_project(config: ProjectConfiguration) -> Result<String, Box<dyn Error>> {
+    if let Some(error) = validate(&config).into_iter().next() {
+        return Err(error.message.into());
+    }
+
     let project_name = sanitize_file_name(&config.name);
     let project_path = Path::new(&config.path).join(&project_name);
     let project_file = project_path.join(format!("{}.ngp", project_name));
@@ -32,24 +130,18 @@ async fn create_new_async(config: ProjectConfiguration) -> Result<String, Box<dy
     let scores_file = subdir_lables.join("scores.json");
     let markers_file = subdir_lables.join("markers.json");
     let annotations_file = subdir_lables.join("annotations.json");
+    let events_file = subdir_lables.join("events.json");
     let session_file = project_path.join("session.json");
 
-    // Create the directory if it is missing and throw an error
-    // in case the directory exists and is not empty
-    if project_path.is_dir() {
-        if read_dir(project_path).is_ok_and(|r| r.count() > 0) {
-            return Err("Project directory already exists and is not empty".into());
-        }
-    }
-    else {
-        create_dir_all(project_path)?;
-    }
+    // Create the directory if it is missing, the validation ensures it is empty otherwise
+    create_dir_all(&project_path)?;
 
-    // Create and store the project file
-    let xml_serializer = SerdeXml::new().emitter(EmitterConfig::new().perform_indent(true));
-    let project = Project::from_config(&config);
-    let xml = xml_serializer.to_string(&project)?;
-    fs::write(&project_file, xml)?;
+    // Create and store the project file including the fingerprints of the sources to find moved files again
+    let mut project = Project::from_config(&config);
+    for (source, signals) in project.signals.iter_mut().zip(&config.data) {
+        source.update_fingerprint(Path::new(&signals.path))?;
+    }
+    fs::write(&project_file, serialize_project(&project)?)?;
 
     // Create project directory structure
     create_dir_all(&subdir_sources)?;
@@ -74,11 +166,12 @@ async fn create_new_async(config: ProjectConfiguration) -> Result<String, Box<dy
 
     // Create the default scores collection file if required for project type
     if project.project_type == ProjectType::SleepScoring {
-        let scores_json = serde_json::to_string_pretty(&Scorings {
-            epoch_duration: project.epoch_duration,
-            values: BTreeMap::new()
-        })?;
+        let scores_json = serde_json::to_string_pretty(&Scorings::new(project.epoch_duration))?;
         fs::write(scores_file, scores_json)?;
+
+        // Create the default events collection file
+        let events_json = serde_json::to_string_pretty(&Events::default())?;
+        fs::write(events_file, events_json)?;
     }
 
     // Create the default user session state
@@ -88,6 +181,11 @@ async fn create_new_async(config: ProjectConfiguration) -> Result<String, Box<dy
     Ok(project_file.to_string_lossy().to_string())
 }
 
+pub fn serialize_project(project: &Project) -> Result<String, Box<dyn Error>> {
+    let xml_serializer = SerdeXml::new().emitter(EmitterConfig::new().perform_indent(true));
+    Ok(xml_serializer.to_string(project)?)
+}
+
 pub fn sanitize_file_name(value: &str) -> String {
     let positions = illegal_path_char_positions(value);
     value.char_indices()
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use edf_rs::file::EDFFile;
+
+use crate::scoring::history::History;
+use crate::signals::derived::{self, DerivedChannel};
+use crate::signals::montage::{self, Montage, MontageChannel};
+use crate::storage::epoch_reader::EpochReader;
+use crate::{CurrentProject, ProjectSignals, SignalSource};
+
+// Maximum amount of epochs shown before and after the current epoch
+pub const MAX_CONTEXT_EPOCHS: u8 = 4;
+
+/// Size and content hash of the added sources by their path
+pub type Fingerprints = HashMap<String, (u64, String)>;
+
+/// Editable copy of the settings of an opened project. The changes are only
+/// applied to the project once they are validated and saved.
+pub struct ProjectSettings {
+    pub name: String,
+    pub new_tag: String,
+    pub tags: Vec<String>,
+    pub epochs_before_current: u8,
+    pub epochs_after_current: u8,
+    pub filter_signal: bool,
+    pub clip_signal: bool,
+    pub auto_align_signals: bool,
+    pub sources: Vec<SourceSettings>,
+    pub derived_channels: Vec<DerivedChannel>,
+    pub montages: Vec<MontageSettings>,
+    pub errors: Vec<String>,
+
+    // Result of an action which succeeded (e.g. saving the settings as preset)
+    pub notice: Option<String>,
+
+    // Whether the added sources are hashed before the settings are applied
+    pub is_saving: bool
+}
+
+pub struct SourceSettings {
+    pub signals: ProjectSignals,
+
+    // Index of the source within the project, new sources are not part of the project yet
+    pub index: Option<usize>,
+
+    // Offset in milliseconds as entered by the user
+    pub offset: String
+}
+
+impl SourceSettings {
+    pub fn is_new(&self) -> bool {
+        self.index.is_none()
+    }
+}
+
+/// Editable montage, the values of the channels are kept as entered by the user
+pub struct MontageSettings {
+    pub name: String,
+    pub channels: Vec<MontageChannelSettings>
+}
+
+pub struct MontageChannelSettings {
+    pub channel: String,
+    pub gain: String,
+    pub high_pass: String,
+    pub low_pass: String,
+    pub color: String
+}
+
+/// Editable value of a channel of a montage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MontageChannelField {
+    Gain,
+    HighPass,
+    LowPass,
+    Color
+}
+
+impl MontageSettings {
+    pub fn from_montage(montage: &Montage) -> Self {
+        let value = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+        Self {
+            name: montage.name.clone(),
+            channels: montage.channels.iter().map(|c| MontageChannelSettings {
+                channel: c.channel.clone(),
+                gain: value(c.gain),
+                high_pass: value(c.high_pass),
+                low_pass: value(c.low_pass),
+                color: c.color.clone().unwrap_or_default()
+            }).collect()
+        }
+    }
+
+    /// Converts the entered values to a montage. Empty values keep the defaults of the channel.
+    pub fn to_montage(&self) -> Result<Montage, String> {
+        let name = self.name.trim();
+        let value = |value: &str, description: &str, channel: &str| match value.trim() {
+            "" => Ok(None),
+            value => value.parse::<f64>().ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .map(Some)
+                .ok_or(format!("The {} of {} in montage {} must be a positive number", description, channel, name))
+        };
+
+        let channels = self.channels.iter().map(|c| {
+            let color = c.color.trim();
+            if !color.is_empty() && montage::parse_color(color).is_none() {
+                return Err(format!("The color of {} in montage {} must be a hex code like #4275DB", c.channel, name));
+            }
+
+            Ok(MontageChannel {
+                channel: c.channel.trim().to_string(),
+                gain: value(&c.gain, "gain", &c.channel)?.filter(|g| *g > 0.0),
+                high_pass: value(&c.high_pass, "high-pass", &c.channel)?,
+                low_pass: value(&c.low_pass, "low-pass", &c.channel)?,
+                color: Some(color.to_uppercase()).filter(|c| !c.is_empty())
+            })
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Montage {
+            name: name.to_string(),
+            channels
+        })
+    }
+}
+
+impl MontageChannelSettings {
+    pub fn new(channel: String) -> Self {
+        Self {
+            channel,
+            gain: String::new(),
+            high_pass: String::new(),
+            low_pass: String::new(),
+            color: String::new()
+        }
+    }
+
+    pub fn field_mut(&mut self, field: MontageChannelField) -> &mut String {
+        match field {
+            MontageChannelField::Gain => &mut self.gain,
+            MontageChannelField::HighPass => &mut self.high_pass,
+            MontageChannelField::LowPass => &mut self.low_pass,
+            MontageChannelField::Color => &mut self.color
+        }
+    }
+}
+
+impl ProjectSettings {
+    pub fn from_project(project: &CurrentProject) -> Self {
+        let sources = project.project.signals.iter().enumerate().map(|(i, source)| {
+            let path = Path::new(&project.path).join(&source.path).to_string_lossy().to_string();
+            let mut signals = ProjectSignals::from_path(path);
+            signals.is_reference = source.read_only;
+            if let Some(reader) = project.readers.get(i) {
+                signals.channels = reader.get_channels().clone();
+            }
+
+            SourceSettings {
+                signals,
+                index: Some(i),
+                offset: source.offset.to_string()
+            }
+        }).collect();
+
+        Self {
+            name: project.project.name.clone(),
+            new_tag: String::new(),
+            tags: project.project.tags.clone(),
+            epochs_before_current: project.project.epochs_before_current,
+            epochs_after_current: project.project.epochs_after_current,
+            filter_signal: project.project.filter_signal,
+            clip_signal: project.project.clip_signal,
+            auto_align_signals: project.project.auto_align_signals,
+            sources,
+            derived_channels: project.project.derived_channels.clone(),
+            montages: project.project.montages.iter().map(MontageSettings::from_montage).collect(),
+            errors: Vec::new(),
+            notice: None,
+            is_saving: false
+        }
+    }
+
+    /// Adds the EDF files at the given paths as new sources, skipping files which are already part of the project
+    pub fn add_sources(&mut self, paths: Vec<PathBuf>) {
+        for path in paths.iter().filter_map(|p| p.to_str()) {
+            if self.sources.iter().any(|s| Path::new(&s.signals.path) == Path::new(path)) {
+                continue;
+            }
+
+            self.sources.push(SourceSettings {
+                signals: ProjectSignals::from_path(path.to_string()),
+                index: None,
+                offset: "0".to_string()
+            });
+        }
+    }
+
+    /// Gets the references of all channels which can be added to a montage. Channels of the sources
+    /// are prefixed with the number of their source in case the project has multiple sources.
+    pub fn channel_references(&self) -> Vec<String> {
+        let is_single_source = self.sources.len() == 1;
+        let channels = self.sources.iter().enumerate().flat_map(|(i, source)| source.signals.channels.iter().map(move |channel| {
+            if is_single_source {
+                channel.display_name().to_string()
+            } else {
+                format!("{}:{}", i + 1, channel.display_name())
+            }
+        }));
+        let derived = self.derived_channels.iter().map(|c| c.name.trim().to_string()).filter(|n| !n.is_empty());
+
+        channels.chain(derived).collect()
+    }
+
+    /// Removes the source at the given index. Derived channels and montages referring to sources by their
+    /// number are updated, references to the removed source are marked, so they have to be fixed before saving.
+    pub fn remove_source(&mut self, index: usize) {
+        if index >= self.sources.len() {
+            return;
+        }
+
+        self.sources.remove(index);
+        for channel in &mut self.derived_channels {
+            channel.expression = derived::remove_source_references(&channel.expression, index);
+        }
+        for channel in self.montages.iter_mut().flat_map(|m| &mut m.channels) {
+            channel.channel = derived::remove_source_reference(&channel.channel, index);
+        }
+    }
+
+    /// Gets the paths of the sources which are not part of the project yet
+    pub fn new_source_paths(&self) -> Vec<String> {
+        self.sources.iter().filter(|s| s.is_new()).map(|s| s.signals.path.clone()).collect()
+    }
+
+    /// Gets all problems which prevent the settings from being applied to the project
+    pub fn validate(&self, project_path: &Path) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push("The project name must not be empty".to_string());
+        }
+        if self.sources.is_empty() {
+            errors.push("At least one signal source is required".to_string());
+        }
+
+        let sources_dir = project_path.join("sources");
+        for source in &self.sources {
+            let name = &source.signals.name;
+            if source.offset.trim().parse::<u64>().is_err() {
+                errors.push(format!("The offset of {} must be a positive amount of milliseconds", name));
+            }
+            if !source.is_new() {
+                continue;
+            }
+
+            match EDFFile::open(&source.signals.path) {
+                Ok(edf) if edf.header.get_signals().iter().all(|s| s.is_annotation()) => errors.push(format!("{} does not contain any signals", name)),
+                Ok(_) => {}
+                // The debug representation is used, as displaying EDF errors recurses endlessly
+                Err(e) => errors.push(format!("{} cannot be read ({:?})", name, e))
+            }
+
+            // Copies are stored by their file name in the sources directory of the project
+            let is_duplicate = self.sources.iter().filter(|s| !s.signals.is_reference && s.signals.name == *name).count() > 1;
+            if !source.signals.is_reference && (is_duplicate || sources_dir.join(name).exists()) {
+                errors.push(format!("A source named {} already exists in the project, add it as reference instead", name));
+            }
+        }
+
+        // Derived channels may only use channels of the sources which are kept
+        let channels = self.sources.iter().map(|s| s.signals.channels.as_slice()).collect::<Vec<_>>();
+        for (i, channel) in self.derived_channels.iter().enumerate() {
+            let name = channel.name.trim();
+            if name.is_empty() {
+                errors.push(format!("The name of derived channel {} must not be empty", i + 1));
+            } else if self.derived_channels.iter().filter(|c| c.name.trim() == name).count() > 1 {
+                errors.push(format!("There are multiple derived channels named {}", name));
+            }
+            if let Err(e) = channel.resolve(&channels) {
+                let name = if name.is_empty() { (i + 1).to_string() } else { name.to_string() };
+                errors.push(format!("The expression of derived channel {} is invalid: {}", name, e));
+            }
+        }
+
+        // Montages may only show channels which exist within the project
+        for (i, settings) in self.montages.iter().enumerate() {
+            let name = settings.name.trim();
+            if name.is_empty() {
+                errors.push(format!("The name of montage {} must not be empty", i + 1));
+            } else if self.montages.iter().filter(|m| m.name.trim() == name).count() > 1 {
+                errors.push(format!("There are multiple montages named {}", name));
+            }
+
+            match settings.to_montage() {
+                Ok(montage) => errors.extend(montage.channels.iter()
+                    .filter(|c| c.resolve(&channels, &self.derived_channels).is_none())
+                    .map(|c| format!("The channel {} of montage {} does not exist", c.channel, name))),
+                Err(e) => errors.push(e)
+            }
+        }
+
+        errors
+    }
+}
+
+/// Calculates the size and content hash of the files at the given paths, which can take a while for large files
+pub fn fingerprints(paths: &[String]) -> std::io::Result<Fingerprints> {
+    paths.iter().map(|path| Ok((path.clone(), SignalSource::fingerprint(Path::new(path))?))).collect()
+}
+
+/// Applies the validated settings to the project, copies new sources into the project, reopens the
+/// readers and writes the project file. The labels are kept, only events of removed sources are dropped.
+pub fn apply(project: &mut CurrentProject, settings: &ProjectSettings, fingerprints: &Fingerprints) -> Result<(), Box<dyn Error>> {
+    // Sources which were added while the others were hashed are not hashed yet
+    if let Some(source) = settings.sources.iter().find(|s| s.is_new() && !fingerprints.contains_key(&s.signals.path)) {
+        return Err(format!("{} was added while saving, save the settings again", source.signals.name).into());
+    }
+
+    let sources_dir = Path::new(&project.path).join("sources");
+    let mut copied = Vec::new();
+
+    // Copy the new sources which are not referenced into the project
+    let mut signals = Vec::new();
+    for source in &settings.sources {
+        let mut signal = match source.index {
+            Some(index) => project.project.signals[index].clone(),
+            None => {
+                if !source.signals.is_reference {
+                    fs::create_dir_all(&sources_dir)?;
+                    let target = sources_dir.join(&source.signals.name);
+                    fs::copy(&source.signals.path, &target)?;
+                    copied.push(target);
+                }
+                let mut signal = SignalSource::from_config(&source.signals);
+                signal.set_fingerprint(fingerprints[&source.signals.path].clone());
+                signal
+            }
+        };
+        signal.offset = source.offset.trim().parse()?;
+        signals.push(signal);
+    }
+
+    // Open the readers of the changed sources before changing anything within the project
+    let readers = match CurrentProject::open_readers(&project.path, &signals, settings.epochs_before_current, settings.filter_signal) {
+        Ok(readers) => readers,
+        Err(e) => {
+            for path in copied {
+                _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+    };
+
+    // Events are bound to their source by index
+    let source_indices = settings.sources.iter().enumerate()
+        .filter_map(|(new, source)| source.index.map(|old| (old, new)))
+        .collect::<HashMap<_, _>>();
+    let event_count = project.events.values.len();
+    project.events.values.retain_mut(|event| match source_indices.get(&event.source) {
+        Some(index) => {
+            event.source = *index;
+            true
+        }
+        None => false
+    });
+    if project.events.values.len() != event_count || source_indices.iter().any(|(old, new)| old != new) {
+        // Previous states of the events refer to the previous sources
+        project.history = History::default();
+    }
+
+    // Markers and annotations of a channel are bound to the index of the signal across all sources
+    let signal_indices = signal_indices(&project.readers, &readers, &source_indices);
+    project.markers.local = std::mem::take(&mut project.markers.local).into_iter()
+        .filter_map(|(index, markers)| Some((*signal_indices.get(&index)?, markers)))
+        .collect();
+    project.annotations.local = std::mem::take(&mut project.annotations.local).into_iter()
+        .filter_map(|(index, annotations)| Some((*signal_indices.get(&index)?, annotations)))
+        .collect();
+
+    project.project.name = settings.name.trim().to_string();
+    project.project.tags = settings.tags.clone();
+    project.project.epochs_before_current = settings.epochs_before_current;
+    project.project.epochs_after_current = settings.epochs_after_current;
+    project.session.context_epochs = None;
+    project.project.filter_signal = settings.filter_signal;
+    project.project.clip_signal = settings.clip_signal;
+    project.project.auto_align_signals = settings.auto_align_signals;
+    project.project.signals = signals;
+    project.project.derived_channels = settings.derived_channels.iter().map(|c| DerivedChannel {
+        name: c.name.trim().to_string(),
+        expression: c.expression.trim().to_string(),
+        channel_type: c.channel_type
+    }).collect();
+    project.project.montages = settings.montages.iter().filter_map(|m| m.to_montage().ok()).collect();
+    if project.active_montage().is_none() {
+        project.project.active_montage = None;
+    }
+    project.readers = readers;
+    project.spectrogram = None;
+    project.spectrogram_channel = None;
+
+    // Write the project file and the labels, as the events might refer to other sources now
+    project.save()
+}
+
+/// Maps the indices of the signals across the previous readers to the indices across the new readers,
+/// signals of removed sources are missing
+fn signal_indices(previous: &[EpochReader], readers: &[EpochReader], source_indices: &HashMap<usize, usize>) -> HashMap<u32, u32> {
+    let bases = |readers: &[EpochReader]| readers.iter()
+        .scan(0, |base, reader| {
+            let index = *base;
+            *base += reader.signal_count();
+            Some(index)
+        })
+        .collect::<Vec<_>>();
+    let (previous_bases, bases) = (bases(previous), bases(readers));
+
+    let mut indices = HashMap::new();
+    for (old, new) in source_indices {
+        let (Some(reader), Some(base)) = (previous.get(*old), bases.get(*new)) else { continue };
+        for offset in 0..reader.signal_count() {
+            indices.insert((previous_bases[*old] + offset) as u32, (base + offset) as u32);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, TestDirectory};
+    use crate::{AnnotationValue, Marker};
+
+    #[test]
+    fn remaps_markers_and_annotations_of_removed_sources() {
+        let directory = TestDirectory::new("settings-markers");
+        let mut project = testing::project(&directory, &[("a.edf", "EEG", 0), ("b.edf", "EOG", 0)]);
+        project.markers.local.insert(0, HashMap::from([(Marker::Red, vec![30_000])]));
+        project.markers.local.insert(1, HashMap::from([(Marker::Blue, vec![60_000])]));
+        let annotation = AnnotationValue { timestamp: 90_000, value: "Arousal".to_string() };
+        project.annotations.local.insert(1, HashMap::from([(Marker::Green, vec![annotation.clone()])]));
+
+        fs::create_dir(directory.join("lables")).unwrap();
+
+        let mut settings = ProjectSettings::from_project(&project);
+        settings.remove_source(0);
+        apply(&mut project, &settings, &Fingerprints::new()).unwrap();
+
+        assert_eq!(project.markers.local, HashMap::from([(0, HashMap::from([(Marker::Blue, vec![60_000])]))]));
+        assert_eq!(project.annotations.local, HashMap::from([(0, HashMap::from([(Marker::Green, vec![annotation])]))]));
+        assert_eq!(project.signal_at(0).map(|(_, label)| label), Some("EOG".to_string()));
+    }
+}
@@ -0,0 +1,181 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use md5::{Digest, Md5};
+
+use crate::storage::project_initializer;
+use crate::{Project, SignalSource};
+
+// Maximum depth of subdirectories which are searched for moved source files
+const MAX_SEARCH_DEPTH: usize = 4;
+
+const HASH_BUFFER_SIZE: usize = 1 << 20;
+
+/// Calculates the MD5 hash of the content of the file at the given path
+pub fn content_hash(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0; HASH_BUFFER_SIZE];
+
+    loop {
+        let count = file.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+    }
+
+    Ok(format!("{:X}", hasher.finalize()))
+}
+
+/// Checks whether the file at the given path is the file of the signal source by comparing the size and
+/// content hash. Sources of older projects without size and hash can only be identified by their file name.
+pub fn is_source_file(source: &SignalSource, path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() || source.size.is_some_and(|size| size != metadata.len()) {
+        return false;
+    }
+
+    match &source.hash {
+        Some(hash) => content_hash(path).is_ok_and(|h| h == *hash),
+        None => source.size.is_some() || path.file_name() == Path::new(&source.path).file_name()
+    }
+}
+
+/// Searches the given directories and their subdirectories for the file of the signal source.
+/// Files with the original file name are checked first, as the content hash is expensive to calculate.
+pub fn search(directories: &[PathBuf], source: &SignalSource) -> Option<PathBuf> {
+    let file_name = Path::new(&source.path).file_name();
+    let mut candidates = Vec::new();
+    for directory in directories {
+        collect_files(directory, MAX_SEARCH_DEPTH, &mut candidates);
+    }
+    candidates.sort_by(|a, b| (a.file_name() != file_name, a).cmp(&(b.file_name() != file_name, b)));
+    candidates.dedup();
+
+    candidates.into_iter().find(|path| is_source_file(source, path))
+}
+
+fn collect_files(directory: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() && depth > 0 {
+            collect_files(&path, depth - 1, files);
+        } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("edf")) {
+            files.push(path);
+        }
+    }
+}
+
+/// Error of a project whose signal source files could not be found
+#[derive(Debug)]
+pub struct MissingSources {
+    pub project_file: PathBuf,
+    pub sources: Vec<(usize, SignalSource)>
+}
+
+impl Display for MissingSources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let paths = self.sources.iter().map(|(_, s)| s.path.as_str()).collect::<Vec<_>>();
+        write!(f, "Signal sources not found: {}", paths.join(", "))
+    }
+}
+
+impl Error for MissingSources {}
+
+/// State of the dialog to find the files of missing signal sources of a project
+pub struct Relink {
+    pub project_file: PathBuf,
+    pub sources: Vec<RelinkSource>,
+    pub directories: Vec<PathBuf>,
+    pub is_searching: bool,
+    pub errors: Vec<String>
+}
+
+pub struct RelinkSource {
+    pub index: usize,
+    pub source: SignalSource,
+    pub found: Option<PathBuf>
+}
+
+impl Relink {
+    /// Prepares relinking the missing sources. The directories of the project, the original location
+    /// of the sources (and its parent) and the given additional directories (e.g. the last browsed one) are searched.
+    pub fn new(missing: &MissingSources, additional_directories: Vec<PathBuf>) -> Self {
+        let project_dir = missing.project_file.parent().map(Path::to_path_buf).unwrap_or_default();
+        let originals = missing.sources.iter()
+            .flat_map(|(_, source)| project_dir.join(&source.path).ancestors().skip(1).take(2).filter(|p| p.parent().is_some()).map(Path::to_path_buf).collect::<Vec<_>>());
+
+        let mut directories: Vec<PathBuf> = Vec::new();
+        for directory in std::iter::once(project_dir.clone()).chain(originals).chain(additional_directories) {
+            if directory.is_dir() && !directories.contains(&directory) {
+                directories.push(directory);
+            }
+        }
+
+        Self {
+            project_file: missing.project_file.clone(),
+            sources: missing.sources.iter().map(|(index, source)| RelinkSource {
+                index: *index,
+                source: source.clone(),
+                found: None
+            }).collect(),
+            directories,
+            is_searching: false,
+            errors: Vec::new()
+        }
+    }
+
+    /// Gets the sources which have not been found yet
+    pub fn unresolved(&self) -> Vec<(usize, SignalSource)> {
+        self.sources.iter()
+            .filter(|s| s.found.is_none())
+            .map(|s| (s.index, s.source.clone()))
+            .collect()
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.sources.iter().all(|s| s.found.is_some())
+    }
+
+    /// Sets the found file of the source with the given index within the project
+    pub fn set_found(&mut self, index: usize, path: PathBuf) {
+        if let Some(source) = self.sources.iter_mut().find(|s| s.index == index) {
+            source.found = Some(path);
+        }
+    }
+
+    /// Writes the found locations of the sources to the project file. Files within the project directory
+    /// are stored relative to it, all others are referenced.
+    pub fn apply(&self) -> Result<(), Box<dyn Error>> {
+        let project_dir = self.project_file.parent().ok_or("Invalid project file")?;
+        let project_xml = fs::read_to_string(&self.project_file)?;
+        let mut project = serde_xml_rs::from_str::<Project>(&project_xml)?;
+
+        for source in &self.sources {
+            let (Some(found), Some(signal)) = (&source.found, project.signals.get_mut(source.index)) else {
+                continue;
+            };
+
+            match found.strip_prefix(project_dir) {
+                Ok(relative) => signal.path = relative.to_string_lossy().to_string(),
+                Err(_) => {
+                    signal.path = found.to_string_lossy().to_string();
+                    signal.read_only = true;
+                }
+            }
+        }
+
+        fs::write(&self.project_file, project_initializer::serialize_project(&project)?)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use edf_rs::file::EDFFile;
+use md5::{Digest, Md5};
+use ndarray::Array1;
+use ndarray_npy::{NpzReader, NpzWriter};
+
+use crate::SignalSource;
+use crate::external::lspopt::spectrogram_lspopt;
+use crate::external::scipy::Spectrogram;
+
+/// Gets the path of the precomputed spectrogram of a signal within the processed directory of the project, e.g.
+/// `processed/recording.edf.3F2A….0.spectrogram.npz`. The file name is only kept for readability, the source is
+/// identified by its path within the project and its content hash, so same-named files in different directories
+/// and relinked sources do not share a spectrogram.
+pub fn cache_path(project_path: &Path, source: &SignalSource, signal_index: usize) -> PathBuf {
+    let file_name = Path::new(&source.path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let key = format!("{}|{}", source.path, source.hash.as_deref().unwrap_or_default());
+    named_cache_path(project_path, &format!("{}.{:X}.{}", file_name, Md5::digest(key.as_bytes()), signal_index))
+}
+
+/// Gets the path of a precomputed spectrogram with the given name within the processed directory of the project
+pub fn named_cache_path(project_path: &Path, name: &str) -> PathBuf {
+    project_path.join("processed").join(format!("{}.spectrogram.npz", name))
+}
+
+/// Calculates the spectrogram of an entire signal with 30 second windows. The progress of reading the
+/// samples is reported in percent whenever it changes.
+pub fn compute(path: &Path, signal_index: usize, mut progress: impl FnMut(u16)) -> Result<Spectrogram, Box<dyn Error>> {
+    let mut reader = EDFFile::open(path)?;
+    let signal = reader.header.get_signals().get(signal_index).cloned().ok_or("Signal does not exist")?;
+    let sf = reader.header.get_signal_sample_frequency(signal_index).ok_or("Signal has no sample frequency")?;
+    let nperseg = (30.0 * sf) as i32;
+    let record_count = reader.header.get_record_count().unwrap_or(0);
+
+    // Collect all samples of the target signal across all records
+    let mut samples = Vec::with_capacity(signal.samples_count * record_count);
+    let mut records = 0;
+    let mut last_progress = 0;
+    while let Some(record) = reader.read_record()? {
+        samples.extend_from_slice(&record.get_physical_samples(&signal)[signal_index]);
+        records += 1;
+        let current = (100.0 * records as f32 / record_count.max(1) as f32).round() as u16;
+        if current > last_progress {
+            last_progress = current;
+            progress(current);
+        }
+    }
+
+    Ok(spectrogram_lspopt(Array1::from_vec(samples), sf, nperseg))
+}
+
+pub fn load(path: &Path) -> Result<Spectrogram, Box<dyn Error>> {
+    let mut npz = NpzReader::new(File::open(path)?)?;
+
+    Ok(Spectrogram {
+        freqs: npz.by_name("freqs")?,
+        time: npz.by_name("time")?,
+        result: npz.by_name("result")?
+    })
+}
+
+pub fn save(path: &Path, spectrogram: &Spectrogram) -> Result<(), Box<dyn Error>> {
+    let mut npz = NpzWriter::new_compressed(File::create(path)?);
+    npz.add_array("freqs", &spectrogram.freqs)?;
+    npz.add_array("time", &spectrogram.time)?;
+    npz.add_array("result", &spectrogram.result)?;
+    npz.finish()?;
+
+    Ok(())
+}
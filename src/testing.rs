@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveTime};
+use edf_rs::EDFSpecifications;
+use edf_rs::file::EDFFile;
+use edf_rs::headers::signal_header::SignalHeader;
+
+use crate::scoring::stages::StageVocabulary;
+use crate::{CurrentProject, Project, ProjectType, ScorerSet, SignalSource};
+
+// Amount of 30 second epochs of the generated sources
+pub const EPOCH_COUNT: usize = 8;
+
+/// Temporary directory of a test which is removed again when it is dropped
+pub struct TestDirectory(pub PathBuf);
+
+impl TestDirectory {
+    /// Creates an empty directory, the name has to be unique across all tests
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("noctig-{}-{}", name, std::process::id()));
+        _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn join(&self, path: &str) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TestDirectory {
+    fn drop(&mut self) {
+        _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes an EDF file with a single channel of the given label sampled at 1 Hz
+pub fn write_source(path: &Path, label: &str) {
+    let mut file = EDFFile::new(path).unwrap();
+    file.header
+        .with_specification(EDFSpecifications::EDF)
+        .with_start_date(NaiveDate::from_ymd_opt(2026, 2, 13).unwrap())
+        .with_start_time(NaiveTime::from_hms_opt(22, 30, 0).unwrap())
+        .with_record_duration(30.0);
+
+    let mut signal = SignalHeader::new();
+    signal
+        .with_label(label.to_string())
+        .with_transducer(String::new())
+        .with_physical_dimension("uV".to_string())
+        .with_prefilter(String::new())
+        .with_physical_range(-100.0, 100.0)
+        .with_digital_range(-1000, 1000)
+        .with_samples_count(30);
+    file.insert_signal(0, signal).unwrap();
+
+    for _ in 0..EPOCH_COUNT {
+        let mut record = file.header.create_record();
+        record.raw_signal_samples = vec![vec![0; 30]];
+        file.append_record(record).unwrap();
+    }
+    file.save().unwrap();
+}
+
+/// Creates a project within the directory with a source for each of the given file names,
+/// channel labels and offsets in milliseconds
+pub fn project(directory: &TestDirectory, sources: &[(&str, &str, u64)]) -> CurrentProject {
+    for (file, label, _) in sources {
+        write_source(&directory.join(file), label);
+    }
+
+    let project = Project {
+        name: "Test".to_string(),
+        project_type: ProjectType::SleepScoring,
+        epoch_duration: 30,
+        epochs_before_current: 1,
+        epochs_after_current: 1,
+        filter_signal: false,
+        clip_signal: false,
+        auto_align_signals: false,
+        signals: sources.iter().map(|(file, _, offset)| SignalSource {
+            path: file.to_string(),
+            read_only: true,
+            offset: *offset,
+            merge_groups: Vec::new(),
+            size: None,
+            hash: None,
+            channels: Vec::new()
+        }).collect(),
+        tags: Vec::new(),
+        stage_vocabulary: StageVocabulary::aasm(),
+        lights_off: None,
+        lights_on: None,
+        scorers: vec![ScorerSet::default()],
+        active_scorer: None,
+        derived_channels: Vec::new(),
+        montages: Vec::new(),
+        active_montage: None
+    };
+
+    CurrentProject::from_project(directory.0.to_string_lossy().to_string(), "Test.ngp".to_string(), project).unwrap()
+}
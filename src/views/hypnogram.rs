@@ -0,0 +1,120 @@
+use iced::{mouse, Color, Point, Rectangle, Renderer, Size, Theme};
+use iced::widget::canvas::{Action, Cache, Event, Frame, Geometry, Path};
+use iced::widget::canvas;
+
+use crate::scoring::stages::StageVocabulary;
+use crate::statistics::sleep_stats::AnalysisPeriod;
+use crate::{Message, Scorings};
+
+const LEVEL_PADDING_VERTICAL: f32 = 4.0;
+
+/// Overview of the stages of all epochs of the recording
+pub struct Hypnogram {
+    // Hypnogram level and color of the stage of every epoch
+    stages: Vec<Option<(u8, Color)>>,
+    level_count: u8,
+    current_epoch: u64,
+    period: AnalysisPeriod,
+    highlights: Vec<u64>,
+    cache: Cache,
+}
+
+impl Hypnogram {
+    pub fn new(scorings: &Scorings, vocabulary: &StageVocabulary, epoch_count: u64, current_epoch: u64, period: AnalysisPeriod) -> Self {
+        Self {
+            stages: (0..epoch_count).map(|e| scorings.values.get(&e)
+                .and_then(|stage| vocabulary.get(stage))
+                .map(|definition| (definition.hypnogram_level, definition.color()))
+            ).collect(),
+            level_count: vocabulary.hypnogram_levels(),
+            current_epoch,
+            period,
+            highlights: Vec::new(),
+            cache: Cache::new(),
+        }
+    }
+
+    /// Sets epochs which should be marked below the hypnogram (e.g. disagreements between scorers)
+    pub fn with_highlights(mut self, highlights: Vec<u64>) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    fn epoch_width(&self, width: f32) -> f32 {
+        width / self.stages.len().max(1) as f32
+    }
+}
+
+impl canvas::Program<Message> for Hypnogram {
+    type State = ();
+
+    fn update(&self, _state: &mut Self::State, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                let epoch = (position.x / self.epoch_width(bounds.width)) as u64;
+                Some(Action::publish(Message::JumpToEpoch(epoch)).and_capture())
+            }
+            _ => None
+        }
+    }
+
+    fn mouse_interaction(&self, _state: &Self::State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if cursor.is_over(bounds) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(&self, _state: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame: &mut Frame| {
+            let epoch_width = self.epoch_width(frame.width());
+            let level_height = (frame.height() - 2.0 * LEVEL_PADDING_VERTICAL) / self.level_count.max(1) as f32;
+
+            // Draw a bar for every sequence of epochs with the same stage
+            let mut start = 0;
+            while start < self.stages.len() {
+                let stage = &self.stages[start];
+                let end = self.stages[start..].iter().position(|s| s != stage).map_or(self.stages.len(), |len| start + len);
+
+                if let Some((level, color)) = stage {
+                    frame.fill_rectangle(
+                        Point::new(start as f32 * epoch_width, LEVEL_PADDING_VERTICAL + *level as f32 * level_height + level_height * 0.2),
+                        Size::new((end - start) as f32 * epoch_width, level_height * 0.6),
+                        *color
+                    );
+                }
+
+                start = end;
+            }
+
+            // Shade all epochs outside of the analysis period
+            let shade = theme.extended_palette().background.weakest.color.scale_alpha(0.72);
+            let period_start = self.period.epochs.start as f32 * epoch_width;
+            let period_end = self.period.epochs.end as f32 * epoch_width;
+            frame.fill_rectangle(Point::ORIGIN, Size::new(period_start, frame.height()), shade);
+            frame.fill_rectangle(Point::new(period_end, 0.0), Size::new((frame.width() - period_end).max(0.0), frame.height()), shade);
+
+            // Mark all highlighted epochs at the bottom
+            let highlight = theme.extended_palette().danger.base.color;
+            for epoch in &self.highlights {
+                frame.fill_rectangle(
+                    Point::new(*epoch as f32 * epoch_width, frame.height() - LEVEL_PADDING_VERTICAL),
+                    Size::new(epoch_width.max(1.0), LEVEL_PADDING_VERTICAL),
+                    highlight
+                );
+            }
+        });
+
+        // Draw the current position indicator
+        let mut overlay = Frame::new(renderer, bounds.size());
+        let x = (self.current_epoch as f32 + 0.5) * self.epoch_width(bounds.width);
+        overlay.stroke(
+            &Path::line(Point::new(x, 0.0), Point::new(x, bounds.height)),
+            canvas::Stroke::default().with_color(Color { a: 0.8, ..theme.palette().text }).with_width(1.5)
+        );
+
+        vec![geometry, overlay.into_geometry()]
+    }
+}
@@ -1,32 +1,139 @@
-use iced::{mouse, Color, Point, Rectangle, Renderer, Theme};
+use std::ops::Range;
+
+use iced::{keyboard, mouse, Color, Point, Rectangle, Renderer, Size, Theme};
 use iced::alignment::Vertical;
 use iced::gradient::ColorStop;
-use iced::widget::canvas::{Path, Cache, Frame, Geometry, Text};
+use iced::widget::canvas::{Action, Cache, Event, Frame, Geometry, Path, Text};
 use iced::widget::canvas::gradient::Linear;
 use iced::widget::canvas;
+use serde::{Deserialize, Serialize};
 
-use crate::storage::epoch_reader::ChartSignal;
-use crate::layout::scorer::SIGNAL_PADDING_VERTICAL;
+use crate::signals::channel::microvolt_factor;
+use crate::storage::epoch_reader::{ChartSignal, EpochReader};
+use crate::layout::scorer::{SIGNAL_PADDING_VERTICAL, scroll_delta_lines};
 use crate::formatting::font::REGULAR_BOLD;
+use crate::scoring::events::EventType;
+use crate::{Message, TimeSelection};
+
+// Minimum horizontal drag distance in pixels for a drag to be considered a selection
+const MIN_SELECTION_WIDTH: f32 = 3.0;
+
+// Amplitudes in µV above and below the baseline of the reference band which can be selected
+pub const REFERENCE_BANDS: [Option<f64>; 4] = [None, Some(37.5), Some(50.0), Some(75.0)];
+
+/// Vertical gridlines drawn across the signals
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum TimeGrid {
+    Off,
+    Epochs,
+    FiveSeconds,
+    #[default]
+    Seconds
+}
+
+impl TimeGrid {
+    pub fn next(self) -> Self {
+        match self {
+            TimeGrid::Off => TimeGrid::Epochs,
+            TimeGrid::Epochs => TimeGrid::FiveSeconds,
+            TimeGrid::FiveSeconds => TimeGrid::Seconds,
+            TimeGrid::Seconds => TimeGrid::Off
+        }
+    }
+}
+
+/// Time and amplitude references drawn behind the signals
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GridSettings {
+    #[serde(default)]
+    pub time_grid: TimeGrid,
+
+    // Amplitude in µV above and below the baseline (e.g. ±37.5 µV to judge 75 µV slow waves), only drawn for voltage channels
+    #[serde(default)]
+    pub reference_band: Option<f64>
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            time_grid: TimeGrid::default(),
+            reference_band: REFERENCE_BANDS[1]
+        }
+    }
+}
+
+impl GridSettings {
+    /// Switches to the next amplitude of the reference band, including no band at all
+    pub fn cycle_reference_band(&mut self) {
+        let current = REFERENCE_BANDS.iter().position(|b| *b == self.reference_band).unwrap_or(0);
+        self.reference_band = REFERENCE_BANDS[(current + 1) % REFERENCE_BANDS.len()];
+    }
+}
 
 pub struct Liner {
     signal_index: usize,
     label: String,
+    color: Color,
     range: [String; 2],
     draw_ranges: bool,
+    inverted: bool,
+    focused: bool,
+    grid: GridSettings,
+    microvolt_factor: Option<f64>,
+    dimension: String,
     data_min: (f32, f32),
     data_max: (f32, f32),
     count_before: u8,
     count_after: u8,
     points: Vec<(f32, f32)>,
+    window_start: i64,      // ms
+    view: Option<(i64, f32)>,   // Start and duration in ms of the visible part of the window, the entire window by default
+    selection: Option<TimeSelection>,
+    analysis_period: Option<Range<i64>>,    // ms
+    events: Vec<EventOverlay>,
     cache: canvas::Cache,
 }
 
+/// Measurement of the signal between two points in time, with the values in the physical dimension of the signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub duration: f64,  // s
+    pub min: f64,
+    pub max: f64
+}
+
+impl Measurement {
+    /// Frequency in Hz of a wave with the measured duration as period
+    pub fn frequency(&self) -> f64 {
+        1.0 / self.duration
+    }
+
+    pub fn peak_to_peak(&self) -> f64 {
+        self.max - self.min
+    }
+}
+
+/// A scored event drawn on top of the signal it is bound to
+pub struct EventOverlay {
+    pub index: usize,       // Index within the events of the project
+    pub event_type: EventType,
+    pub start: i64,         // ms
+    pub end: i64,           // ms
+}
+
+#[derive(Default)]
+pub struct LinerState {
+    drag_start: Option<f32>,
+    drag_current: f32,
+    is_shift_pressed: bool,
+}
+
 impl Liner {
     pub fn from_chart_signal(chart_signal: ChartSignal, base_index: usize, draw_ranges: bool, count_before: u8, count_after: u8) -> Self {
         Self {
             signal_index: base_index + chart_signal.index,
-            label: chart_signal.label,
+            label: chart_signal.name,
+            color: chart_signal.color,
             range: chart_signal.range,
             data_min: (0.0, chart_signal.physical_min as f32),
             data_max: (chart_signal.points.len() as f32, chart_signal.physical_max as f32),
@@ -34,21 +141,334 @@ impl Liner {
             count_after,
             points: chart_signal.points,
             draw_ranges,
+            inverted: false,
+            focused: false,
+            grid: GridSettings { time_grid: TimeGrid::Off, reference_band: None },
+            microvolt_factor: microvolt_factor(&chart_signal.dimension),
+            dimension: chart_signal.dimension,
+            window_start: 0,
+            view: None,
+            selection: None,
+            analysis_period: None,
+            events: Vec::new(),
             cache: Cache::new(),
         }
     }
+
+    /// Sets the start of the visible window in milliseconds relative to the first epoch
+    pub fn with_window_start(mut self, window_start: i64) -> Self {
+        self.window_start = window_start;
+        self
+    }
+
+    pub fn with_selection(mut self, selection: Option<TimeSelection>) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets the analysis period in milliseconds relative to the first epoch. Everything outside of it will be shaded
+    pub fn with_analysis_period(mut self, analysis_period: Range<i64>) -> Self {
+        self.analysis_period = Some(analysis_period);
+        self
+    }
+
+    pub fn with_events(mut self, events: Vec<EventOverlay>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Draws the signal with inverted polarity, i.e. negative values above the baseline
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Highlights the signal as the one the scaling shortcuts are applied to
+    pub fn with_focus(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn with_grid(mut self, grid: GridSettings) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Only shows the given part of the window (e.g. when zoomed into the current epoch). The start is in milliseconds
+    /// relative to the first epoch like the start of the window and the duration is in milliseconds.
+    pub fn with_view(mut self, start: i64, duration: i64) -> Self {
+        self.view = Some((start, duration as f32));
+        self
+    }
+
+    fn window_duration(&self) -> f32 {
+        let segment_count = self.count_before as u32 + self.count_after as u32 + 1;
+        (segment_count * EpochReader::EPOCH_DURATION * 1_000) as f32
+    }
+
+    fn visible_span(&self) -> (i64, f32) {
+        self.view.unwrap_or((self.window_start, self.window_duration()))
+    }
+
+    fn x_to_time(&self, x: f32, width: f32) -> i64 {
+        let (start, duration) = self.visible_span();
+        start + (x / width.max(1.0) * duration).round() as i64
+    }
+
+    fn time_to_x(&self, time: i64, width: f32) -> f32 {
+        let (start, duration) = self.visible_span();
+        (time - start) as f32 / duration * width
+    }
+
+    /// Gets the start and end of the current epoch relative to the visible width (may exceed 0 to 1 when zoomed in)
+    fn current_epoch_offsets(&self) -> (f32, f32) {
+        let epoch_millis = EpochReader::EPOCH_DURATION as i64 * 1_000;
+        let start = self.window_start + self.count_before as i64 * epoch_millis;
+        (self.time_to_x(start, 1.0), self.time_to_x(start + epoch_millis, 1.0))
+    }
+
+    /// Projects a physical value to the vertical canvas coordinate
+    fn value_to_y(&self, value: f32, frame_height: f32) -> f32 {
+        let (min_y, max_y) = (self.data_min.1, self.data_max.1);
+        let height = (frame_height - 2.0 * SIGNAL_PADDING_VERTICAL).max(1.0);
+        let relative = (value - min_y) / (max_y - min_y).max(1e-12) * height;
+
+        // Higher values appear at the top, unless the polarity is inverted
+        if self.inverted {
+            relative + SIGNAL_PADDING_VERTICAL
+        } else {
+            height - relative + SIGNAL_PADDING_VERTICAL
+        }
+    }
+
+    /// Draws vertical lines every second, every five seconds and at the epoch boundaries depending on the time grid
+    fn draw_time_grid(&self, frame: &mut Frame) {
+        let interval = match self.grid.time_grid {
+            TimeGrid::Off => return,
+            TimeGrid::Epochs => EpochReader::EPOCH_DURATION as i64 * 1_000,
+            TimeGrid::FiveSeconds => 5_000,
+            TimeGrid::Seconds => 1_000
+        };
+
+        let (start, duration) = self.visible_span();
+        let window_end = start + duration as i64;
+        let mut time = start.div_euclid(interval) * interval;
+        while time <= window_end {
+            let alpha = if time % (EpochReader::EPOCH_DURATION as i64 * 1_000) == 0 {
+                0.9
+            } else if time % 5_000 == 0 {
+                0.45
+            } else {
+                0.2
+            };
+
+            let x = self.time_to_x(time, frame.width());
+            frame.stroke(
+                &Path::line(Point::new(x, 0.0), Point::new(x, frame.height())),
+                canvas::Stroke::default().with_color(Color::from_rgba(0.31, 0.31, 0.35, alpha))
+            );
+            time += interval;
+        }
+    }
+
+    /// Draws the reference band around the baseline, scaled to the current gain of the signal
+    fn draw_reference_band(&self, frame: &mut Frame) {
+        let (Some(amplitude), Some(factor)) = (self.grid.reference_band, self.microvolt_factor) else {
+            return;
+        };
+
+        let amplitude = (amplitude / factor) as f32;
+        let upper = self.value_to_y(amplitude, frame.height()).clamp(0.0, frame.height());
+        let lower = self.value_to_y(-amplitude, frame.height()).clamp(0.0, frame.height());
+        let (top, bottom) = (upper.min(lower), upper.max(lower));
+        if bottom <= top {
+            return;
+        }
+
+        let color = Color::from_rgb(0.31, 0.31, 0.35);
+        frame.fill_rectangle(Point::new(0.0, top), Size::new(frame.width(), bottom - top), color.scale_alpha(0.16));
+        for y in [top, bottom] {
+            frame.stroke(
+                &Path::line(Point::new(0.0, y), Point::new(frame.width(), y)),
+                canvas::Stroke {
+                    line_dash: canvas::LineDash { segments: &[4.0, 4.0], offset: 0 },
+                    ..canvas::Stroke::default().with_color(color.scale_alpha(0.6))
+                }
+            );
+        }
+    }
+
+    fn draw_event(&self, frame: &mut Frame, event: &EventOverlay) {
+        let from = self.time_to_x(event.start, frame.width()).clamp(0.0, frame.width());
+        let to = self.time_to_x(event.end, frame.width()).clamp(0.0, frame.width());
+        if to <= from {
+            return;
+        }
+
+        let color = event.event_type.color();
+        frame.fill_rectangle(Point::new(from, 0.0), Size::new(to - from, frame.height()), color.scale_alpha(0.16));
+        frame.fill_rectangle(Point::new(from, 0.0), Size::new(to - from, 3.0), color);
+
+        let mut label = Text::from(event.event_type.short_label());
+        label.size = 12.0.into();
+        label.font = *REGULAR_BOLD;
+        label.color = color;
+        label.align_y = Vertical::Top;
+        label.position = Point::new(from + 4.0, 6.0);
+        frame.fill_text(label);
+    }
+
+    fn draw_selection(&self, frame: &mut Frame, theme: &Theme, from: f32, to: f32, is_source: bool) {
+        let from = from.clamp(0.0, frame.width());
+        let to = to.clamp(0.0, frame.width());
+        if to <= from {
+            return;
+        }
+
+        let color = theme.extended_palette().primary.base.color;
+        frame.fill_rectangle(Point::new(from, 0.0), Size::new(to - from, frame.height()), color.scale_alpha(if is_source { 0.28 } else { 0.12 }));
+        if is_source {
+            let stroke = canvas::Stroke::default().with_color(color);
+            frame.stroke(&Path::line(Point::new(from, 0.0), Point::new(from, frame.height())), stroke);
+            frame.stroke(&Path::line(Point::new(to, 0.0), Point::new(to, frame.height())), stroke);
+            self.draw_measurement(frame, theme, from, to);
+        }
+    }
+
+    /// Measures the shown (filtered) samples between the given points in time in milliseconds relative to the first epoch
+    fn measure(&self, start: i64, end: i64) -> Option<Measurement> {
+        let index = |time: i64| ((time - self.window_start) as f32 / self.window_duration() * self.data_max.0)
+            .clamp(0.0, self.points.len() as f32) as usize;
+        let (first, last) = (index(start), index(end));
+
+        let values = self.points.get(first..last)?.iter().map(|(_, y)| *y as f64).filter(|y| !y.is_nan());
+        let (min, max) = values.fold(None, |range: Option<(f64, f64)>, y| Some(match range {
+            Some((min, max)) => (min.min(y), max.max(y)),
+            None => (y, y)
+        }))?;
+
+        Some(Measurement {
+            duration: (end - start) as f64 / 1_000.0,
+            min,
+            max
+        })
+    }
+
+    /// Draws the calipers of the selected range, i.e. lines at the minimum and maximum and the measured values
+    fn draw_measurement(&self, frame: &mut Frame, theme: &Theme, from: f32, to: f32) {
+        let Some(measurement) = self.measure(self.x_to_time(from, frame.width()), self.x_to_time(to, frame.width())) else {
+            return;
+        };
+
+        let color = theme.extended_palette().primary.strong.color;
+        for value in [measurement.min, measurement.max] {
+            let y = self.value_to_y(value as f32, frame.height()).clamp(0.0, frame.height());
+            frame.stroke(
+                &Path::line(Point::new(from, y), Point::new(to, y)),
+                canvas::Stroke {
+                    line_dash: canvas::LineDash { segments: &[3.0, 3.0], offset: 0 },
+                    ..canvas::Stroke::default().with_color(color)
+                }
+            );
+        }
+
+        let lines = [
+            format!("{:.2} s  {:.2} Hz", measurement.duration, measurement.frequency()),
+            format!("{:.1} {} p-p", measurement.peak_to_peak(), self.dimension),
+            format!("{:.1} / {:.1} {}", measurement.min, measurement.max, self.dimension),
+        ];
+
+        // Show the values next to the selection, on its left side in case there is no space on the right
+        let is_left = to + 140.0 > frame.width();
+        let mut label = Text::from(lines.join("\n"));
+        label.size = 12.0.into();
+        label.font = *REGULAR_BOLD;
+        label.color = color;
+        label.align_y = Vertical::Top;
+        label.align_x = if is_left { iced::widget::text::Alignment::Right } else { iced::widget::text::Alignment::Left };
+        label.position = Point::new(if is_left { from - 6.0 } else { to + 6.0 }, 6.0);
+        frame.fill_text(label);
+    }
 }
 
-impl<Message> canvas::Program<Message> for Liner {
-    type State = ();
+impl canvas::Program<Message> for Liner {
+    type State = LinerState;
 
-    fn draw(&self, _state: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+    fn update(&self, state: &mut Self::State, event: &Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<Action<Message>> {
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.is_shift_pressed = modifiers.shift();
+                None
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                // Right clicking an event removes it
+                let position = cursor.position_in(bounds)?;
+                let time = self.x_to_time(position.x, bounds.width);
+                let event = self.events.iter().rev().find(|e| (e.start..e.end).contains(&time))?;
+                Some(Action::publish(Message::RemoveEvent(event.index)).and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                state.drag_start = Some(position.x);
+                state.drag_current = position.x;
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                state.drag_start?;
+                let position = cursor.position_from(bounds.position())?;
+                state.drag_current = position.x.clamp(0.0, bounds.width);
+                Some(Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let start = state.drag_start.take()?;
+                let (from, to) = (start.min(state.drag_current), start.max(state.drag_current));
+
+                // A simple click without dragging clears the current selection and focuses the signal
+                if to - from < MIN_SELECTION_WIDTH {
+                    return Some(Action::publish(Message::FocusSignal(self.signal_index)).and_capture());
+                }
+
+                Some(Action::publish(Message::SelectRange(Some(TimeSelection {
+                    signal_index: self.signal_index,
+                    start: self.x_to_time(from, bounds.width),
+                    end: self.x_to_time(to, bounds.width),
+                }))).and_capture())
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                if !cursor.is_over(bounds) {
+                    return None;
+                }
+
+                // Only horizontal scrolling (trackpad or shift + wheel) navigates through the epochs,
+                // as vertical scrolling is required to scroll through the signals
+                let (x, y) = scroll_delta_lines(*delta);
+                let lines = if x.abs() > y.abs() { x } else if state.is_shift_pressed { y } else { 0.0 };
+                if lines == 0.0 {
+                    return None;
+                }
+
+                Some(Action::publish(Message::ScrollAxis(-lines)).and_capture())
+            }
+            _ => None
+        }
+    }
+
+    fn mouse_interaction(&self, state: &Self::State, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        if state.drag_start.is_some() || cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(&self, state: &Self::State, renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
         const RANGE_OFFSET_Y: f32 = 10.0;
 
         // Use cache so geometry is only rebuilt when cache is invalidated.
         let geometry = self.cache.draw(renderer, bounds.size(), |frame: &mut Frame| {
-            let segment_count = self.count_before as u16 + self.count_after as u16 + 1;
-            let segment_percent = 1.0 / segment_count as f32;
+            // Gradient offsets have to be within 0 to 1, which only is the case for the entire current epoch if not zoomed in
+            let (current_start, current_end) = self.current_epoch_offsets();
+            let (gradient_start, gradient_end) = (current_start.clamp(0.0, 1.0), current_end.clamp(0.0, 1.0));
             frame.fill_rectangle(Point::ORIGIN, frame.size(), canvas::Gradient::Linear(Linear {
                 start: Point::ORIGIN,
                 end: Point::new(bounds.width, 0.0),
@@ -58,11 +478,11 @@ impl<Message> canvas::Program<Message> for Liner {
                         color: Color::TRANSPARENT
                     }),
                     Some(ColorStop {
-                        offset: segment_percent * self.count_before as f32,
+                        offset: gradient_start,
                         color: Color::TRANSPARENT
                     }),
                     Some(ColorStop {
-                        offset: segment_percent * self.count_before as f32,
+                        offset: gradient_start,
                         color: Color {
                             r: 0.21,
                             g: 0.21,
@@ -71,7 +491,7 @@ impl<Message> canvas::Program<Message> for Liner {
                         }
                     }),
                     Some(ColorStop {
-                        offset: segment_percent * (self.count_before as f32 + 1.0),
+                        offset: gradient_end,
                         color: Color {
                             r: 0.21,
                             g: 0.21,
@@ -80,7 +500,7 @@ impl<Message> canvas::Program<Message> for Liner {
                         }
                     }),
                     Some(ColorStop {
-                        offset: segment_percent * (self.count_before as f32 + 1.0),
+                        offset: gradient_end,
                         color: Color::TRANSPARENT
                     }),
                     Some(ColorStop {
@@ -93,11 +513,11 @@ impl<Message> canvas::Program<Message> for Liner {
 
             frame.stroke(&Path::line(
                 Point {
-                    x: frame.width() * segment_percent * self.count_before as f32,
+                    x: frame.width() * current_start,
                     y: 0.0
                 },
                 Point {
-                    x: frame.width() * segment_percent * self.count_before as f32,
+                    x: frame.width() * current_start,
                     y: frame.height()
                 }),
                 canvas::Stroke::default().with_color(Color {
@@ -109,11 +529,11 @@ impl<Message> canvas::Program<Message> for Liner {
             );
             frame.stroke(&Path::line(
                 Point {
-                    x: frame.width() * segment_percent * (self.count_before as f32 + 1.0),
+                    x: frame.width() * current_end,
                     y: 0.0
                 },
                 Point {
-                    x: frame.width() * segment_percent * (self.count_before as f32 + 1.0),
+                    x: frame.width() * current_end,
                     y: frame.height()
                 }),
                 canvas::Stroke::default().with_color(Color {
@@ -131,10 +551,21 @@ impl<Message> canvas::Program<Message> for Liner {
                 frame.fill_rectangle(Point::ORIGIN, frame.size(), highlight);
             }
 
-            // Draw min / max values if desired
+            // Draw the time and amplitude references behind the signal
+            self.draw_time_grid(frame);
+            self.draw_reference_band(frame);
+
+            // Mark the focused signal
+            if self.focused {
+                frame.fill_rectangle(Point::ORIGIN, Size::new(3.0, frame.height()), theme.extended_palette().primary.base.color);
+            }
+
+            // Draw min / max values if desired (swapped in case the polarity is inverted)
             if self.draw_ranges {
+                let (lower, upper) = if self.inverted { (1, 0) } else { (0, 1) };
+
                 // Draw max value
-                let mut max_text = Text::from(self.range[1].clone());
+                let mut max_text = Text::from(self.range[upper].clone());
                 max_text.size = 14.0.into();
                 max_text.color = Color::from_rgb8(100, 100, 100);
                 max_text.align_y = Vertical::Top;
@@ -143,7 +574,7 @@ impl<Message> canvas::Program<Message> for Liner {
                 frame.fill_text(max_text);
 
                 // Draw min value
-                let mut min_text = Text::from(self.range[0].clone());
+                let mut min_text = Text::from(self.range[lower].clone());
                 min_text.size = 14.0.into();
                 min_text.color = Color::from_rgb8(100, 100, 100);
                 min_text.align_y = Vertical::Bottom;
@@ -163,16 +594,18 @@ impl<Message> canvas::Program<Message> for Liner {
             frame.fill_text(label_text.clone());
 
             // Draw actual line
-            let (min_x, min_y) = self.data_min;
-            let (max_x, max_y) = self.data_max;
+            let min_x = self.data_min.0;
+            let max_x = self.data_max.0;
             let width = frame.width().max(1.0);
-            let height = (frame.height() - 2.0 * SIGNAL_PADDING_VERTICAL).max(1.0);
             let data_w = (max_x - min_x).max(1e-12);
-            let data_h = (max_y - min_y).max(1e-12);
+            let (view_start, view_duration) = self.visible_span();
+            let view_offset = ((view_start - self.window_start) as f32 / view_duration) * width;
+            let view_scale = self.window_duration() / view_duration;
 
             let mut builder = canvas::path::Builder::new();
             let mut first = true;
             let mut last_pixel: Option<(i32, i32)> = None;
+            let mut previous: Option<Point> = None;
 
             for &(x, y) in &self.points {
                 if y.is_nan() {
@@ -180,9 +613,21 @@ impl<Message> canvas::Program<Message> for Liner {
                 }
 
                 // Project to canvas coordinate (sx, sy)
-                let sx = ((x - min_x) / data_w) * width;
-                // flip Y so higher data y appears at top; adjust to your preference
-                let sy = height - ((y - min_y) / data_h) * height + SIGNAL_PADDING_VERTICAL;
+                let sx = ((x - min_x) / data_w) * width * view_scale - view_offset;
+                let sy = self.value_to_y(y, frame.height());
+
+                // Skip the samples before the visible part (e.g. when zoomed in), but start the line at the last of them
+                if sx < 0.0 {
+                    previous = Some(Point::new(sx, sy.clamp(0.0, frame.height())));
+                    continue;
+                }
+                if first && let Some(previous) = previous.take() {
+                    builder.move_to(previous);
+                    first = false;
+                }
+
+                // Keep values outside of the displayed range within the signal
+                let sy = sy.clamp(0.0, frame.height());
 
                 // Pixel coordinates (rounded)
                 let px = (sx.round() as i32, sy.round() as i32);
@@ -199,6 +644,11 @@ impl<Message> canvas::Program<Message> for Liner {
                 } else {
                     builder.line_to(Point::new(sx as f32, sy as f32));
                 }
+
+                // The line ends with the first sample after the visible part
+                if sx > width {
+                    break;
+                }
             }
 
             // Build and stroke the path if there are segments
@@ -221,7 +671,7 @@ impl<Message> canvas::Program<Message> for Liner {
                                     }
                                 }),
                                 Some(ColorStop {
-                                    offset: segment_percent * self.count_before as f32,
+                                    offset: gradient_start,
                                     color: Color {
                                         r: 0.36,
                                         g: 0.36,
@@ -230,25 +680,20 @@ impl<Message> canvas::Program<Message> for Liner {
                                     }
                                 }),
                                 Some(ColorStop {
-                                    offset: segment_percent * self.count_before as f32,
+                                    offset: gradient_start,
                                     color: Color {
-                                        r: 0.26,
-                                        g: 0.36,
-                                        b: 0.76,
+                                        r: self.color.r * 0.9,
+                                        g: self.color.g * 0.9,
+                                        b: self.color.b * 0.9,
                                         a: 1.0
                                     }
                                 }),
                                 Some(ColorStop {
-                                    offset: segment_percent * (self.count_before as f32 + 1.0),
-                                    color: Color {
-                                        r: 0.26,
-                                        g: 0.46,
-                                        b: 0.86,
-                                        a: 1.0
-                                    }
+                                    offset: gradient_end,
+                                    color: self.color
                                 }),
                                 Some(ColorStop {
-                                    offset: segment_percent * (self.count_before as f32 + 1.0),
+                                    offset: gradient_end,
                                     color: Color {
                                         r: 0.36,
                                         g: 0.36,
@@ -285,6 +730,31 @@ impl<Message> canvas::Program<Message> for Liner {
             // );
         });
 
-        vec![geometry]
+        // Shade the parts of the signal outside of the analysis period
+        let mut overlay = Frame::new(renderer, bounds.size());
+        if let Some(period) = &self.analysis_period {
+            let shade = theme.extended_palette().background.weakest.color.scale_alpha(0.72);
+            let start = self.time_to_x(period.start, bounds.width).clamp(0.0, bounds.width);
+            let end = self.time_to_x(period.end, bounds.width).clamp(0.0, bounds.width);
+            overlay.fill_rectangle(Point::ORIGIN, Size::new(start, bounds.height), shade);
+            overlay.fill_rectangle(Point::new(end, 0.0), Size::new(bounds.width - end, bounds.height), shade);
+        }
+
+        // Draw the scored events bound to this signal
+        for event in &self.events {
+            self.draw_event(&mut overlay, event);
+        }
+
+        // Draw the current selection or the selection currently being dragged on top of the signal
+        if let Some(start) = state.drag_start {
+            self.draw_selection(&mut overlay, theme, start.min(state.drag_current), start.max(state.drag_current), true);
+        }
+        else if let Some(selection) = &self.selection {
+            let from = self.time_to_x(selection.start, bounds.width);
+            let to = self.time_to_x(selection.end, bounds.width);
+            self.draw_selection(&mut overlay, theme, from, to, selection.signal_index == self.signal_index);
+        }
+
+        vec![geometry, overlay.into_geometry()]
     }
 }
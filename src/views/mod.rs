@@ -1,3 +1,4 @@
 pub mod line_chart;
+pub mod hypnogram;
 pub mod spectrogram;
 pub mod collapsible;